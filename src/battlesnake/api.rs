@@ -0,0 +1,278 @@
+use serde::{Deserialize, Serialize};
+
+use super::{Battle, Battlesnake, Ruleset};
+use crate::snake::SnakeDirection;
+
+/// Version of the Battlesnake API spoken by this module.
+pub const API_VERSION: &str = "1";
+
+/// A cell of the board, `(0, 0)` is the bottom left corner and up increases `y`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct Coord {
+    pub x: i16,
+    pub y: i16,
+}
+
+impl From<(i16, i16)> for Coord {
+    fn from((x, y): (i16, i16)) -> Self {
+        Coord { x, y }
+    }
+}
+
+/// Answer to `GET /`, how the bot looks.
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BotInfo {
+    pub apiversion: String,
+    pub author: String,
+    pub color: String,
+    pub head: String,
+    pub tail: String,
+    pub version: String,
+}
+
+impl Default for BotInfo {
+    fn default() -> Self {
+        BotInfo {
+            apiversion: API_VERSION.to_string(),
+            author: String::new(),
+            color: "#888888".to_string(),
+            head: "default".to_string(),
+            tail: "default".to_string(),
+            version: String::new(),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct RulesetSettings {
+    pub food_spawn_chance: u8,
+    pub minimum_food: usize,
+    pub hazard_damage_per_turn: u8,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RulesetInfo {
+    pub name: String,
+    pub version: String,
+    pub settings: RulesetSettings,
+}
+
+/// The game every request belongs to.
+#[derive(Debug, PartialEq, Eq, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GameInfo {
+    pub id: String,
+    pub ruleset: RulesetInfo,
+    pub map: String,
+    /// Milliseconds a bot has to answer.
+    pub timeout: u32,
+    pub source: String,
+}
+
+impl GameInfo {
+    /// A local game following `rules`.
+    pub fn standard(id: &str, rules: &Ruleset, timeout: u32) -> Self {
+        GameInfo {
+            id: id.to_string(),
+            ruleset: RulesetInfo {
+                name: "standard".to_string(),
+                version: env!("CARGO_PKG_VERSION").to_string(),
+                settings: RulesetSettings {
+                    food_spawn_chance: rules.food_spawn_chance,
+                    minimum_food: rules.minimum_food,
+                    hazard_damage_per_turn: rules.hazard_damage,
+                },
+            },
+            map: "standard".to_string(),
+            timeout,
+            source: "custom".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Customizations {
+    pub color: String,
+    pub head: String,
+    pub tail: String,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SnakeInfo {
+    pub id: String,
+    pub name: String,
+    pub health: u8,
+    /// From head to tail.
+    pub body: Vec<Coord>,
+    pub latency: String,
+    pub head: Coord,
+    pub length: usize,
+    pub shout: String,
+    pub squad: String,
+    pub customizations: Customizations,
+}
+
+impl From<&Battlesnake> for SnakeInfo {
+    fn from(battlesnake: &Battlesnake) -> Self {
+        let body: Vec<Coord> = battlesnake
+            .snake
+            .body
+            .iter()
+            .map(|point| Coord {
+                x: point.x,
+                y: point.y,
+            })
+            .collect();
+        SnakeInfo {
+            id: battlesnake.id.clone(),
+            name: battlesnake.name.clone(),
+            health: battlesnake.health,
+            head: body[0],
+            length: body.len(),
+            body,
+            latency: "0".to_string(),
+            shout: battlesnake.shout.clone(),
+            squad: String::new(),
+            customizations: Customizations::default(),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Board {
+    pub height: i16,
+    pub width: i16,
+    pub food: Vec<Coord>,
+    pub hazards: Vec<Coord>,
+    /// Only the snakes still in the game.
+    pub snakes: Vec<SnakeInfo>,
+}
+
+/// Body of the `/start`, `/move` and `/end` requests.
+#[derive(Debug, PartialEq, Eq, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GameRequest {
+    pub game: GameInfo,
+    pub turn: u32,
+    pub board: Board,
+    /// The snake the request is sent to.
+    pub you: SnakeInfo,
+}
+
+/// Answer to `/move`.
+#[derive(Debug, PartialEq, Eq, Clone, Default, Serialize, Deserialize)]
+pub struct MoveResponse {
+    /// `up`, `down`, `left` or `right`.
+    #[serde(rename = "move")]
+    pub direction: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub shout: String,
+}
+
+impl MoveResponse {
+    pub fn new(direction: SnakeDirection) -> Self {
+        MoveResponse {
+            direction: direction_name(direction).to_string(),
+            shout: String::new(),
+        }
+    }
+    /// The [`SnakeDirection`] of the move, `None` if it is not a valid one.
+    pub fn to_direction(&self) -> Option<SnakeDirection> {
+        parse_direction(&self.direction)
+    }
+}
+
+/// Name of a direction in the API, diagonals and layers don't exist in Battlesnake and are named after
+/// the closest orthogonal move.
+pub fn direction_name(direction: SnakeDirection) -> &'static str {
+    match direction {
+        SnakeDirection::Up | SnakeDirection::UpLeft | SnakeDirection::Ascend => "up",
+        SnakeDirection::Down | SnakeDirection::DownRight | SnakeDirection::Descend => "down",
+        SnakeDirection::Left | SnakeDirection::DownLeft => "left",
+        SnakeDirection::Right | SnakeDirection::UpRight => "right",
+    }
+}
+
+pub fn parse_direction(name: &str) -> Option<SnakeDirection> {
+    match name {
+        "up" => Some(SnakeDirection::Up),
+        "down" => Some(SnakeDirection::Down),
+        "left" => Some(SnakeDirection::Left),
+        "right" => Some(SnakeDirection::Right),
+        _ => None,
+    }
+}
+
+impl Battle {
+    /// The request sent to the snake at `you` in [`Battle::snakes`].
+    /// # Examples
+    /// ```
+    /// use snake3::battlesnake::{Battle, GameInfo};
+    /// let battle = Battle::new(11, 11, &[("a", "Alice")]);
+    /// let game = GameInfo::standard("local", &battle.rules, 500);
+    /// let request = battle.request(&game, 0);
+    /// assert_eq!(request.you.length, 3);
+    /// assert!(serde_json::to_string(&request).unwrap().contains("\"minimumFood\":1"));
+    /// ```
+    pub fn request(&self, game: &GameInfo, you: usize) -> GameRequest {
+        let mut hazards: Vec<Coord> = self.hazards.iter().map(|cell| (*cell).into()).collect();
+        hazards.sort_by_key(|coord| (coord.x, coord.y));
+        GameRequest {
+            game: game.clone(),
+            turn: self.turn,
+            board: Board {
+                height: self.height,
+                width: self.width,
+                food: self.food.iter().map(|cell| (*cell).into()).collect(),
+                hazards,
+                snakes: self.alive().map(SnakeInfo::from).collect(),
+            },
+            you: (&self.snakes[you]).into(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn api_parses_battlesnake_json() {
+        let json = r##"{
+            "game": {"id": "g", "ruleset": {"name": "standard", "version": "v1.2.3",
+                "settings": {"foodSpawnChance": 25, "minimumFood": 1, "hazardDamagePerTurn": 14}},
+                "map": "standard", "timeout": 500, "source": "league"},
+            "turn": 14,
+            "board": {"height": 11, "width": 11, "food": [{"x": 5, "y": 5}], "hazards": [],
+                "snakes": [{"id": "s", "name": "S", "health": 54,
+                    "body": [{"x": 0, "y": 0}, {"x": 1, "y": 0}, {"x": 2, "y": 0}],
+                    "latency": "111", "head": {"x": 0, "y": 0}, "length": 3,
+                    "shout": "why are we shouting??", "squad": "",
+                    "customizations": {"color": "#FF0000", "head": "pixel", "tail": "pixel"}}]},
+            "you": {"id": "s", "name": "S", "health": 54, "body": [{"x": 0, "y": 0}],
+                "head": {"x": 0, "y": 0}, "length": 1}
+        }"##;
+        let request: GameRequest = serde_json::from_str(json).unwrap();
+        assert_eq!(request.game.ruleset.settings.food_spawn_chance, 25);
+        assert_eq!(request.board.snakes[0].body[2], Coord { x: 2, y: 0 });
+        assert_eq!(request.you.health, 54);
+    }
+
+    #[test]
+    fn api_move_response() {
+        let response = MoveResponse::new(SnakeDirection::Left);
+        assert_eq!(
+            serde_json::to_string(&response).unwrap(),
+            r#"{"move":"left"}"#
+        );
+        let response: MoveResponse = serde_json::from_str(r#"{"move":"up","shout":"hi"}"#).unwrap();
+        assert_eq!(response.to_direction(), Some(SnakeDirection::Up));
+        assert_eq!(parse_direction("north"), None);
+    }
+}
@@ -0,0 +1,187 @@
+use std::collections::{HashMap, HashSet};
+
+use super::{Battle, BotInfo, Coord, GameInfo, GameRequest, MoveResponse};
+use crate::snake::SnakeDirection;
+
+/// A Battlesnake player, either running in this process or behind the JSON API with
+/// [`RemoteBot`](`super::RemoteBot`).
+pub trait Bot {
+    /// Answer to `GET /`.
+    fn info(&self) -> BotInfo {
+        BotInfo::default()
+    }
+    /// Called once when the game starts.
+    fn start(&mut self, _request: &GameRequest) {}
+    /// Called every turn to pick the next move.
+    fn choose_move(&mut self, request: &GameRequest) -> MoveResponse;
+    /// Called once when the game is over, or when the snake of the bot got eliminated.
+    fn end(&mut self, _request: &GameRequest) {}
+}
+
+/// Bot that moves to the closest food while avoiding walls and bodies.
+#[derive(Debug, Default)]
+pub struct SimpleBot;
+
+impl Bot for SimpleBot {
+    fn info(&self) -> BotInfo {
+        BotInfo {
+            author: "snake3".to_string(),
+            color: "#00aa44".to_string(),
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            ..BotInfo::default()
+        }
+    }
+
+    fn choose_move(&mut self, request: &GameRequest) -> MoveResponse {
+        let board = &request.board;
+        let head = request.you.head;
+        // Tails move away this turn unless the snake just ate, which stacks the tail
+        let taken: HashSet<(i16, i16)> = board
+            .snakes
+            .iter()
+            .flat_map(|snake| {
+                let stacked = snake.body.len() > 1
+                    && snake.body[snake.body.len() - 1] == snake.body[snake.body.len() - 2];
+                let end = if stacked {
+                    snake.body.len()
+                } else {
+                    snake.body.len().saturating_sub(1)
+                };
+                snake.body[..end].iter().map(|coord| (coord.x, coord.y))
+            })
+            .collect();
+        let safe: Vec<(SnakeDirection, Coord)> = [
+            SnakeDirection::Up,
+            SnakeDirection::Down,
+            SnakeDirection::Left,
+            SnakeDirection::Right,
+        ]
+        .into_iter()
+        .map(|direction| {
+            let (x, y) = direction.offset();
+            (
+                direction,
+                Coord {
+                    x: head.x + x,
+                    y: head.y + y,
+                },
+            )
+        })
+        .filter(|(_, next)| {
+            next.x >= 0
+                && next.y >= 0
+                && next.x < board.width
+                && next.y < board.height
+                && !taken.contains(&(next.x, next.y))
+        })
+        .collect();
+        let distance = |coord: &Coord| {
+            board
+                .food
+                .iter()
+                .map(|food| (food.x - coord.x).abs() + (food.y - coord.y).abs())
+                .min()
+                .unwrap_or(0)
+        };
+        let direction = safe
+            .iter()
+            .min_by_key(|(_, next)| distance(next))
+            .map_or(SnakeDirection::Up, |(direction, _)| *direction);
+        MoveResponse::new(direction)
+    }
+}
+
+/// Plays `battle` until it [is over](`Battle::is_over`) or `max_turns` are played, `bots` are in the same
+/// order as [`Battle::snakes`]. <br>
+/// A bot that does not answer with a valid move keeps going in its last direction.
+/// # Examples
+/// ```
+/// use snake3::battlesnake::{Battle, Bot, GameInfo, SimpleBot, play};
+/// let mut battle = Battle::new(7, 7, &[("a", "A"), ("b", "B")]);
+/// let game = GameInfo::standard("local", &battle.rules, 500);
+/// let mut bots: Vec<Box<dyn Bot>> = vec![Box::new(SimpleBot), Box::new(SimpleBot)];
+/// play(&mut battle, &game, &mut bots, 50);
+/// assert!(battle.turn <= 50);
+/// ```
+pub fn play(battle: &mut Battle, game: &GameInfo, bots: &mut [Box<dyn Bot>], max_turns: u32) {
+    for (index, bot) in bots.iter_mut().enumerate() {
+        bot.start(&battle.request(game, index));
+    }
+    let mut ended = vec![false; bots.len()];
+    while !battle.is_over() && battle.turn < max_turns {
+        let mut moves = HashMap::new();
+        for (index, bot) in bots.iter_mut().enumerate() {
+            if !battle.snakes[index].is_alive() {
+                continue;
+            }
+            let response = bot.choose_move(&battle.request(game, index));
+            if let Some(direction) = response.to_direction() {
+                moves.insert(battle.snakes[index].id.clone(), direction);
+            }
+            battle.snakes[index].shout = response.shout;
+        }
+        battle.step(&moves);
+        for (index, bot) in bots.iter_mut().enumerate() {
+            if !ended[index] && !battle.snakes[index].is_alive() {
+                bot.end(&battle.request(game, index));
+                ended[index] = true;
+            }
+        }
+    }
+    for (index, bot) in bots.iter_mut().enumerate() {
+        if !ended[index] {
+            bot.end(&battle.request(game, index));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use super::*;
+    use crate::battlesnake::Battlesnake;
+
+    #[test]
+    fn simple_bot_avoids_walls_and_goes_for_food() {
+        let mut battle = Battle::new(7, 7, &[("a", "A")]);
+        battle.food = vec![(4, 1)];
+        let game = GameInfo::default();
+        let mut bot = SimpleBot;
+        let response = bot.choose_move(&battle.request(&game, 0));
+        assert_eq!(response.to_direction(), Some(SnakeDirection::Right));
+
+        battle.snakes[0] = Battlesnake::new("a", "A", (0, 0));
+        battle.food = vec![(0, 0)];
+        let response = bot.choose_move(&battle.request(&game, 0));
+        assert!(matches!(
+            response.to_direction(),
+            Some(SnakeDirection::Up | SnakeDirection::Right)
+        ));
+    }
+
+    #[test]
+    fn play_calls_every_bot() {
+        struct Recorder(Rc<RefCell<Vec<&'static str>>>);
+        impl Bot for Recorder {
+            fn start(&mut self, _request: &GameRequest) {
+                self.0.borrow_mut().push("start");
+            }
+            fn choose_move(&mut self, _request: &GameRequest) -> MoveResponse {
+                self.0.borrow_mut().push("move");
+                MoveResponse::new(SnakeDirection::Left)
+            }
+            fn end(&mut self, _request: &GameRequest) {
+                self.0.borrow_mut().push("end");
+            }
+        }
+        let calls = Rc::new(RefCell::new(Vec::new()));
+        let mut battle = Battle::new(7, 7, &[("a", "A")]);
+        let game = GameInfo::default();
+        let mut bots: Vec<Box<dyn Bot>> = vec![Box::new(Recorder(calls.clone()))];
+        play(&mut battle, &game, &mut bots, 100);
+        // Two moves to the left and the snake hits the wall
+        assert_eq!(battle.turn, 2);
+        assert_eq!(*calls.borrow(), vec!["start", "move", "move", "end"]);
+    }
+}
@@ -0,0 +1,260 @@
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream, ToSocketAddrs};
+use std::time::Duration;
+
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+
+use super::{Bot, BotInfo, GameRequest, MoveResponse};
+
+/// Biggest request body [`serve`] reads, game states of large boards stay far below it.
+const MAX_BODY_SIZE: usize = 256 * 1024;
+/// Biggest request line and headers [`serve`] reads.
+const MAX_HEADERS_SIZE: usize = 16 * 1024;
+/// Time [`serve`] waits on a client before dropping it, so an idle connection can't hold the server.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Serves `bot` over the Battlesnake JSON API, one connection at a time. <br>
+/// Answers `GET /` with [`Bot::info`] and `POST` to `/start`, `/move` and `/end`,
+/// stops after `max_requests` requests or never if it is `None`. <br>
+/// Clients taking longer than a couple of seconds or sending bodies over 256 KB are dropped.
+/// # Examples
+/// ```no_run
+/// use std::net::TcpListener;
+/// use snake3::battlesnake::{SimpleBot, serve};
+/// let listener = TcpListener::bind("127.0.0.1:8000").unwrap();
+/// serve(&listener, &mut SimpleBot, None).unwrap();
+/// ```
+pub fn serve<B: Bot + ?Sized>(
+    listener: &TcpListener,
+    bot: &mut B,
+    max_requests: Option<usize>,
+) -> io::Result<()> {
+    for (served, stream) in listener.incoming().enumerate() {
+        // A client going away should not stop the server
+        let _ = handle(stream?, bot);
+        if max_requests.is_some_and(|max| served + 1 >= max) {
+            break;
+        }
+    }
+    Ok(())
+}
+
+fn handle<B: Bot + ?Sized>(mut stream: TcpStream, bot: &mut B) -> io::Result<()> {
+    stream.set_read_timeout(Some(REQUEST_TIMEOUT))?;
+    stream.set_write_timeout(Some(REQUEST_TIMEOUT))?;
+    let (method, path, body) = read_request(&mut stream)?;
+    let path = path.trim_end_matches('/');
+    let request = || serde_json::from_slice::<GameRequest>(&body).map_err(invalid_data);
+    let response = match (method.as_str(), path.rsplit('/').next().unwrap_or("")) {
+        ("GET", "") => to_json(&bot.info())?,
+        ("POST", "start") => {
+            bot.start(&request()?);
+            "{}".to_string()
+        }
+        ("POST", "move") => to_json(&bot.choose_move(&request()?))?,
+        ("POST", "end") => {
+            bot.end(&request()?);
+            "{}".to_string()
+        }
+        _ => return write_response(&mut stream, "404 Not Found", "{}"),
+    };
+    write_response(&mut stream, "200 OK", &response)
+}
+
+/// Reads an HTTP request and returns its method, path and body.
+fn read_request(stream: &mut TcpStream) -> io::Result<(String, String, Vec<u8>)> {
+    let mut reader = BufReader::new(stream.take((MAX_HEADERS_SIZE + MAX_BODY_SIZE) as u64));
+    let mut line = String::new();
+    reader.read_line(&mut line)?;
+    let mut parts = line.split_whitespace();
+    let method = parts.next().unwrap_or("").to_string();
+    let path = parts.next().unwrap_or("/").to_string();
+    let length = read_headers(&mut reader)?;
+    if length > MAX_BODY_SIZE {
+        return Err(invalid_data(format!("body of {length} bytes is too big")));
+    }
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body)?;
+    Ok((method, path, body))
+}
+
+/// Skips the headers and returns the `Content-Length`, `0` if there is none.
+fn read_headers<R: BufRead>(reader: &mut R) -> io::Result<usize> {
+    let mut length = 0;
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header)? == 0 || header.trim().is_empty() {
+            return Ok(length);
+        }
+        if let Some((name, value)) = header.split_once(':')
+            && name.trim().eq_ignore_ascii_case("content-length")
+        {
+            length = value.trim().parse().map_err(invalid_data)?;
+        }
+    }
+}
+
+fn write_response(stream: &mut TcpStream, status: &str, body: &str) -> io::Result<()> {
+    write!(
+        stream,
+        "HTTP/1.1 {status}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    )?;
+    stream.flush()
+}
+
+fn to_json<T: Serialize>(value: &T) -> io::Result<String> {
+    serde_json::to_string(value).map_err(invalid_data)
+}
+
+fn invalid_data<E: std::fmt::Display>(error: E) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, error.to_string())
+}
+
+/// A bot behind the Battlesnake JSON API, like one running with [`serve`] or any other Battlesnake server. <br>
+/// Requests that fail or take longer than the timeout are ignored, so the snake keeps going in its last direction.
+pub struct RemoteBot {
+    address: String,
+    path: String,
+    timeout: Duration,
+}
+
+impl RemoteBot {
+    /// `url` is like `http://127.0.0.1:8000` or `localhost:8000/my-bot`, only plain HTTP is supported.
+    pub fn new(url: &str, timeout: Duration) -> Self {
+        let url = url.trim_start_matches("http://");
+        let (address, path) = match url.split_once('/') {
+            Some((address, path)) => (address, format!("/{}", path.trim_end_matches('/'))),
+            None => (url, String::new()),
+        };
+        let address = if address.contains(':') {
+            address.to_string()
+        } else {
+            format!("{address}:80")
+        };
+        RemoteBot {
+            address,
+            path,
+            timeout,
+        }
+    }
+    fn send<T: DeserializeOwned>(
+        &self,
+        method: &str,
+        endpoint: &str,
+        body: Option<&GameRequest>,
+    ) -> io::Result<T> {
+        let address = self
+            .address
+            .to_socket_addrs()?
+            .next()
+            .ok_or_else(|| invalid_data("no address"))?;
+        let mut stream = TcpStream::connect_timeout(&address, self.timeout)?;
+        stream.set_read_timeout(Some(self.timeout))?;
+        stream.set_write_timeout(Some(self.timeout))?;
+        let body = match body {
+            Some(request) => to_json(request)?,
+            None => String::new(),
+        };
+        write!(
+            stream,
+            "{method} {}{endpoint} HTTP/1.1\r\nHost: {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+            self.path,
+            self.address,
+            body.len()
+        )?;
+        stream.flush()?;
+        let mut reader = BufReader::new(stream);
+        let mut status = String::new();
+        reader.read_line(&mut status)?;
+        if status.split_whitespace().nth(1) != Some("200") {
+            return Err(invalid_data(format!("unexpected answer {}", status.trim())));
+        }
+        read_headers(&mut reader)?;
+        let mut response = Vec::new();
+        reader.read_to_end(&mut response)?;
+        serde_json::from_slice(&response).map_err(invalid_data)
+    }
+}
+
+impl Bot for RemoteBot {
+    fn info(&self) -> BotInfo {
+        self.send("GET", "/", None).unwrap_or_default()
+    }
+
+    fn start(&mut self, request: &GameRequest) {
+        let _: io::Result<serde_json::Value> = self.send("POST", "/start", Some(request));
+    }
+
+    fn choose_move(&mut self, request: &GameRequest) -> MoveResponse {
+        self.send("POST", "/move", Some(request))
+            .unwrap_or_default()
+    }
+
+    fn end(&mut self, request: &GameRequest) {
+        let _: io::Result<serde_json::Value> = self.send("POST", "/end", Some(request));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+
+    use super::*;
+    use crate::battlesnake::{Battle, GameInfo, SimpleBot};
+    use crate::snake::SnakeDirection;
+
+    #[test]
+    fn remote_bot_talks_to_served_bot() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        let server = thread::spawn(move || serve(&listener, &mut SimpleBot, Some(4)));
+
+        let mut remote = RemoteBot::new(&format!("http://{address}/"), Duration::from_secs(5));
+        let mut battle = Battle::new(7, 7, &[("a", "A")]);
+        battle.food = vec![(1, 4)];
+        let request = battle.request(&GameInfo::default(), 0);
+        assert_eq!(remote.info().author, "snake3");
+        remote.start(&request);
+        assert_eq!(
+            remote.choose_move(&request).to_direction(),
+            Some(SnakeDirection::Up)
+        );
+        remote.end(&request);
+        server.join().unwrap().unwrap();
+    }
+
+    #[test]
+    fn serve_drops_huge_and_idle_requests() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        let server = thread::spawn(move || serve(&listener, &mut SimpleBot, Some(3)));
+
+        let mut huge = TcpStream::connect(address).unwrap();
+        write!(
+            huge,
+            "POST /move HTTP/1.1\r\nContent-Length: 99999999999\r\n\r\n"
+        )
+        .unwrap();
+        let mut answer = Vec::new();
+        let _ = huge.read_to_end(&mut answer);
+        assert!(answer.is_empty());
+        // Says nothing, the server moves on once it times out
+        let _idle = TcpStream::connect(address).unwrap();
+        let remote = RemoteBot::new(&address.to_string(), Duration::from_secs(10));
+        assert_eq!(remote.info().author, "snake3");
+        server.join().unwrap().unwrap();
+    }
+
+    #[test]
+    fn remote_bot_without_server() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        drop(listener);
+        let mut remote = RemoteBot::new(&address.to_string(), Duration::from_millis(200));
+        let battle = Battle::new(7, 7, &[("a", "A")]);
+        let response = remote.choose_move(&battle.request(&GameInfo::default(), 0));
+        assert_eq!(response.to_direction(), None);
+    }
+}
@@ -0,0 +1,25 @@
+//! Battlesnake on top of the snake3 building blocks: the standard rules for many snakes sharing a board
+//! and the JSON API, so bots can play local games or be served to the official engine.
+//!
+//! ```
+//! use snake3::battlesnake::{Battle, Bot, GameInfo, SimpleBot, play};
+//! let mut battle = Battle::new(11, 11, &[("a", "Alice"), ("b", "Bob")]);
+//! let game = GameInfo::standard("local", &battle.rules, 500);
+//! let mut bots: Vec<Box<dyn Bot>> = vec![Box::new(SimpleBot), Box::new(SimpleBot)];
+//! play(&mut battle, &game, &mut bots, 500);
+//! if let Some(winner) = battle.winner() {
+//!     println!("{} won after {} turns", winner.name, battle.turn);
+//! }
+//! ```
+pub mod api;
+pub mod bot;
+pub mod http;
+pub mod rules;
+
+pub use api::{
+    API_VERSION, Board, BotInfo, Coord, Customizations, GameInfo, GameRequest, MoveResponse,
+    RulesetInfo, RulesetSettings, SnakeInfo, direction_name, parse_direction,
+};
+pub use bot::{Bot, SimpleBot, play};
+pub use http::{RemoteBot, serve};
+pub use rules::{Battle, Battlesnake, Elimination, MAX_HEALTH, Ruleset, START_LENGTH};
@@ -0,0 +1,404 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::snake::{Snake, SnakeBodyPoint, SnakeDirection, random_index, random_range};
+
+/// Health of a snake at the start of the game and after eating.
+pub const MAX_HEALTH: u8 = 100;
+/// Body points of a snake at the start of the game, all stacked on the starting cell.
+pub const START_LENGTH: usize = 3;
+
+/// Settings of the standard rules.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Ruleset {
+    /// Chance in percent of a new food being placed every turn.
+    pub food_spawn_chance: u8,
+    /// Food is added at the end of every turn until there is at least this much on the board.
+    pub minimum_food: usize,
+    /// Extra health lost every turn the head spends on a hazard.
+    pub hazard_damage: u8,
+}
+
+impl Default for Ruleset {
+    fn default() -> Self {
+        Ruleset {
+            food_spawn_chance: 15,
+            minimum_food: 1,
+            hazard_damage: 14,
+        }
+    }
+}
+
+/// Why a snake was taken out of the [`Battle`].
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Elimination {
+    OutOfHealth,
+    /// The head left the board.
+    Wall,
+    SelfCollision,
+    /// The head hit the body of the snake with this id.
+    Collision(String),
+    /// The head met the head of the snake with this id, and it was not shorter.
+    HeadToHead(String),
+}
+
+/// A player of a [`Battle`], a [`Snake`] with health.
+pub struct Battlesnake {
+    pub id: String,
+    pub name: String,
+    pub snake: Snake,
+    pub health: u8,
+    /// Last thing the bot said, sent back to every bot on the next turn.
+    pub shout: String,
+    pub eliminated: Option<Elimination>,
+}
+
+impl Battlesnake {
+    pub fn new(id: &str, name: &str, position: (i16, i16)) -> Self {
+        let mut snake = Snake::new(position, SnakeDirection::Up);
+        let start = snake.body[0];
+        snake.body.resize(START_LENGTH, start);
+        Battlesnake {
+            id: id.to_string(),
+            name: name.to_string(),
+            snake,
+            health: MAX_HEALTH,
+            shout: String::new(),
+            eliminated: None,
+        }
+    }
+    pub fn head(&self) -> (i16, i16) {
+        let head = self.snake.body[0];
+        (head.x, head.y)
+    }
+    pub fn is_alive(&self) -> bool {
+        self.eliminated.is_none()
+    }
+}
+
+/// A game following the [Battlesnake standard rules](https://docs.battlesnake.com/rules), every turn: <br>
+/// 1. Snakes move, a snake without a move keeps going in its last direction.
+/// 2. Snakes lose one health point and more if their head is on a hazard without food.
+/// 3. Snakes eating food get back to [`MAX_HEALTH`] and grow right away.
+/// 4. New food is placed following the [`Ruleset`].
+/// 5. Snakes out of health, out of the board, or whose head hit a body or a head at least as long as them are eliminated.
+/// # Examples
+/// ```
+/// use std::collections::HashMap;
+/// use snake3::battlesnake::Battle;
+/// use snake3::snake::SnakeDirection;
+/// let mut battle = Battle::new(11, 11, &[("a", "Alice"), ("b", "Bob")]);
+/// let moves = HashMap::from([("a".to_string(), SnakeDirection::Right)]);
+/// battle.step(&moves);
+/// assert_eq!(battle.turn, 1);
+/// assert_eq!(battle.snakes[0].head(), (2, 1));
+/// ```
+pub struct Battle {
+    pub width: i16,
+    pub height: i16,
+    pub turn: u32,
+    pub snakes: Vec<Battlesnake>,
+    pub food: Vec<(i16, i16)>,
+    pub hazards: HashSet<(i16, i16)>,
+    pub rules: Ruleset,
+}
+
+impl Battle {
+    /// Returns a new battle with a snake for every `(id, name)` on the standard starting
+    /// positions and a food next to each of them and another one in the center.
+    /// # Panics
+    /// - If there are more players than [starting positions](`Battle::start_positions`).
+    pub fn new(width: i16, height: i16, players: &[(&str, &str)]) -> Self {
+        let positions = Battle::start_positions(width, height);
+        if players.len() > positions.len() {
+            panic!("There are only {} starting positions.", positions.len());
+        }
+        let snakes: Vec<Battlesnake> = players
+            .iter()
+            .zip(&positions)
+            .map(|((id, name), position)| Battlesnake::new(id, name, *position))
+            .collect();
+        let center = ((width - 1) / 2, (height - 1) / 2);
+        let mut food: Vec<(i16, i16)> = snakes
+            .iter()
+            .map(|battlesnake| {
+                let (x, y) = battlesnake.head();
+                (x + (center.0 - x).signum(), y + (center.1 - y).signum())
+            })
+            .collect();
+        if !food.contains(&center) && !positions[..snakes.len()].contains(&center) {
+            food.push(center);
+        }
+        Battle {
+            width,
+            height,
+            turn: 0,
+            snakes,
+            food,
+            hazards: HashSet::new(),
+            rules: Ruleset::default(),
+        }
+    }
+    /// Cells one step away from the corners and then from the middle of every side.
+    pub fn start_positions(width: i16, height: i16) -> Vec<(i16, i16)> {
+        let (min_x, mid_x, max_x) = (1, (width - 1) / 2, width - 2);
+        let (min_y, mid_y, max_y) = (1, (height - 1) / 2, height - 2);
+        vec![
+            (min_x, min_y),
+            (max_x, max_y),
+            (min_x, max_y),
+            (max_x, min_y),
+            (mid_x, min_y),
+            (mid_x, max_y),
+            (min_x, mid_y),
+            (max_x, mid_y),
+        ]
+    }
+    /// Runs one turn, `moves` has the direction of each snake by id.
+    pub fn step(&mut self, moves: &HashMap<String, SnakeDirection>) {
+        for battlesnake in self.snakes.iter_mut().filter(|b| b.is_alive()) {
+            if let Some(direction) = moves.get(&battlesnake.id) {
+                battlesnake.snake.face(*direction);
+            }
+            battlesnake.snake.advance();
+            battlesnake.health = battlesnake.health.saturating_sub(1);
+            let head = battlesnake.head();
+            if self.hazards.contains(&head) && !self.food.contains(&head) {
+                battlesnake.health = battlesnake.health.saturating_sub(self.rules.hazard_damage);
+            }
+        }
+        self.feed();
+        self.spawn_food();
+        self.eliminate();
+        self.turn += 1;
+    }
+    fn feed(&mut self) {
+        let mut eaten = HashSet::new();
+        for battlesnake in self.snakes.iter_mut().filter(|b| b.is_alive()) {
+            let head = battlesnake.head();
+            if self.food.contains(&head) {
+                battlesnake.health = MAX_HEALTH;
+                let tail = battlesnake.snake.body[battlesnake.snake.body.len() - 1];
+                battlesnake.snake.body.push(tail);
+                eaten.insert(head);
+            }
+        }
+        self.food.retain(|food| !eaten.contains(food));
+    }
+    fn spawn_food(&mut self) {
+        let missing = self.rules.minimum_food.saturating_sub(self.food.len());
+        let amount = if missing > 0 {
+            missing
+        } else if self.rules.food_spawn_chance > 0
+            && random_range(0, 100) < self.rules.food_spawn_chance as i16
+        {
+            1
+        } else {
+            0
+        };
+        for _ in 0..amount {
+            let free = self.free_cells();
+            if free.is_empty() {
+                return;
+            }
+            self.food.push(free[random_index(free.len())]);
+        }
+    }
+    /// Cells without snakes or food.
+    pub fn free_cells(&self) -> Vec<(i16, i16)> {
+        let taken: HashSet<(i16, i16)> = self
+            .snakes
+            .iter()
+            .filter(|b| b.is_alive())
+            .flat_map(|b| b.snake.body.iter().map(|point| (point.x, point.y)))
+            .chain(self.food.iter().copied())
+            .collect();
+        (0..self.width)
+            .flat_map(|x| (0..self.height).map(move |y| (x, y)))
+            .filter(|cell| !taken.contains(cell))
+            .collect()
+    }
+    fn eliminate(&mut self) {
+        for battlesnake in self.snakes.iter_mut().filter(|b| b.is_alive()) {
+            let (x, y) = battlesnake.head();
+            if battlesnake.health == 0 {
+                battlesnake.eliminated = Some(Elimination::OutOfHealth);
+            } else if x < 0 || y < 0 || x >= self.width || y >= self.height {
+                battlesnake.eliminated = Some(Elimination::Wall);
+            }
+        }
+        let alive: Vec<(&str, &[SnakeBodyPoint])> = self
+            .snakes
+            .iter()
+            .filter(|b| b.is_alive())
+            .map(|b| (b.id.as_str(), b.snake.body.as_slice()))
+            .collect();
+        let eliminations: Vec<Option<Elimination>> = self
+            .snakes
+            .iter()
+            .map(|battlesnake| {
+                if !battlesnake.is_alive() {
+                    return None;
+                }
+                let body = &battlesnake.snake.body;
+                let head = body[0];
+                if body[1..].contains(&head) {
+                    return Some(Elimination::SelfCollision);
+                }
+                for (id, other) in &alive {
+                    if *id == battlesnake.id {
+                        continue;
+                    }
+                    if other[1..].contains(&head) {
+                        return Some(Elimination::Collision(id.to_string()));
+                    }
+                    if other[0] == head && other.len() >= body.len() {
+                        return Some(Elimination::HeadToHead(id.to_string()));
+                    }
+                }
+                None
+            })
+            .collect();
+        for (battlesnake, elimination) in self.snakes.iter_mut().zip(eliminations) {
+            if elimination.is_some() {
+                battlesnake.eliminated = elimination;
+            }
+        }
+    }
+    /// Snakes still in the game.
+    pub fn alive(&self) -> impl Iterator<Item = &Battlesnake> {
+        self.snakes.iter().filter(|b| b.is_alive())
+    }
+    /// A solo game is over when its snake is eliminated, other games when one snake or none is left.
+    pub fn is_over(&self) -> bool {
+        let alive = self.alive().count();
+        if self.snakes.len() == 1 {
+            alive == 0
+        } else {
+            alive <= 1
+        }
+    }
+    /// The last snake standing once the game [is over](`Battle::is_over`), `None` for a draw or a solo game.
+    pub fn winner(&self) -> Option<&Battlesnake> {
+        if self.snakes.len() > 1 && self.is_over() {
+            self.alive().next()
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn moves(moves: &[(&str, SnakeDirection)]) -> HashMap<String, SnakeDirection> {
+        moves
+            .iter()
+            .map(|(id, direction)| (id.to_string(), *direction))
+            .collect()
+    }
+
+    #[test]
+    fn battle_new() {
+        let battle = Battle::new(11, 11, &[("a", "A"), ("b", "B")]);
+        assert_eq!(battle.snakes[0].head(), (1, 1));
+        assert_eq!(battle.snakes[1].head(), (9, 9));
+        assert_eq!(battle.snakes[0].snake.body.len(), START_LENGTH);
+        assert_eq!(battle.food, vec![(2, 2), (8, 8), (5, 5)]);
+        assert!(!battle.is_over());
+    }
+
+    #[test]
+    fn battle_health_and_food() {
+        let mut battle = Battle::new(11, 11, &[("a", "A")]);
+        battle.rules.food_spawn_chance = 0;
+        battle.food = vec![(1, 3)];
+        battle.hazards.insert((1, 2));
+        battle.step(&moves(&[("a", SnakeDirection::Up)]));
+        assert_eq!(battle.snakes[0].health, MAX_HEALTH - 1 - 14);
+        // Food under a hazard cancels the damage
+        battle.hazards.insert((1, 3));
+        battle.step(&moves(&[("a", SnakeDirection::Up)]));
+        assert_eq!(battle.snakes[0].health, MAX_HEALTH);
+        assert_eq!(battle.snakes[0].snake.body.len(), START_LENGTH + 1);
+        // Minimum food is placed again
+        assert_eq!(battle.food.len(), 1);
+        assert_ne!(battle.food[0], (1, 3));
+    }
+
+    #[test]
+    fn battle_spawns_food_on_big_boards() {
+        let mut battle = Battle::new(200, 200, &[("a", "A")]);
+        battle.food.clear();
+        battle.spawn_food();
+        assert_eq!(battle.food.len(), 1);
+    }
+
+    #[test]
+    fn battle_eliminations() {
+        let mut battle = Battle::new(7, 7, &[("a", "A"), ("b", "B")]);
+        battle.rules.food_spawn_chance = 0;
+        battle.step(&moves(&[
+            ("a", SnakeDirection::Left),
+            ("b", SnakeDirection::Down),
+        ]));
+        battle.step(&moves(&[
+            ("a", SnakeDirection::Left),
+            ("b", SnakeDirection::Up),
+        ]));
+        assert_eq!(battle.snakes[0].eliminated, Some(Elimination::Wall));
+        assert_eq!(
+            battle.snakes[1].eliminated,
+            Some(Elimination::SelfCollision)
+        );
+        assert!(battle.is_over());
+        assert!(battle.winner().is_none());
+
+        let mut battle = Battle::new(7, 7, &[("a", "A")]);
+        battle.snakes[0].health = 1;
+        battle.step(&HashMap::new());
+        assert_eq!(battle.snakes[0].eliminated, Some(Elimination::OutOfHealth));
+        assert!(battle.is_over());
+    }
+
+    #[test]
+    fn battle_head_to_head() {
+        let mut battle = Battle::new(7, 7, &[("a", "A"), ("b", "B")]);
+        battle.rules.food_spawn_chance = 0;
+        battle.food.clear();
+        battle.snakes[1] = Battlesnake::new("b", "B", (3, 1));
+        battle.snakes[1]
+            .snake
+            .body
+            .push(SnakeBodyPoint { x: 3, y: 1, z: 0 });
+        battle.step(&moves(&[
+            ("a", SnakeDirection::Right),
+            ("b", SnakeDirection::Left),
+        ]));
+        assert_eq!(
+            battle.snakes[0].eliminated,
+            Some(Elimination::HeadToHead("b".to_string()))
+        );
+        assert!(battle.snakes[1].is_alive());
+        assert_eq!(battle.winner().map(|b| b.id.as_str()), Some("b"));
+    }
+
+    #[test]
+    fn battle_body_collision() {
+        let mut battle = Battle::new(7, 7, &[("a", "A"), ("b", "B")]);
+        battle.rules.food_spawn_chance = 0;
+        battle.snakes[1] = Battlesnake::new("b", "B", (2, 2));
+        battle.step(&moves(&[
+            ("a", SnakeDirection::Up),
+            ("b", SnakeDirection::Up),
+        ]));
+        battle.step(&moves(&[
+            ("a", SnakeDirection::Right),
+            ("b", SnakeDirection::Up),
+        ]));
+        assert_eq!(
+            battle.snakes[0].eliminated,
+            Some(Elimination::Collision("b".to_string()))
+        );
+    }
+}
@@ -55,6 +55,33 @@
 //! }
 //! ```
 //!
+//! ## Game loop and events
+//! Instead of advancing and checking collisions by hand we can call [tick](`SnakeGame::tick`), which also
+//! records what happened as [`snake::GameEvent`]s:
+//! ```
+//! # use snake3::{SnakeGame, GameState};
+//! # let mut snake_game = SnakeGame::new(10,10,None,None);
+//! use snake3::snake::{Apple, GameEvent};
+//! snake_game.set_state(GameState::Playing);
+//! if let Some(hit) = snake_game.tick() {
+//!     // Same as with `check_entity_collision`
+//! }
+//! for event in snake_game.drain_events() {
+//!     // Play sounds, show particles...
+//! }
+//! ```
+//!
+//! ## Plugins
+//! Rules that change how a tick plays out can live in a [`snake::GamePlugin`] instead of around
+//! [tick](`SnakeGame::tick`), plugins hook into every phase of it and can veto collisions, meals and spawns:
+//! ```
+//! # use snake3::{SnakeGame, GameState};
+//! use snake3::snake::AppleGrowth;
+//! # let mut snake_game = SnakeGame::new(10,10,None,None);
+//! // Grow the snake on every apple without checking what tick returns
+//! snake_game.add_plugin(AppleGrowth);
+//! ```
+//!
 //! ## Adding entities and customization
 //! You can randomly add entities to the game with:
 //! ```
@@ -78,6 +105,40 @@
 //! new_game.generate_entity(named!(Bomb));
 //! ```
 //!
+//! Register an [`snake::EntityKind`] for it so renderers know how to draw it and saves how to store it,
+//! entities nobody registered are drawn like apples and can't be saved:
+//! ```
+//! # use snake3::{SnakeGame, impl_entity};
+//! # use snake3::snake::{Entity, EntityColor, EntityKind};
+//! # pub struct Bomb {
+//! #     pub x: i16,
+//! #     pub y: i16,
+//! # }
+//! # impl_entity!(Bomb);
+//! # let mut new_game = SnakeGame::new(10, 10, None, None);
+//! new_game.register_entity(
+//!     EntityKind::new("bomb", "Bomb")
+//!         .with_glyph('X')
+//!         .with_color(EntityColor::Magenta)
+//!         .with_constructor(|x, y| Box::new(Bomb::new(x, y))),
+//! );
+//! let saved = new_game.entity_registry().save(&Bomb::new(3, 4)).unwrap();
+//! assert_eq!(saved, "bomb 3,4,0");
+//! ```
+//!
+//! ## Battlesnake
+//! With the `battlesnake` feature, enabled by default, the [`battlesnake`] module runs games with the
+//! [Battlesnake](<https://docs.battlesnake.com>) standard rules and speaks its JSON API, both as a server
+//! for your own bot and as a client for remote ones.
+//!
+//! ## Scripting
+//! With the `scripting` feature the [`scripting`] module loads entity types and rules from
+//! [Rhai](<https://rhai.rs>) scripts at runtime, running them against a sandboxed API over the game.
+//!
+//! ## Multiplayer
+//! Outside of the browser the [`net`] module runs a game on a server for players and spectators
+//! connecting over TCP, keeping everyone in lockstep with a small versioned text protocol.
+//!
 //! ## Working example
 //! You can see an example implementation that runs in the terminal in [the repo](<https://github.com/ciurana-life/snake3/blob/main/src/main.rs>).
 //!
@@ -86,6 +147,12 @@
 //! ```bash
 //! cargo build --release --target wasm32-unknown-unknown
 //! ```
+#[cfg(feature = "battlesnake")]
+pub mod battlesnake;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod net;
+#[cfg(feature = "scripting")]
+pub mod scripting;
 pub mod snake;
 
 pub use snake::{GameState, SnakeGame};
@@ -0,0 +1,93 @@
+use snake3::battlesnake::{Battle, Bot, Elimination, GameInfo, RemoteBot, SimpleBot, play, serve};
+use std::io;
+use std::net::TcpListener;
+use std::time::Duration;
+
+const DEFAULT_PORT: u16 = 8000;
+const BOARD_SIZE: i16 = 11;
+const TIMEOUT_MS: u32 = 500;
+const MAX_TURNS: u32 = 1000;
+
+/// Runs `snake3 battlesnake <command>`, `args` are the ones after `battlesnake`.
+pub fn run(args: &[String]) -> io::Result<()> {
+    match args.first().map(String::as_str) {
+        Some("serve") => {
+            let port = match args.get(1).map(String::as_str) {
+                Some("--port" | "-p") => match args.get(2).map(|port| port.parse::<u16>()) {
+                    Some(Ok(port)) => port,
+                    _ => usage("The port must be a number."),
+                },
+                Some(other) => usage(&format!("Unknown argument '{other}'.")),
+                None => DEFAULT_PORT,
+            };
+            let listener = TcpListener::bind(("0.0.0.0", port))?;
+            println!(
+                "Serving the snake3 bot on http://{}",
+                listener.local_addr()?
+            );
+            serve(&listener, &mut SimpleBot, None)
+        }
+        Some("play") if args.len() > 1 => {
+            play_remote(&args[1..]);
+            Ok(())
+        }
+        Some("play") => usage("Missing the bots to play with."),
+        Some(other) => usage(&format!("Unknown command '{other}'.")),
+        None => usage(""),
+    }
+}
+
+/// Plays a local game between the bots at `urls`, `simple` plays with the built-in bot.
+fn play_remote(urls: &[String]) {
+    let ids: Vec<String> = (1..=urls.len()).map(|n| format!("snake-{n}")).collect();
+    let players: Vec<(&str, &str)> = ids
+        .iter()
+        .zip(urls)
+        .map(|(id, url)| (id.as_str(), url.as_str()))
+        .collect();
+    if players.len() > Battle::start_positions(BOARD_SIZE, BOARD_SIZE).len() {
+        usage("Too many bots for a single game.");
+    }
+    let mut battle = Battle::new(BOARD_SIZE, BOARD_SIZE, &players);
+    let game = GameInfo::standard("snake3-local", &battle.rules, TIMEOUT_MS);
+    let mut bots: Vec<Box<dyn Bot>> = urls
+        .iter()
+        .map(|url| -> Box<dyn Bot> {
+            if url == "simple" {
+                Box::new(SimpleBot)
+            } else {
+                Box::new(RemoteBot::new(
+                    url,
+                    Duration::from_millis(TIMEOUT_MS as u64),
+                ))
+            }
+        })
+        .collect();
+    play(&mut battle, &game, &mut bots, MAX_TURNS);
+
+    println!("Game over after {} turns.", battle.turn);
+    for battlesnake in &battle.snakes {
+        let status = match &battlesnake.eliminated {
+            None => format!("alive with length {}", battlesnake.snake.body.len()),
+            Some(Elimination::OutOfHealth) => "starved".to_string(),
+            Some(Elimination::Wall) => "hit a wall".to_string(),
+            Some(Elimination::SelfCollision) => "ran into itself".to_string(),
+            Some(Elimination::Collision(other)) => format!("ran into {other}"),
+            Some(Elimination::HeadToHead(other)) => format!("lost a head-to-head with {other}"),
+        };
+        println!("{} ({}): {status}", battlesnake.id, battlesnake.name);
+    }
+    match battle.winner() {
+        Some(winner) => println!("{} ({}) wins!", winner.id, winner.name),
+        None => println!("No winner."),
+    }
+}
+
+fn usage(error: &str) -> ! {
+    if !error.is_empty() {
+        eprintln!("{error}\n");
+    }
+    eprintln!("Usage: snake3 battlesnake serve [--port <port>]");
+    eprintln!("       snake3 battlesnake play <url|simple>...");
+    std::process::exit(if error.is_empty() { 0 } else { 2 });
+}
@@ -0,0 +1,146 @@
+use crossterm::{
+    ExecutableCommand,
+    cursor::MoveTo,
+    event::{self, Event, KeyCode, KeyEvent, KeyModifiers},
+    style::{self, Stylize},
+};
+use snake3::{
+    SnakeGame,
+    net::Broadcaster,
+    snake::{AppleGrowth, Campaign, CampaignProgress, CampaignStep, GameResult},
+};
+use std::io::{self, Stdout, Write};
+use std::path::PathBuf;
+
+use super::{COUNTDOWN_TICKS, Hud, InputAction, clear_terminal, data_dir, play};
+
+fn progress_path() -> PathBuf {
+    data_dir().join("campaign")
+}
+
+/// Plays the builtin [`Campaign`], starting from the level select screen.
+pub fn run(stdout: &mut Stdout, broadcaster: &mut Option<Broadcaster>) -> io::Result<()> {
+    let path = progress_path();
+    let mut progress = CampaignProgress::load(&path).unwrap_or_default();
+    let mut campaign = Campaign::builtin();
+
+    loop {
+        let Some(index) = level_select(stdout, &campaign, &progress)? else {
+            return Ok(());
+        };
+        campaign.select_level(index, &progress);
+
+        // LEVEL LOOP
+        loop {
+            clear_terminal(stdout)?;
+            let level = campaign.current_level().clone();
+            let level_index = campaign.current_index();
+            let mut snake_game = campaign.start_level();
+            snake_game.set_countdown(COUNTDOWN_TICKS);
+            snake_game.add_plugin(AppleGrowth);
+            snake_game.start();
+
+            let status = format!(
+                "Level {}/{} {} | Lives {}",
+                level_index + 1,
+                campaign.levels().len(),
+                level.name,
+                campaign.lives_left()
+            );
+            let carried_score = campaign.score();
+            let last_level = level_index + 1 == campaign.levels().len();
+            let last_life = campaign.lives_left() == 1;
+            let hud = |snake_game: &SnakeGame| {
+                let next = match snake_game.result() {
+                    Some(GameResult::Won) if last_level => "see the credits",
+                    Some(GameResult::Won) => "go to the next level",
+                    Some(GameResult::Died(_)) if !last_life => "try again",
+                    _ => "go back to the level select",
+                };
+                let eaten = snake_game.score().saturating_sub(carried_score);
+                Hud {
+                    status: format!("{status} | Apples {eaten}/{}", level.target_apples),
+                    end_prompt: format!("Press <y> to {next}, to close press <q>"),
+                    info: String::new(),
+                }
+            };
+            let spawn = |snake_game: &mut SnakeGame| level.spawn_apples(snake_game);
+
+            match play(
+                stdout,
+                &mut snake_game,
+                &level.difficulty,
+                &spawn,
+                &hud,
+                broadcaster,
+                None,
+            )? {
+                InputAction::Quit => return Ok(()),
+                InputAction::Continue | InputAction::Restart => {}
+            }
+
+            if snake_game.result() == Some(GameResult::Won) {
+                progress.record(level_index, snake_game.score());
+                progress.save(&path)?;
+            }
+            match campaign.finish_level(&snake_game) {
+                CampaignStep::NextLevel(_) | CampaignStep::Retry => {}
+                CampaignStep::Completed | CampaignStep::GameOver => break,
+            }
+        }
+    }
+}
+
+/// Shows the levels and waits for the player to pick an unlocked one, `None` if they want to quit.
+fn level_select(
+    stdout: &mut Stdout,
+    campaign: &Campaign,
+    progress: &CampaignProgress,
+) -> io::Result<Option<usize>> {
+    clear_terminal(stdout)?;
+    stdout
+        .execute(MoveTo(0, 0))?
+        .execute(style::PrintStyledContent("Campaign".green()))?;
+    for (index, level) in campaign.levels().iter().enumerate() {
+        let line = if progress.is_unlocked(index) {
+            let best = progress.best_scores.get(index).copied().unwrap_or(0);
+            format!("<{}> {} - best score {best}", index + 1, level.name)
+        } else {
+            format!("    {} - locked", level.name)
+        };
+        stdout.execute(MoveTo(0, index as u16 + 2))?;
+        if progress.is_unlocked(index) {
+            stdout.execute(style::PrintStyledContent(line.cyan()))?;
+        } else {
+            stdout.execute(style::PrintStyledContent(line.dark_grey()))?;
+        }
+    }
+    let help = "Press the number of a level to play it, to close press <q>";
+    stdout
+        .execute(MoveTo(0, campaign.levels().len() as u16 + 3))?
+        .execute(style::PrintStyledContent(help.dark_grey()))?;
+    stdout.flush()?;
+
+    loop {
+        if let Event::Key(KeyEvent {
+            code, modifiers, ..
+        }) = event::read()?
+        {
+            match code {
+                KeyCode::Char('q') => return Ok(None),
+                KeyCode::Char('c') if modifiers.contains(KeyModifiers::CONTROL) => {
+                    return Ok(None);
+                }
+                KeyCode::Char(ch) => {
+                    if let Some(index) = ch.to_digit(10).and_then(|n| (n as usize).checked_sub(1))
+                        && index < campaign.levels().len()
+                        && progress.is_unlocked(index)
+                    {
+                        return Ok(Some(index));
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+}
@@ -0,0 +1,79 @@
+use snake3::{
+    SnakeGame,
+    net::Broadcaster,
+    snake::{
+        AppleGrowth, DailyChallenge, DailyResults, Date, Difficulty, DifficultyCurve, GameResult,
+    },
+};
+use std::io::{self, Stdout};
+use std::path::PathBuf;
+
+use super::{COUNTDOWN_TICKS, Hud, InputAction, clear_terminal, data_dir, play, spawn_entities};
+
+fn results_path() -> PathBuf {
+    data_dir().join("daily")
+}
+
+/// Plays the [`DailyChallenge`] of today as many times as the player wants, at normal speed for everyone. <br>
+/// Returns the line to share the best run of the day, if there is one.
+pub fn run(
+    stdout: &mut Stdout,
+    broadcaster: &mut Option<Broadcaster>,
+) -> io::Result<Option<String>> {
+    let path = results_path();
+    let mut results = DailyResults::load(&path).unwrap_or_default();
+    let challenge = DailyChallenge::for_date(Date::today());
+    let curve = DifficultyCurve::preset(Difficulty::Normal);
+    let mut snake_game = challenge.new_game();
+    snake_game.set_countdown(COUNTDOWN_TICKS);
+    snake_game.add_plugin(AppleGrowth);
+
+    loop {
+        clear_terminal(stdout)?;
+        snake_game.reset();
+        snake_game.start_recording(&challenge.config());
+        spawn_entities(&mut snake_game);
+        snake_game.start();
+
+        let best = match results.get(challenge.date) {
+            Some(result) => format!("Best {} in {} tries", result.score, result.attempts),
+            None => "First try".to_string(),
+        };
+        let status = format!(
+            "Daily {} {} | {best}",
+            challenge.date,
+            challenge.variant.name()
+        );
+        let hud = |_: &SnakeGame| Hud {
+            status: status.clone(),
+            end_prompt: "Press <y> to try again, to close and get the line to share press <Esc>"
+                .to_string(),
+            info: String::new(),
+        };
+        let action = play(
+            stdout,
+            &mut snake_game,
+            &curve,
+            &spawn_entities,
+            &hud,
+            broadcaster,
+            None,
+        )?;
+
+        if let Some(recording) = snake_game.take_recording()
+            && snake_game.get_state().is_finished()
+            && snake_game.result() != Some(GameResult::Aborted)
+        {
+            results.record(challenge.date, &recording);
+            results.save(&path)?;
+        }
+        match action {
+            InputAction::Quit => {
+                return Ok(results
+                    .get(challenge.date)
+                    .map(|result| challenge.export_line(result)));
+            }
+            InputAction::Continue | InputAction::Restart => {}
+        }
+    }
+}
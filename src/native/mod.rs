@@ -0,0 +1,1074 @@
+use crossterm::{
+    ExecutableCommand,
+    cursor::{Hide, MoveTo, Show},
+    event::{self, Event, KeyCode, KeyEvent, KeyModifiers},
+    style::{self, Stylize},
+    terminal::{self, Clear, ClearType},
+};
+#[cfg(feature = "scripting")]
+use snake3::scripting::ScriptRules;
+use snake3::{
+    GameState, SnakeGame, named,
+    net::Broadcaster,
+    snake::{
+        Apple, AppleGrowth, DeathCause, Difficulty, DifficultyCurve, EntityColor, GameClock,
+        GameResult, Ghost, HazardDamage, HazardGrowth, HazardRules, Health, Movement, Player,
+        PowerUp, Recording, Snake, SnakeDirection, StatusEffect, Topology, VersusMatch,
+    },
+};
+use std::cell::RefCell;
+use std::io::{self, Stdout, Write};
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "battlesnake")]
+mod battlesnake;
+mod campaign;
+mod daily;
+mod multiplayer;
+
+const COUNTDOWN_TICKS: u64 = 3;
+const POWER_UP_TICKS: u32 = 40;
+const SURVIVAL_HEALTH: u32 = 120;
+const HEALTH_BAR_WIDTH: usize = 20;
+const SHRINK_START_TICKS: u64 = 150;
+const SHRINK_EVERY_TICKS: u64 = 60;
+const HAZARD_DRAIN: u32 = 10;
+const MAX_LAYERS: i16 = 9;
+
+pub enum InputAction {
+    Continue,
+    Restart,
+    Quit,
+}
+
+pub enum Mode {
+    Endless,
+    Campaign,
+    /// Two players on the same keyboard, the first one winning more than half of the rounds wins.
+    Versus {
+        best_of: u32,
+    },
+    /// The same game for everyone on the same day, see [`DailyChallenge`](`snake3::snake::DailyChallenge`).
+    Daily,
+}
+
+pub struct Options {
+    pub difficulty: Difficulty,
+    pub mode: Mode,
+    /// Turning back reverses the snake instead of being ignored.
+    pub reverse: bool,
+    pub movement: Movement,
+    pub topology: Topology,
+    pub layers: i16,
+    /// The snake starves if it does not eat.
+    pub survival: bool,
+    /// The borders of the board close in over time.
+    pub shrink: bool,
+    /// Localhost port where every frame is published for `snake3 watch`.
+    pub broadcast: Option<u16>,
+    /// Every game is the same for the same seed and the best run gets saved.
+    pub seed: Option<u64>,
+    /// Race against the best run saved for the seed.
+    pub ghost: bool,
+    /// Entities and rules loaded from a script.
+    #[cfg(feature = "scripting")]
+    pub script: Option<ScriptRules>,
+}
+
+/// Text shown below the board, it changes with the game.
+pub struct Hud {
+    /// Shown next to the score.
+    pub status: String,
+    /// Shown once the game is finished.
+    pub end_prompt: String,
+    /// Shown on the info bar before a short reminder of the controls, the full controls are shown when empty.
+    pub info: String,
+}
+
+/// Reads the command line arguments, exits with a usage message if they are wrong.
+pub fn parse_args() -> Options {
+    let mut options = Options {
+        difficulty: Difficulty::Normal,
+        mode: Mode::Endless,
+        reverse: false,
+        movement: Movement::Orthogonal,
+        topology: Topology::Square,
+        layers: 1,
+        survival: false,
+        shrink: false,
+        broadcast: None,
+        seed: None,
+        ghost: false,
+        #[cfg(feature = "scripting")]
+        script: None,
+    };
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--difficulty" | "-d" => match args.next().map(|value| value.parse::<Difficulty>()) {
+                Some(Ok(difficulty)) => options.difficulty = difficulty,
+                Some(Err(error)) => usage(&error),
+                None => usage("Missing value for --difficulty."),
+            },
+            "--campaign" | "-c" => options.mode = Mode::Campaign,
+            "--daily" => options.mode = Mode::Daily,
+            "--versus" | "-v" => match args.next().map(|value| value.parse::<u32>()) {
+                Some(Ok(best_of)) if best_of >= 1 => options.mode = Mode::Versus { best_of },
+                Some(_) => usage("A versus match needs at least one round."),
+                None => usage("Missing the number of rounds for --versus."),
+            },
+            "--reverse" | "-r" => options.reverse = true,
+            "--eight-way" | "-8" => options.movement = Movement::EightWay,
+            "--hex" => options.topology = Topology::Hex,
+            "--layers" | "-l" => match args.next().map(|value| value.parse::<i16>()) {
+                Some(Ok(layers)) if (1..=MAX_LAYERS).contains(&layers) => options.layers = layers,
+                Some(_) => usage(&format!(
+                    "The number of layers must be from 1 to {MAX_LAYERS}."
+                )),
+                None => usage("Missing value for --layers."),
+            },
+            "--survival" | "-s" => options.survival = true,
+            "--shrink" => options.shrink = true,
+            "--broadcast" | "-b" => match args.next().map(|value| value.parse::<u16>()) {
+                Some(Ok(port)) => options.broadcast = Some(port),
+                Some(Err(_)) => usage("The port to broadcast on must be a number."),
+                None => usage("Missing the port for --broadcast."),
+            },
+            "--seed" => match args.next().map(|value| value.parse::<u64>()) {
+                Some(Ok(seed)) => options.seed = Some(seed),
+                Some(Err(_)) => usage("The seed must be a positive number."),
+                None => usage("Missing value for --seed."),
+            },
+            "--ghost" | "-g" => options.ghost = true,
+            #[cfg(feature = "scripting")]
+            "--script" => match args.next().map(|path| ScriptRules::load(path.as_ref())) {
+                Some(Ok(rules)) => options.script = Some(rules),
+                Some(Err(error)) => usage(&format!("The script failed to load: {error}")),
+                None => usage("Missing value for --script."),
+            },
+            "--help" | "-h" => usage(""),
+            other => usage(&format!("Unknown argument '{other}'.")),
+        }
+    }
+    if matches!(options.mode, Mode::Versus { .. })
+        && (options.movement == Movement::EightWay
+            || options.topology == Topology::Hex
+            || options.layers > 1
+            || options.survival)
+    {
+        usage("--versus can't be combined with --eight-way, --hex, --layers or --survival.");
+    }
+    if options.ghost && options.seed.is_none() {
+        usage("--ghost races the best run of a seed, pick one with --seed.");
+    }
+    if (options.seed.is_some() || options.ghost) && !matches!(options.mode, Mode::Endless) {
+        usage("--seed and --ghost only work on endless games.");
+    }
+    #[cfg(feature = "scripting")]
+    if options.script.is_some() && (options.ghost || !matches!(options.mode, Mode::Endless)) {
+        usage("--script only works on endless games without --ghost.");
+    }
+    // The rules of the day are the same for everyone
+    if matches!(options.mode, Mode::Daily)
+        && (options.reverse
+            || options.movement == Movement::EightWay
+            || options.topology == Topology::Hex
+            || options.layers > 1
+            || options.survival
+            || options.shrink)
+    {
+        usage("--daily picks its own rules, it can't be combined with the rule options.");
+    }
+    options
+}
+
+fn usage(error: &str) -> ! {
+    if !error.is_empty() {
+        eprintln!("{error}\n");
+    }
+    eprintln!(
+        "Usage: snake3 [--difficulty easy|normal|hard|insane] [--campaign] [--daily] [--versus <rounds>] [--reverse] [--eight-way] [--hex] [--layers <n>] [--survival] [--shrink] [--broadcast <port>] [--seed <n> [--ghost]]"
+    );
+    eprintln!("       snake3 host [--port <port>] [--players 1|2]");
+    eprintln!("       snake3 join <address> [--watch]");
+    eprintln!("       snake3 watch [<address>]");
+    #[cfg(feature = "scripting")]
+    eprintln!("       snake3 --script <file.rhai> [...]");
+    #[cfg(feature = "battlesnake")]
+    eprintln!("       snake3 battlesnake serve|play ...");
+    std::process::exit(if error.is_empty() { 0 } else { 2 });
+}
+
+/// Directory where the game keeps its files, `$SNAKE3_HOME` or `~/.snake3`.
+pub fn data_dir() -> PathBuf {
+    if let Some(dir) = std::env::var_os("SNAKE3_HOME") {
+        return PathBuf::from(dir);
+    }
+    match std::env::var_os("HOME") {
+        Some(home) => PathBuf::from(home).join(".snake3"),
+        None => PathBuf::from(".snake3"),
+    }
+}
+
+pub fn main() -> io::Result<()> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let mut stdout = io::stdout();
+    match args.first().map(String::as_str) {
+        #[cfg(feature = "battlesnake")]
+        Some("battlesnake") => return battlesnake::run(&args[1..]),
+        Some("host") => return multiplayer::host(&args[1..]),
+        Some("join") => {
+            multiplayer::join(&mut stdout, &args[1..])?;
+            println!("The game was closed, have a nice day :)");
+            return Ok(());
+        }
+        Some("watch") => {
+            multiplayer::watch(&mut stdout, &args[1..])?;
+            println!("The game was closed, have a nice day :)");
+            return Ok(());
+        }
+        _ => {}
+    }
+    let options = parse_args();
+    // Only local watchers, the game is not meant to be exposed to the network
+    let mut broadcaster = match options.broadcast {
+        Some(port) => Some(Broadcaster::bind(("127.0.0.1", port))?),
+        None => None,
+    };
+    enable_game_mode(&mut stdout)?;
+
+    let (cols, rows) = get_terminal_size(&mut stdout);
+    let mut share = None;
+    match options.mode {
+        Mode::Endless => endless(&mut stdout, &options, &mut broadcaster, cols, rows)?,
+        Mode::Campaign => campaign::run(&mut stdout, &mut broadcaster)?,
+        Mode::Daily => share = daily::run(&mut stdout, &mut broadcaster)?,
+        Mode::Versus { best_of } => {
+            versus(&mut stdout, &options, &mut broadcaster, best_of, cols, rows)?
+        }
+    }
+
+    disable_game_mode(&mut stdout)?;
+    if let Some(line) = share {
+        println!("Your best run of the day, paste it to compare:\n{line}");
+    }
+    println!("The game was closed, have a nice day :)");
+    Ok(())
+}
+
+fn endless(
+    stdout: &mut Stdout,
+    options: &Options,
+    broadcaster: &mut Option<Broadcaster>,
+    cols: u16,
+    rows: u16,
+) -> io::Result<()> {
+    let curve = DifficultyCurve::preset(options.difficulty);
+    let columns = board_columns(options.topology, cols);
+    let new_game = || {
+        let mut snake_game = SnakeGame::new(columns, rows as i16, None, None);
+        configure(&mut snake_game, options);
+        snake_game.set_seed(options.seed);
+        snake_game
+    };
+    let mut snake_game = new_game();
+    #[cfg(feature = "scripting")]
+    if let Some(rules) = &options.script {
+        rules.install(&mut snake_game);
+    }
+    // What the script printed last or why it failed
+    let script_info = RefCell::new(String::new());
+    let spawn = |snake_game: &mut SnakeGame| {
+        spawn_entities(snake_game);
+        run_script(snake_game, options, &script_info, false);
+    };
+    // Runs are only comparable on the same board with the same rules
+    let config = run_config(&snake_game, options);
+    let ghost_path = options.seed.map(|seed| {
+        data_dir()
+            .join("ghosts")
+            .join(format!("{seed}-{config}.run"))
+    });
+    let hud = |_: &SnakeGame| Hud {
+        status: script_info.borrow().clone(),
+        end_prompt: if options.movement == Movement::EightWay || options.topology == Topology::Hex {
+            "Press <y> to play a new game, to close press <Esc>".to_string()
+        } else {
+            "Press <y> to play a new game, to close press <q>".to_string()
+        },
+        info: String::new(),
+    };
+
+    loop {
+        clear_terminal(stdout)?;
+        snake_game.reset();
+        if ghost_path.is_some() {
+            snake_game.start_recording(&config);
+        }
+        spawn_entities(&mut snake_game);
+        run_script(&mut snake_game, options, &script_info, true);
+        let mut ghost = match &ghost_path {
+            Some(path) if options.ghost => Recording::load(path).ok().map(|recording| {
+                let mut ghost = Ghost::new(new_game(), recording);
+                spawn_entities(ghost.game_mut());
+                ghost
+            }),
+            _ => None,
+        };
+        snake_game.start();
+        let action = play(
+            stdout,
+            &mut snake_game,
+            &curve,
+            &spawn,
+            &hud,
+            broadcaster,
+            ghost.as_mut(),
+        )?;
+        if let Some(path) = &ghost_path
+            && let Some(recording) = snake_game.take_recording()
+            && snake_game.get_state().is_finished()
+            && snake_game.result() != Some(GameResult::Aborted)
+            && Recording::load(path).map_or(true, |best| recording.score > best.score)
+        {
+            recording.save(path)?;
+        }
+        match action {
+            InputAction::Quit => return Ok(()),
+            InputAction::Continue | InputAction::Restart => {}
+        }
+    }
+}
+
+/// Runs the hooks of the `--script` rules, `on_spawn` alone when `starting`, or the ones after a tick. <br>
+/// A failing script ends the game, its error and the last line it printed show up in `info`.
+#[cfg(feature = "scripting")]
+fn run_script(
+    snake_game: &mut SnakeGame,
+    options: &Options,
+    info: &RefCell<String>,
+    starting: bool,
+) {
+    let Some(rules) = &options.script else {
+        return;
+    };
+    let result = if starting {
+        rules.spawn(snake_game)
+    } else {
+        rules.update(snake_game)
+    };
+    if let Some(line) = rules.drain_log().pop() {
+        *info.borrow_mut() = line;
+    }
+    if let Err(error) = result {
+        *info.borrow_mut() = error.to_string();
+        snake_game.abort();
+    }
+}
+
+#[cfg(not(feature = "scripting"))]
+fn run_script(_: &mut SnakeGame, _: &Options, _: &RefCell<String>, _: bool) {}
+
+/// Describes the board and the options that change the game, used to name the saved runs.
+fn run_config(snake_game: &SnakeGame, options: &Options) -> String {
+    let mut config = format!(
+        "{}x{}x{}-{}-{:?}-{:?}",
+        snake_game.columns,
+        snake_game.rows,
+        options.layers,
+        options.difficulty.name(),
+        options.movement,
+        options.topology
+    );
+    for (enabled, name) in [
+        (options.reverse, "reverse"),
+        (options.survival, "survival"),
+        (options.shrink, "shrink"),
+    ] {
+        if enabled {
+            config += &format!("-{name}");
+        }
+    }
+    config.to_lowercase()
+}
+
+/// Player one on the left with WASD against player two on the right with the arrows.
+fn versus(
+    stdout: &mut Stdout,
+    options: &Options,
+    broadcaster: &mut Option<Broadcaster>,
+    best_of: u32,
+    cols: u16,
+    rows: u16,
+) -> io::Result<()> {
+    let curve = DifficultyCurve::preset(options.difficulty);
+    // One more line below the board for the score of player two
+    let (columns, rows) = (cols as i16, rows as i16 - 1);
+    let mut snake_game = SnakeGame::new(
+        columns,
+        rows,
+        Some(SnakeDirection::Right),
+        Some((columns / 4, rows / 2)),
+    );
+    configure(&mut snake_game, options);
+    snake_game.add_rival((columns * 3 / 4, rows / 2), SnakeDirection::Left);
+    let mut versus_match = VersusMatch::new(best_of);
+
+    loop {
+        clear_terminal(stdout)?;
+        snake_game.reset();
+        spawn_entities(&mut snake_game);
+        snake_game.start();
+        let hud = |snake_game: &SnakeGame| versus_hud(&versus_match, snake_game);
+        match play(
+            stdout,
+            &mut snake_game,
+            &curve,
+            &spawn_entities,
+            &hud,
+            broadcaster,
+            None,
+        )? {
+            InputAction::Quit => return Ok(()),
+            InputAction::Continue | InputAction::Restart => {}
+        }
+        if let Some(result) = snake_game.result() {
+            versus_match.record(result);
+        }
+        if versus_match.is_over() {
+            versus_match.reset();
+        }
+    }
+}
+
+fn versus_hud(versus_match: &VersusMatch, snake_game: &SnakeGame) -> Hud {
+    let mut versus_match = versus_match.clone();
+    let round = versus_match.rounds_played() + 1;
+    let finished = match snake_game.result() {
+        Some(result) => {
+            versus_match.record(result);
+            true
+        }
+        None => false,
+    };
+    let (one, two) = (
+        versus_match.wins(Player::One),
+        versus_match.wins(Player::Two),
+    );
+    let end_prompt = match versus_match.winner() {
+        Some(Player::One) => format!(
+            "Player one wins the match {one}-{two}! Press <y> for a new match, to close press <q>"
+        ),
+        Some(Player::Two) => format!(
+            "Player two wins the match {two}-{one}! Press <y> for a new match, to close press <q>"
+        ),
+        None if finished => format!(
+            "Match {one}-{two}, best of {}. Press <y> for the next round, to close press <q>",
+            versus_match.best_of()
+        ),
+        None => String::new(),
+    };
+    Hud {
+        status: format!(
+            "Round {round}, best of {}: {one}-{two}",
+            versus_match.best_of()
+        ),
+        end_prompt,
+        info: String::new(),
+    }
+}
+
+/// Applies the command line options to a new game.
+fn configure(snake_game: &mut SnakeGame, options: &Options) {
+    snake_game.add_plugin(AppleGrowth);
+    snake_game.set_topology(options.topology);
+    snake_game.set_layers(options.layers);
+    snake_game.set_countdown(COUNTDOWN_TICKS);
+    snake_game.snake.set_reverse_on_opposite(options.reverse);
+    snake_game.snake.set_movement(options.movement);
+    if options.survival {
+        snake_game
+            .snake
+            .set_health(Some(Health::new(SURVIVAL_HEALTH)));
+    }
+    if options.shrink {
+        // Survivors get hurt by the hazards, everyone else dies on them
+        snake_game.set_hazard_rules(HazardRules {
+            growth: HazardGrowth::Shrink,
+            start: SHRINK_START_TICKS,
+            every: SHRINK_EVERY_TICKS,
+            damage: HazardDamage::Drain(HAZARD_DRAIN),
+        });
+    }
+}
+
+/// Keeps an apple on the board and sometimes adds a [`PowerUp`].
+fn spawn_entities(snake_game: &mut SnakeGame) {
+    if !snake_game
+        .entities
+        .iter()
+        .any(|entity| entity.downcast_ref::<Apple>().is_some())
+    {
+        snake_game.generate_entity(named!(Apple));
+    }
+    spawn_power_up(snake_game);
+}
+
+/// Every now and then places a random [`PowerUp`] if there is none on the board.
+pub fn spawn_power_up(snake_game: &mut SnakeGame) {
+    let on_board = snake_game
+        .entities
+        .iter()
+        .any(|entity| entity.downcast_ref::<PowerUp>().is_some());
+    if on_board || snake_game.random_range(0, 60) != 0 {
+        return;
+    }
+    let effect = StatusEffect::ALL[snake_game.random_index(StatusEffect::ALL.len())];
+    snake_game.generate_entity(|x, y| Box::new(PowerUp::new(x, y, effect, POWER_UP_TICKS)));
+}
+
+/// Runs the game loop until the player asks to restart or quit, `spawn` is called every
+/// tick to place the entities the game needs and every frame goes to the `broadcaster` if there is one. <br>
+/// The `ghost` moves along with the snake and is drawn below it.
+pub fn play(
+    stdout: &mut Stdout,
+    snake_game: &mut SnakeGame,
+    curve: &DifficultyCurve,
+    spawn: &dyn Fn(&mut SnakeGame),
+    hud: &dyn Fn(&SnakeGame) -> Hud,
+    broadcaster: &mut Option<Broadcaster>,
+    mut ghost: Option<&mut Ghost>,
+) -> io::Result<InputAction> {
+    let mut clock = GameClock::new(curve.interval(speed_score(snake_game)));
+    let mut last_update = Instant::now();
+
+    // GAME LOOP
+    loop {
+        // CLEAR
+        clear_frame(stdout, snake_game)?;
+        // DRAW
+        let mut frame_hud = hud(snake_game);
+        if let Some(ghost) = &ghost {
+            if !snake_game.get_state().is_finished() && !ghost.game().get_state().is_finished() {
+                draw_ghost(stdout, snake_game, &ghost.game().snake)?;
+            }
+            frame_hud.info = ghost_info(ghost, snake_game);
+        }
+        draw_frame(stdout, snake_game, &frame_hud)?;
+        if let Some(broadcaster) = broadcaster {
+            broadcaster.publish(snake_game)?;
+        }
+        // INPUT
+        match handle_input(snake_game, clock.until_next_tick())? {
+            InputAction::Continue => {}
+            action => return Ok(action),
+        }
+        // LOGIC
+        let now = Instant::now();
+        for _ in 0..clock.update(now - last_update) {
+            let ticks = snake_game.ticks();
+            game_logic(snake_game, spawn)?;
+            if snake_game.ticks() > ticks
+                && let Some(ghost) = &mut ghost
+            {
+                let mut result = Ok(());
+                ghost.advance(|ghost_game| result = game_logic(ghost_game, spawn));
+                result?;
+            }
+            clock.set_interval(curve.interval(speed_score(snake_game)));
+            clock.set_time_scale(snake_game.snake.effects().time_scale());
+        }
+        last_update = now;
+    }
+}
+
+/// The points the difficulty curve goes by, the ones scored in this game leaving out those carried from
+/// earlier campaign levels, so every level starts at the speed of its own difficulty.
+fn speed_score(snake_game: &SnakeGame) -> u64 {
+    let carried = snake_game.scoring().breakdown().carried;
+    snake_game.score().saturating_sub(carried)
+}
+
+fn clear_frame(stdout: &mut Stdout, snake_game: &SnakeGame) -> io::Result<()> {
+    let text_lines = if snake_game.rival.is_some() { 5 } else { 4 };
+    for y in 0..snake_game.rows as u16 + text_lines {
+        stdout
+            .execute(MoveTo(0, y))?
+            .execute(Clear(ClearType::CurrentLine))?;
+    }
+    Ok(())
+}
+
+fn draw_frame(stdout: &mut Stdout, snake_game: &SnakeGame, hud: &Hud) -> io::Result<()> {
+    // Walls
+    for (x, y) in snake_game.walls() {
+        stdout
+            .execute(cell(snake_game, *x, *y))?
+            .execute(style::PrintStyledContent("#".dark_grey()))?;
+    }
+
+    // Hazards
+    for (x, y) in snake_game.hazards().cells() {
+        stdout
+            .execute(cell(snake_game, *x, *y))?
+            .execute(style::PrintStyledContent("░".dark_red()))?;
+    }
+
+    // Portals
+    for (index, (a, b)) in snake_game.portals().pairs().iter().enumerate() {
+        let letter = (b'A' + (index % 26) as u8) as char;
+        for (x, y) in [a, b] {
+            stdout
+                .execute(cell(snake_game, *x, *y))?
+                .execute(style::PrintStyledContent(letter.magenta()))?;
+        }
+    }
+
+    // Snake and entities, the layers next to the one of the head are drawn ghosted below it
+    if !snake_game.get_state().is_finished() {
+        let layer = snake_game.snake.body[0].z;
+        for ghosted in [true, false] {
+            draw_snake(stdout, snake_game, &snake_game.snake, layer, ghosted)?;
+            if let Some(rival) = &snake_game.rival {
+                draw_snake(stdout, snake_game, rival, layer, ghosted)?;
+            }
+            draw_entities(stdout, snake_game, layer, ghosted)?;
+        }
+    }
+
+    // Paused screen
+    if snake_game.get_state() == GameState::Paused {
+        let x_third = (snake_game.rows / 3) as u16;
+        let y_third = (snake_game.columns / 3) as u16;
+        let lines = "*".repeat(y_third as usize);
+        let lines2 = "*".repeat(y_third as usize);
+        let text = "Game is puased";
+        let text2 = "press <p> to resume";
+        stdout
+            .execute(MoveTo(y_third + 2, x_third + 1))?
+            .execute(style::PrintStyledContent(text.red()))?
+            .execute(MoveTo(y_third + 2, x_third + 2))?
+            .execute(style::PrintStyledContent(text2.red()))?
+            .execute(MoveTo(y_third, x_third - 1))?
+            .execute(style::PrintStyledContent(lines.red()))?
+            .execute(MoveTo(y_third, x_third + 4))?
+            .execute(style::PrintStyledContent(lines2.red()))?;
+    }
+
+    // Countdown
+    if snake_game.get_state() == GameState::Countdown {
+        let text = format!("Get ready... {}", snake_game.countdown_left());
+        stdout
+            .execute(MoveTo(
+                (snake_game.columns / 3) as u16 + 2,
+                (snake_game.rows / 3) as u16,
+            ))?
+            .execute(style::PrintStyledContent(text.yellow()))?;
+    }
+
+    // Game ended
+    if snake_game.get_state().is_finished() {
+        let ending = match snake_game.result() {
+            Some(GameResult::Draw) => "Both players crashed, it's a draw",
+            Some(GameResult::RivalDied(_)) => "Player one wins the round",
+            Some(GameResult::Died(_)) if snake_game.rival.is_some() => "Player two wins the round",
+            Some(GameResult::Won) => "You won",
+            Some(GameResult::Died(DeathCause::Wall)) => "You hit a wall",
+            Some(GameResult::Died(DeathCause::SelfCollision)) => "You bit yourself",
+            Some(GameResult::Died(DeathCause::Starvation)) => "You starved",
+            Some(GameResult::Died(DeathCause::Hazard)) => "The arena got you",
+            Some(GameResult::Died(DeathCause::Rule)) => "The rules got you",
+            Some(GameResult::Died(DeathCause::Rival)) => "You ran into the other snake",
+            Some(GameResult::Aborted) | None => "Your game ended",
+        };
+        let end_text_1 = match &snake_game.rival {
+            Some(_) => format!(
+                "{ending}, scores {} to {}",
+                snake_game.score(),
+                snake_game.rival_score()
+            ),
+            None => format!("{ending} with a score of {} points", snake_game.score()),
+        };
+        let end_text_2 = hud.end_prompt.as_str();
+        let breakdown = snake_game.scoring().breakdown();
+        let end_text_3 = format!(
+            "Carried {} + base {} + combo {} + length {} + time {} + multiplier {} + bonus {}",
+            breakdown.carried,
+            breakdown.base,
+            breakdown.combo,
+            breakdown.length,
+            breakdown.time,
+            breakdown.multiplier,
+            breakdown.bonus
+        );
+        stdout
+            .execute(MoveTo(0, 0))?
+            .execute(style::PrintStyledContent(end_text_1.red()))?
+            .execute(MoveTo(0, 1))?
+            .execute(style::PrintStyledContent(end_text_2.red()))?
+            .execute(MoveTo(0, 2))?
+            .execute(style::PrintStyledContent(end_text_3.dark_red()))?;
+    }
+
+    // Info text
+    let snake_rows = snake_game.rows as u16;
+    let info_text = if !hud.info.is_empty() {
+        format!("{} | <p> to pause, <Esc> to exit.", hud.info)
+    } else if snake_game.rival.is_some() {
+        "Player one moves with <wasd>, player two with the arrows, press <q> to exit and <p> to pause.".to_string()
+    } else if steers_with_letters(snake_game) {
+        "Move with arrows, <qweadzxc> or the numpad, press <Esc> or <Ctrl+C> to exit, press <p> to pause and resume.".to_string()
+    } else {
+        "Move with keyboard arrows, press <q> or <Ctrl+C> to exit, press <p> to pause and resume."
+            .to_string()
+    };
+    let division = "-".repeat(board_width(snake_game) as usize);
+    let mut score = match snake_game.rival {
+        Some(_) => format!("Player one: {}", snake_game.score()),
+        None => format!("Score: {}", snake_game.score()),
+    };
+    if let Some(health) = snake_game.snake.health() {
+        let filled = (health.fraction() * HEALTH_BAR_WIDTH as f32).ceil() as usize;
+        score += &format!(
+            " | Health [{}{}]",
+            "|".repeat(filled),
+            " ".repeat(HEALTH_BAR_WIDTH - filled)
+        );
+    }
+    if snake_game.layers() > 1 {
+        score += &format!(
+            " | Layer {}/{}, <r> to go up and <f> down",
+            snake_game.snake.body[0].z + 1,
+            snake_game.layers()
+        );
+    }
+    if !hud.status.is_empty() {
+        score += &format!(" | {}", hud.status);
+    }
+    for active in snake_game.snake.effects().active() {
+        score += &format!(" | {} {}", active.effect.name(), active.remaining);
+    }
+    stdout
+        .execute(MoveTo(0, snake_rows + 1))?
+        .execute(style::PrintStyledContent(division.dark_grey()))?;
+    let mut info_row = snake_rows + 3;
+    if let Some(rival) = &snake_game.rival {
+        let mut rival_score = format!("Player two: {}", snake_game.rival_score());
+        for active in rival.effects().active() {
+            rival_score += &format!(" | {} {}", active.effect.name(), active.remaining);
+        }
+        stdout
+            .execute(MoveTo(0, info_row))?
+            .execute(style::PrintStyledContent(rival_score.blue()))?;
+        info_row += 1;
+    }
+    stdout
+        .execute(MoveTo(0, info_row))?
+        .execute(style::PrintStyledContent(info_text.dark_grey()))?;
+    stdout
+        .execute(MoveTo(0, snake_rows + 2))?
+        .execute(style::PrintStyledContent(score.cyan()))?;
+    stdout.flush()?;
+    Ok(())
+}
+
+/// Draws the snake of a [`Ghost`] dimmed on the layer of the head of the player, below everything else.
+fn draw_ghost(stdout: &mut Stdout, snake_game: &SnakeGame, ghost: &Snake) -> io::Result<()> {
+    let layer = snake_game.snake.body[0].z;
+    for (i, point) in ghost.body.iter().enumerate() {
+        if point.z != layer {
+            continue;
+        }
+        let ch = if i == 0 { '@' } else { '.' };
+        stdout
+            .execute(cell(snake_game, point.x, point.y))?
+            .execute(style::PrintStyledContent(ch.dark_grey()))?;
+    }
+    Ok(())
+}
+
+/// How the player is doing against the [`Ghost`], for the info bar.
+fn ghost_info(ghost: &Ghost, snake_game: &SnakeGame) -> String {
+    let lead = match ghost.lead(snake_game) {
+        0 => "Tied with".to_string(),
+        lead if lead > 0 => format!("{lead} ahead of"),
+        lead => format!("{} behind", -lead),
+    };
+    let crashed = if ghost.game().get_state().is_finished() {
+        ", it crashed"
+    } else {
+        ""
+    };
+    format!(
+        "{lead} the ghost of your best run ({}{crashed})",
+        ghost.recording().score
+    )
+}
+
+/// Draws the body points of `snake` on `layer`, or with `ghosted` the ones on the layers above and below it. <br>
+/// The [rival](`SnakeGame::rival`) is drawn in blue.
+fn draw_snake(
+    stdout: &mut Stdout,
+    snake_game: &SnakeGame,
+    snake: &Snake,
+    layer: i16,
+    ghosted: bool,
+) -> io::Result<()> {
+    let is_rival = snake_game
+        .rival
+        .as_ref()
+        .is_some_and(|rival| std::ptr::eq(rival, snake));
+    for i in 0..snake.body.len() {
+        let current = &snake.body[i];
+        if !on_layer(current.z, layer, ghosted) {
+            continue;
+        }
+        let ch = if i == 0 {
+            match snake.get_direction() {
+                SnakeDirection::Up => 'v',
+                SnakeDirection::Down => '^',
+                SnakeDirection::Left => '<',
+                SnakeDirection::Right => '>',
+                SnakeDirection::UpRight | SnakeDirection::DownLeft => '\\',
+                SnakeDirection::UpLeft | SnakeDirection::DownRight => '/',
+                SnakeDirection::Ascend | SnakeDirection::Descend => '@',
+            }
+        } else {
+            let prev = &snake.body[i - 1];
+            match snake_game.topology().direction_between(prev, current) {
+                Some(SnakeDirection::Up | SnakeDirection::Down) => '|',
+                Some(SnakeDirection::Left | SnakeDirection::Right) => '-',
+                Some(SnakeDirection::UpRight | SnakeDirection::DownLeft) => '\\',
+                Some(SnakeDirection::UpLeft | SnakeDirection::DownRight) => '/',
+                Some(SnakeDirection::Ascend | SnakeDirection::Descend) => ':',
+                None => 's',
+            }
+        };
+
+        let faded = snake.effects().is_active(StatusEffect::Ghost);
+        let ch = match (ghosted, is_rival, faded) {
+            (true, _, _) => ch.dark_grey(),
+            (false, false, true) => ch.dark_green(),
+            (false, false, false) => ch.green(),
+            (false, true, true) => ch.dark_blue(),
+            (false, true, false) => ch.blue(),
+        };
+        stdout
+            .execute(cell(snake_game, current.x, current.y))?
+            .execute(style::PrintStyledContent(ch))?;
+    }
+    Ok(())
+}
+
+/// Draws the entities on `layer`, or with `ghosted` the ones on the layers above and below it.
+fn draw_entities(
+    stdout: &mut Stdout,
+    snake_game: &SnakeGame,
+    layer: i16,
+    ghosted: bool,
+) -> io::Result<()> {
+    for entity in &snake_game.entities {
+        if !on_layer(entity.z(), layer, ghosted) {
+            continue;
+        }
+        // Entities of types nobody registered still show up, as they always did
+        let (glyph, color) = match snake_game.entity_kind(entity.as_ref()) {
+            Some(kind) => (kind.glyph, kind.color),
+            None => ('o', EntityColor::Red),
+        };
+        let ch = glyph.to_string().with(terminal_color(color));
+        let ch = if ghosted { ch.dark_grey() } else { ch };
+        stdout
+            .execute(cell(snake_game, entity.x(), entity.y()))?
+            .execute(style::PrintStyledContent(ch))?;
+    }
+    Ok(())
+}
+
+fn terminal_color(color: EntityColor) -> style::Color {
+    match color {
+        EntityColor::Red => style::Color::Red,
+        EntityColor::Green => style::Color::Green,
+        EntityColor::Yellow => style::Color::Yellow,
+        EntityColor::Blue => style::Color::Blue,
+        EntityColor::Magenta => style::Color::Magenta,
+        EntityColor::Cyan => style::Color::Cyan,
+        EntityColor::White => style::Color::White,
+        EntityColor::Grey => style::Color::Grey,
+    }
+}
+
+fn on_layer(z: i16, layer: i16, ghosted: bool) -> bool {
+    if ghosted {
+        (z - layer).abs() == 1
+    } else {
+        z == layer
+    }
+}
+
+/// Where the cell at `x` and `y` is drawn, hex boards take two characters per cell with odd rows shifted by one.
+fn cell(snake_game: &SnakeGame, x: i16, y: i16) -> MoveTo {
+    match snake_game.topology() {
+        Topology::Square => MoveTo(x as u16, y as u16),
+        Topology::Hex => MoveTo((x * 2 + (y & 1)) as u16, y as u16),
+    }
+}
+
+/// Characters taken by a row of the board.
+fn board_width(snake_game: &SnakeGame) -> u16 {
+    match snake_game.topology() {
+        Topology::Square => snake_game.columns as u16,
+        Topology::Hex => snake_game.columns as u16 * 2 + 1,
+    }
+}
+
+/// Columns of a board that fits in `cols` characters.
+fn board_columns(topology: Topology, cols: u16) -> i16 {
+    match topology {
+        Topology::Square => cols as i16,
+        Topology::Hex => (cols as i16 - 1) / 2,
+    }
+}
+
+/// Whether the diagonal directions are available and steered with `qweadzxc` and the numpad.
+fn steers_with_letters(snake_game: &SnakeGame) -> bool {
+    snake_game.snake.movement() == Movement::EightWay || snake_game.topology() == Topology::Hex
+}
+
+fn handle_input(snake_game: &mut SnakeGame, timeout: Duration) -> io::Result<InputAction> {
+    if event::poll(timeout)?
+        && let Event::Key(KeyEvent {
+            code, modifiers, ..
+        }) = event::read()?
+    {
+        let eight_way = steers_with_letters(snake_game);
+        match code {
+            KeyCode::Esc => return Ok(InputAction::Quit),
+            KeyCode::Char('q') if !eight_way => return Ok(InputAction::Quit),
+            KeyCode::Char('c') if modifiers.contains(KeyModifiers::CONTROL) => {
+                return Ok(InputAction::Quit);
+            }
+            // Player two steers with the arrows on versus games
+            KeyCode::Left | KeyCode::Right | KeyCode::Up | KeyCode::Down => {
+                let direction = match code {
+                    KeyCode::Left => SnakeDirection::Left,
+                    KeyCode::Right => SnakeDirection::Right,
+                    KeyCode::Up => SnakeDirection::Down,
+                    _ => SnakeDirection::Up,
+                };
+                match &mut snake_game.rival {
+                    Some(rival) => rival.set_direction(direction),
+                    None => snake_game.steer(direction),
+                }
+            }
+            KeyCode::Char(key @ ('w' | 'a' | 's' | 'd')) if snake_game.rival.is_some() => {
+                snake_game.steer(match key {
+                    'w' => SnakeDirection::Down,
+                    'a' => SnakeDirection::Left,
+                    's' => SnakeDirection::Up,
+                    _ => SnakeDirection::Right,
+                });
+            }
+            KeyCode::PageUp | KeyCode::Char('r') => {
+                snake_game.steer(SnakeDirection::Ascend);
+            }
+            KeyCode::PageDown | KeyCode::Char('f') => {
+                snake_game.steer(SnakeDirection::Descend);
+            }
+            // The board is drawn upside down, screen up is SnakeDirection::Down
+            KeyCode::Char(key) if eight_way => {
+                let direction = match key {
+                    'q' | '7' => Some(SnakeDirection::DownLeft),
+                    'w' | '8' => Some(SnakeDirection::Down),
+                    'e' | '9' => Some(SnakeDirection::DownRight),
+                    'a' | '4' => Some(SnakeDirection::Left),
+                    'd' | '6' => Some(SnakeDirection::Right),
+                    'z' | '1' => Some(SnakeDirection::UpLeft),
+                    'x' | '2' => Some(SnakeDirection::Up),
+                    'c' | '3' => Some(SnakeDirection::UpRight),
+                    _ => None,
+                };
+                match direction {
+                    Some(direction) => snake_game.steer(direction),
+                    None => return handle_command(snake_game, key),
+                }
+            }
+            KeyCode::Char(key) => return handle_command(snake_game, key),
+            _ => {}
+        }
+    }
+    Ok(InputAction::Continue)
+}
+
+/// Keys that are not used to steer the snake.
+fn handle_command(snake_game: &mut SnakeGame, key: char) -> io::Result<InputAction> {
+    match key {
+        'p' => match snake_game.get_state() {
+            GameState::Playing | GameState::Countdown => snake_game.set_state(GameState::Paused),
+            GameState::Paused => snake_game.set_state(GameState::Countdown),
+            _ => {}
+        },
+        'y' if snake_game.get_state().is_finished() => return Ok(InputAction::Restart),
+        _ => {}
+    }
+    Ok(InputAction::Continue)
+}
+
+fn game_logic(snake_game: &mut SnakeGame, spawn: &dyn Fn(&mut SnakeGame)) -> io::Result<()> {
+    let ticks = snake_game.ticks();
+    // What the snakes eat is handled by the plugins, like AppleGrowth
+    snake_game.tick();
+    // Only after the snake moved, so seeded games spawn the same no matter the pauses and countdowns
+    if snake_game.ticks() > ticks && snake_game.get_state() == GameState::Playing {
+        spawn(snake_game);
+    }
+    Ok(())
+}
+
+fn get_terminal_size(stdout: &mut Stdout) -> (u16, u16) {
+    let (cols, rows) = terminal::size().unwrap_or((0, 0));
+    if cols < 84 || rows < 24 {
+        let _ = disable_game_mode(stdout);
+        println!("\n*****\n");
+        println!("You should have a minimum 84x28 terminal size in terms of rows and columns");
+        println!("but you have {rows} rows and {cols} columns");
+        println!("please resize your terminal and try again");
+        println!("\n*****\n");
+        std::process::exit(1);
+    } else {
+        let cols = cols - 1; // Collision does not have to wait an extra frame
+        let rows = rows - 4; // Space for text
+        (cols, rows)
+    }
+}
+
+fn clear_terminal(stdout: &mut Stdout) -> io::Result<()> {
+    stdout
+        .execute(Clear(ClearType::All))?
+        .execute(Clear(ClearType::Purge))?
+        .execute(MoveTo(0, 0))?;
+    Ok(())
+}
+
+fn enable_game_mode(stdout: &mut Stdout) -> io::Result<()> {
+    // Prevents input to be forwaded to the screen but also disables Ctrl+C
+    terminal::enable_raw_mode()?;
+    // Hide the cursor
+    stdout.execute(Hide)?;
+    // Full clear
+    stdout
+        .execute(Clear(ClearType::All))?
+        .execute(Clear(ClearType::Purge))?;
+    Ok(())
+}
+
+fn disable_game_mode(stdout: &mut Stdout) -> io::Result<()> {
+    // Enable normal input again
+    terminal::disable_raw_mode()?;
+    // Show cursor again
+    stdout.execute(Show)?;
+    // Clear terminal screen
+    clear_terminal(stdout)?;
+    Ok(())
+}
@@ -0,0 +1,212 @@
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyModifiers};
+use snake3::SnakeGame;
+use snake3::net::{Client, Join, Seat, Server, ServerMessage};
+use snake3::snake::{GameResult, Player, SnakeDirection};
+use std::io::{self, Stdout};
+use std::time::Duration;
+
+use super::{Hud, clear_frame, clear_terminal, disable_game_mode, draw_frame, enable_game_mode};
+
+const DEFAULT_PORT: u16 = 7070;
+/// Where `snake3 watch` looks for a game started with `--broadcast` by default.
+const DEFAULT_BROADCAST_PORT: u16 = 7071;
+const BOARD_COLUMNS: i16 = 60;
+const BOARD_ROWS: i16 = 18;
+const TICK_MS: u64 = 150;
+const FRAME_MS: u64 = 16;
+
+/// Runs `snake3 host [--port <port>] [--players 1|2]`, a server without a screen.
+pub fn host(args: &[String]) -> io::Result<()> {
+    let mut port = DEFAULT_PORT;
+    let mut players = 2;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--port" | "-p" => match args.next().map(|port| port.parse::<u16>()) {
+                Some(Ok(value)) => port = value,
+                _ => usage("The port must be a number."),
+            },
+            "--players" => match args.next().map(String::as_str) {
+                Some("1") => players = 1,
+                Some("2") => players = 2,
+                _ => usage("A game is for 1 or 2 players."),
+            },
+            other => usage(&format!("Unknown argument '{other}'.")),
+        }
+    }
+    let mut game = SnakeGame::new(
+        BOARD_COLUMNS,
+        BOARD_ROWS,
+        Some(SnakeDirection::Right),
+        Some((BOARD_COLUMNS / 4, BOARD_ROWS / 2)),
+    );
+    if players == 2 {
+        game.add_rival(
+            (BOARD_COLUMNS * 3 / 4, BOARD_ROWS / 2),
+            SnakeDirection::Left,
+        );
+    }
+    let server =
+        Server::bind(("0.0.0.0", port), game)?.with_interval(Duration::from_millis(TICK_MS));
+    println!(
+        "Hosting a {players} player game on {}, waiting for players...",
+        server.local_addr()?
+    );
+    let ending = match server.run()? {
+        Some(GameResult::Won) => "Player one won",
+        Some(GameResult::RivalDied(_)) => "Player one won the round",
+        Some(GameResult::Died(_)) if players == 2 => "Player two won the round",
+        Some(GameResult::Died(_)) => "Player one died",
+        Some(GameResult::Draw) => "It was a draw",
+        Some(GameResult::Aborted) | None => "Everyone left",
+    };
+    println!("Game over: {ending}.");
+    Ok(())
+}
+
+/// Runs `snake3 join <address> [--watch]`, drawing the game of a server and sending the keys pressed.
+pub fn join(stdout: &mut Stdout, args: &[String]) -> io::Result<()> {
+    let Some(address) = args.first() else {
+        usage("Missing the address of the server.");
+    };
+    let join = match args.get(1).map(String::as_str) {
+        Some("--watch" | "-w") => Join::Watch,
+        Some(other) => usage(&format!("Unknown argument '{other}'.")),
+        None => Join::Play,
+    };
+    let client = Client::connect(with_port(address, DEFAULT_PORT).as_str(), join)?;
+    follow(stdout, client, "Waiting for the other player")
+}
+
+/// Runs `snake3 watch [<address>]`, drawing a game started with `--broadcast` without touching it.
+pub fn watch(stdout: &mut Stdout, args: &[String]) -> io::Result<()> {
+    let address = match args {
+        [] => format!("127.0.0.1:{DEFAULT_BROADCAST_PORT}"),
+        [address] => with_port(address, DEFAULT_BROADCAST_PORT),
+        _ => usage("watch only takes the address of the game."),
+    };
+    let client = Client::connect(address.as_str(), Join::Watch)?;
+    follow(stdout, client, "Read only")
+}
+
+fn with_port(address: &str, port: u16) -> String {
+    if address.contains(':') {
+        address.to_string()
+    } else {
+        format!("{address}:{port}")
+    }
+}
+
+/// Draws the game the `client` receives until the player quits, `news` is shown until something happens.
+fn follow(stdout: &mut Stdout, mut client: Client, news: &str) -> io::Result<()> {
+    enable_game_mode(stdout)?;
+    clear_terminal(stdout)?;
+    let result = render(stdout, &mut client, news);
+    disable_game_mode(stdout)?;
+    result
+}
+
+fn render(stdout: &mut Stdout, client: &mut Client, news: &str) -> io::Result<()> {
+    let seat = client.seat();
+    let mut game: Option<SnakeGame> = None;
+    let mut tick = 0;
+    let mut next_direction = None;
+    let mut news = news.to_string();
+    let mut over = false;
+    loop {
+        // Everything the server sent since the last frame
+        while let Some(message) = receive(client, &mut over)? {
+            match message {
+                ServerMessage::State(snapshot) => {
+                    tick = snapshot.tick;
+                    let previous = game.replace(snapshot.to_game());
+                    if previous.is_some_and(|previous| previous.rival.is_some())
+                        != game.as_ref().is_some_and(|game| game.rival.is_some())
+                    {
+                        clear_terminal(stdout)?;
+                    }
+                    if let (Seat::Player(player), Some(game)) = (seat, &game) {
+                        let snake = match player {
+                            Player::One => Some(&game.snake),
+                            Player::Two => game.rival.as_ref(),
+                        };
+                        // Lockstep: the server waits for everyone's input before each tick
+                        if let Some(snake) = snake {
+                            let direction = next_direction.take().unwrap_or(snake.get_direction());
+                            client.send_input(tick + 1, direction)?;
+                        }
+                    }
+                }
+                ServerMessage::Joined(player) => news = format!("{} joined", name(player)),
+                ServerMessage::Left(player) => news = format!("{} left", name(player)),
+                ServerMessage::Error(error) => news = error,
+                ServerMessage::Bye => over = true,
+                ServerMessage::Welcome { .. } => {}
+            }
+        }
+
+        if let Some(game) = &game {
+            clear_frame(stdout, game)?;
+            let status = match seat {
+                Seat::Player(player) => format!("You are {} | {news}", name(player)),
+                Seat::Spectator => format!("Watching | {news}"),
+            };
+            let hud = Hud {
+                status,
+                end_prompt: "The game is over, press <q> to exit".to_string(),
+                info: String::new(),
+            };
+            draw_frame(stdout, game, &hud)?;
+        }
+
+        if event::poll(Duration::from_millis(FRAME_MS))?
+            && let Event::Key(KeyEvent {
+                code, modifiers, ..
+            }) = event::read()?
+        {
+            // The board is drawn upside down, screen up is SnakeDirection::Down
+            let direction = match code {
+                KeyCode::Esc | KeyCode::Char('q') => return Ok(()),
+                KeyCode::Char('c') if modifiers.contains(KeyModifiers::CONTROL) => return Ok(()),
+                KeyCode::Up | KeyCode::Char('w') => SnakeDirection::Down,
+                KeyCode::Down | KeyCode::Char('s') => SnakeDirection::Up,
+                KeyCode::Left | KeyCode::Char('a') => SnakeDirection::Left,
+                KeyCode::Right | KeyCode::Char('d') => SnakeDirection::Right,
+                _ => continue,
+            };
+            if matches!(seat, Seat::Player(_)) && !over {
+                next_direction = Some(direction);
+                client.send_input(tick + 1, direction)?;
+            }
+        }
+    }
+}
+
+/// The next message of the server if there is one, the connection closing ends the game.
+fn receive(client: &mut Client, over: &mut bool) -> io::Result<Option<ServerMessage>> {
+    if *over {
+        return Ok(None);
+    }
+    match client.recv_timeout(Duration::from_millis(1)) {
+        Err(error) if error.kind() == io::ErrorKind::UnexpectedEof => {
+            *over = true;
+            Ok(None)
+        }
+        other => other,
+    }
+}
+
+fn name(player: Player) -> &'static str {
+    match player {
+        Player::One => "player one",
+        Player::Two => "player two",
+    }
+}
+
+fn usage(error: &str) -> ! {
+    eprintln!("{error}\n");
+    eprintln!("Usage: snake3 host [--port <port>] [--players 1|2]");
+    eprintln!("       snake3 join <address> [--watch]");
+    eprintln!("       snake3 watch [<address>]");
+    std::process::exit(2);
+}
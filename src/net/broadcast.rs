@@ -0,0 +1,231 @@
+use std::io::{self, Read, Write};
+use std::net::{Shutdown, SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::time::{Duration, Instant};
+
+use super::{ClientMessage, Join, PROTOCOL_VERSION, Seat, ServerMessage, Snapshot};
+use crate::SnakeGame;
+
+/// Watchers that can't keep up are dropped instead of slowing down the game.
+const WRITE_TIMEOUT: Duration = Duration::from_millis(100);
+const MAX_HELLO_LENGTH: usize = 64;
+/// Connections that don't finish their hello in time are dropped.
+const HELLO_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Publishes every frame of a game played somewhere else, like on the terminal, to read-only watchers. <br>
+/// Unlike a [`Server`](`super::Server`) it does not run the game and reads nothing but the hello of
+/// its clients, it speaks the same protocol so watchers connect with a [`Client`](`super::Client`)
+/// and [`Join::Watch`](`super::Join::Watch`). Hellos are read a bit on every [`Broadcaster::publish`],
+/// so a slow or silent client never holds the frame.
+/// # Examples
+/// ```no_run
+/// use snake3::{GameState, SnakeGame};
+/// use snake3::net::Broadcaster;
+/// let mut game = SnakeGame::new(10, 10, None, None);
+/// let mut broadcaster = Broadcaster::bind("127.0.0.1:7071").unwrap();
+/// game.set_state(GameState::Playing);
+/// while !game.get_state().is_finished() {
+///     game.tick();
+///     broadcaster.publish(&game).unwrap();
+/// }
+/// ```
+pub struct Broadcaster {
+    listener: TcpListener,
+    watchers: Vec<TcpStream>,
+    /// Connections still sending their hello.
+    pending: Vec<Hello>,
+    last_frame: Option<String>,
+}
+
+/// The hello of a connection, read as its bytes arrive.
+struct Hello {
+    stream: TcpStream,
+    line: Vec<u8>,
+    since: Instant,
+}
+
+impl Hello {
+    /// Reads what already arrived, `None` until the whole line is there. Nothing after the line is read.
+    fn read(&mut self) -> io::Result<Option<ClientMessage>> {
+        let mut byte = [0];
+        while self.line.len() < MAX_HELLO_LENGTH {
+            match self.stream.read(&mut byte) {
+                Ok(0) => return Err(io::ErrorKind::UnexpectedEof.into()),
+                Ok(_) if byte[0] == b'\n' => break,
+                Ok(_) => self.line.push(byte[0]),
+                Err(error) if error.kind() == io::ErrorKind::WouldBlock => return Ok(None),
+                Err(error) if error.kind() == io::ErrorKind::Interrupted => {}
+                Err(error) => return Err(error),
+            }
+        }
+        String::from_utf8_lossy(&self.line)
+            .parse()
+            .map(Some)
+            .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))
+    }
+}
+
+impl Broadcaster {
+    /// Listens on `address` for watchers, usually a port on `127.0.0.1`.
+    pub fn bind<A: ToSocketAddrs>(address: A) -> io::Result<Self> {
+        let listener = TcpListener::bind(address)?;
+        listener.set_nonblocking(true)?;
+        Ok(Broadcaster {
+            listener,
+            watchers: Vec::new(),
+            pending: Vec::new(),
+            last_frame: None,
+        })
+    }
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.listener.local_addr()
+    }
+    /// How many watchers are connected.
+    pub fn watchers(&self) -> usize {
+        self.watchers.len()
+    }
+    /// Welcomes the new watchers and sends everyone the state of `game` if it changed since the last call,
+    /// so it can be called on every frame of the game loop.
+    pub fn publish(&mut self, game: &SnakeGame) -> io::Result<()> {
+        self.accept()?;
+        let frame = format!("{}\n", ServerMessage::State(Snapshot::of(game)));
+        if self.last_frame.as_ref() != Some(&frame) {
+            self.send(&frame);
+            self.last_frame = Some(frame);
+        }
+        Ok(())
+    }
+    fn accept(&mut self) -> io::Result<()> {
+        loop {
+            match self.listener.accept() {
+                Ok((stream, _)) => {
+                    stream.set_nonblocking(true)?;
+                    self.pending.push(Hello {
+                        stream,
+                        line: Vec::new(),
+                        since: Instant::now(),
+                    });
+                }
+                Err(error) if error.kind() == io::ErrorKind::WouldBlock => break,
+                Err(error) => return Err(error),
+            }
+        }
+        for mut hello in std::mem::take(&mut self.pending) {
+            match hello.read() {
+                Ok(Some(message)) => self.welcome(hello.stream, message),
+                Ok(None) if hello.since.elapsed() < HELLO_TIMEOUT => self.pending.push(hello),
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+    fn welcome(&mut self, mut stream: TcpStream, hello: ClientMessage) {
+        let ready = stream
+            .set_nonblocking(false)
+            .and_then(|_| stream.set_write_timeout(Some(WRITE_TIMEOUT)));
+        if ready.is_err() {
+            return;
+        }
+        let ClientMessage::Hello {
+            version: PROTOCOL_VERSION,
+            join: Join::Watch,
+        } = hello
+        else {
+            let refusal = ServerMessage::Error(format!(
+                "Only watchers speaking version {PROTOCOL_VERSION} are welcome."
+            ));
+            let _ = writeln!(stream, "{refusal}");
+            return;
+        };
+        let welcome = ServerMessage::Welcome {
+            version: PROTOCOL_VERSION,
+            seat: Seat::Spectator,
+        };
+        // Late watchers start with the last frame instead of waiting for the next change
+        let sent = writeln!(stream, "{welcome}").and_then(|_| match &self.last_frame {
+            Some(frame) => stream.write_all(frame.as_bytes()),
+            None => Ok(()),
+        });
+        if sent.is_ok() {
+            self.watchers.push(stream);
+        }
+    }
+    fn send(&mut self, line: &str) {
+        self.watchers
+            .retain_mut(|watcher| watcher.write_all(line.as_bytes()).is_ok());
+    }
+}
+
+impl Drop for Broadcaster {
+    fn drop(&mut self) {
+        self.send(&format!("{}\n", ServerMessage::Bye));
+        for watcher in &self.watchers {
+            let _ = watcher.shutdown(Shutdown::Both);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+
+    use super::*;
+    use crate::GameState;
+    use crate::net::{Client, Join};
+
+    #[test]
+    fn broadcaster_publishes_frames_to_watchers() {
+        let mut game = SnakeGame::new(10, 10, None, None);
+        let mut broadcaster = Broadcaster::bind("127.0.0.1:0").unwrap();
+        let address = broadcaster.local_addr().unwrap();
+        broadcaster.publish(&game).unwrap();
+        let watcher = thread::spawn(move || {
+            let mut client = Client::connect(address, Join::Watch).unwrap();
+            let mut ticks = Vec::new();
+            while let Some(message) = client.recv_timeout(Duration::from_secs(5)).unwrap() {
+                match message {
+                    ServerMessage::State(snapshot) => ticks.push(snapshot.tick),
+                    ServerMessage::Bye => break,
+                    _ => {}
+                }
+            }
+            ticks
+        });
+        while broadcaster.watchers() == 0 {
+            broadcaster.publish(&game).unwrap();
+            thread::sleep(Duration::from_millis(1));
+        }
+        game.set_state(GameState::Playing);
+        for _ in 0..3 {
+            game.tick();
+            broadcaster.publish(&game).unwrap();
+            // Frames that did not change are not sent again
+            broadcaster.publish(&game).unwrap();
+        }
+        drop(broadcaster);
+        assert_eq!(watcher.join().unwrap(), vec![0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn broadcaster_does_not_wait_for_slow_hellos() {
+        let game = SnakeGame::new(10, 10, None, None);
+        let mut broadcaster = Broadcaster::bind("127.0.0.1:0").unwrap();
+        let address = broadcaster.local_addr().unwrap();
+        let _silent = TcpStream::connect(address).unwrap();
+        let mut slow = TcpStream::connect(address).unwrap();
+        write!(slow, "HELLO {PROTOCOL_VERSION} wa").unwrap();
+        let started = Instant::now();
+        for _ in 0..20 {
+            broadcaster.publish(&game).unwrap();
+        }
+        assert!(started.elapsed() < Duration::from_millis(100));
+        assert_eq!(broadcaster.watchers(), 0);
+        writeln!(slow, "tch").unwrap();
+        let started = Instant::now();
+        while broadcaster.watchers() == 0 && started.elapsed() < Duration::from_secs(5) {
+            broadcaster.publish(&game).unwrap();
+            thread::sleep(Duration::from_millis(1));
+        }
+        assert_eq!(broadcaster.watchers(), 1);
+        assert_eq!(broadcaster.pending.len(), 1);
+    }
+}
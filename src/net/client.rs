@@ -0,0 +1,103 @@
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::time::Duration;
+
+use super::{ClientMessage, Join, PROTOCOL_VERSION, Seat, ServerMessage};
+use crate::snake::SnakeDirection;
+
+/// How long [`Client::connect`] waits for the server to answer.
+const HELLO_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Connection to a [`Server`](`super::Server`), as a player or a spectator.
+/// # Examples
+/// ```no_run
+/// use std::time::Duration;
+/// use snake3::net::{Client, Join, ServerMessage};
+/// let mut client = Client::connect("127.0.0.1:7070", Join::Watch).unwrap();
+/// while let Ok(Some(message)) = client.recv_timeout(Duration::from_secs(1)) {
+///     if let ServerMessage::State(snapshot) = message {
+///         let game = snapshot.to_game();
+///         // Draw the game
+///     }
+/// }
+/// ```
+pub struct Client {
+    stream: TcpStream,
+    reader: BufReader<TcpStream>,
+    buffer: Vec<u8>,
+    seat: Seat,
+}
+
+impl Client {
+    /// Says hello to the server at `address`, failing if it turns the client away.
+    pub fn connect<A: ToSocketAddrs>(address: A, join: Join) -> io::Result<Self> {
+        let stream = TcpStream::connect(address)?;
+        let mut client = Client {
+            reader: BufReader::new(stream.try_clone()?),
+            stream,
+            buffer: Vec::new(),
+            seat: Seat::Spectator,
+        };
+        client.send(&ClientMessage::Hello {
+            version: PROTOCOL_VERSION,
+            join,
+        })?;
+        match client.recv_timeout(HELLO_TIMEOUT)? {
+            Some(ServerMessage::Welcome { seat, .. }) => {
+                client.seat = seat;
+                Ok(client)
+            }
+            Some(ServerMessage::Error(error)) => {
+                Err(io::Error::new(io::ErrorKind::ConnectionRefused, error))
+            }
+            Some(other) => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("Expected a welcome, got '{other}'."),
+            )),
+            None => Err(io::ErrorKind::TimedOut.into()),
+        }
+    }
+    pub fn seat(&self) -> Seat {
+        self.seat
+    }
+    pub fn send(&mut self, message: &ClientMessage) -> io::Result<()> {
+        writeln!(self.stream, "{message}")
+    }
+    /// Asks the server to turn the snake of the player on `tick`, usually the one after the last [`Snapshot`](`super::Snapshot`).
+    pub fn send_input(&mut self, tick: u64, direction: SnakeDirection) -> io::Result<()> {
+        self.send(&ClientMessage::Input { tick, direction })
+    }
+    /// Waits up to `timeout` for a message of the server, `None` if there was none. <br>
+    /// Fails with [`io::ErrorKind::UnexpectedEof`] once the server closes the connection.
+    pub fn recv_timeout(&mut self, timeout: Duration) -> io::Result<Option<ServerMessage>> {
+        self.stream.set_read_timeout(Some(timeout))?;
+        // Whatever was read before a timeout stays in the buffer for the next call
+        match self.reader.read_until(b'\n', &mut self.buffer) {
+            Ok(0) => return Err(io::ErrorKind::UnexpectedEof.into()),
+            Ok(_) if !self.buffer.ends_with(b"\n") => {
+                return Err(io::ErrorKind::UnexpectedEof.into());
+            }
+            Ok(_) => {}
+            Err(error)
+                if matches!(
+                    error.kind(),
+                    io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut
+                ) =>
+            {
+                return Ok(None);
+            }
+            Err(error) => return Err(error),
+        }
+        let line = String::from_utf8_lossy(&self.buffer).into_owned();
+        self.buffer.clear();
+        line.parse()
+            .map(Some)
+            .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))
+    }
+}
+
+impl Drop for Client {
+    fn drop(&mut self) {
+        let _ = self.send(&ClientMessage::Bye);
+    }
+}
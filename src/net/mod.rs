@@ -0,0 +1,17 @@
+//! Multiplayer over TCP: a [`Server`] simulates the game and [`Client`]s play or watch it. <br>
+//! The protocol is made of text lines, one per message, see [`ClientMessage`] and [`ServerMessage`].
+//! Clients start with a hello carrying the [`PROTOCOL_VERSION`], then players send their inputs
+//! for upcoming ticks and everyone gets a [`Snapshot`] of the game after every tick. <br>
+//! A [`Broadcaster`] publishes a game running somewhere else to watchers speaking the same protocol.
+pub mod broadcast;
+pub mod client;
+pub mod protocol;
+pub mod server;
+
+pub use broadcast::Broadcaster;
+pub use client::Client;
+pub use protocol::{
+    ClientMessage, EntitySnapshot, Join, PROTOCOL_VERSION, ProtocolError, RemoteEntity, Seat,
+    ServerMessage, SnakeSnapshot, Snapshot,
+};
+pub use server::Server;
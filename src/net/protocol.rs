@@ -0,0 +1,809 @@
+use std::any::Any;
+use std::collections::HashSet;
+use std::{fmt, str::FromStr};
+
+use crate::SnakeGame;
+use crate::snake::{
+    Apple, DeathCause, Entity, GameResult, GameState, Player, PortalPair, PowerUp, Snake,
+    SnakeBodyPoint, SnakeDirection, StatusEffect, Topology,
+};
+
+/// Version of the wire protocol, clients speaking another one are turned away on [`ClientMessage::Hello`].
+pub const PROTOCOL_VERSION: u32 = 2;
+
+/// Returned when a line of the protocol can't be parsed.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ProtocolError(pub String);
+
+impl fmt::Display for ProtocolError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for ProtocolError {}
+
+/// What a client wants to do on the server.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum Join {
+    /// Take the seat of one of the snakes.
+    Play,
+    /// Only receive the state of the game.
+    Watch,
+}
+
+/// What a client got on the server.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum Seat {
+    Player(Player),
+    Spectator,
+}
+
+/// Messages sent by clients, one per line.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum ClientMessage {
+    /// First message of every connection.
+    Hello { version: u32, join: Join },
+    /// Direction of the snake for the tick `tick`, see [`Snapshot::tick`].
+    Input {
+        tick: u64,
+        direction: SnakeDirection,
+    },
+    /// The client is leaving.
+    Bye,
+}
+
+/// Messages sent by the server, one per line.
+#[derive(Debug, PartialEq, Clone)]
+pub enum ServerMessage {
+    /// Answer to a valid [`ClientMessage::Hello`].
+    Welcome { version: u32, seat: Seat },
+    /// Something went wrong, the server closes the connection after a failed hello.
+    Error(String),
+    /// A player took their seat.
+    Joined(Player),
+    /// A player disconnected, their snake keeps going in its last direction.
+    Left(Player),
+    /// The authoritative state of the game, sent after every tick.
+    State(Snapshot),
+    /// The game is over and the server is closing.
+    Bye,
+}
+
+/// A snake as seen on a [`Snapshot`].
+#[derive(Debug, PartialEq, Clone)]
+pub struct SnakeSnapshot {
+    pub player: Player,
+    pub score: u64,
+    pub direction: SnakeDirection,
+    pub body: Vec<SnakeBodyPoint>,
+}
+
+/// An entity as seen on a [`Snapshot`].
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct EntitySnapshot {
+    /// The [name](`Entity::name`) of the entity.
+    pub name: String,
+    pub x: i16,
+    pub y: i16,
+    pub z: i16,
+    /// The [effect](`Entity::effect`) of the entity and its duration.
+    pub effect: Option<(StatusEffect, u32)>,
+}
+
+/// Stand-in for the entities of a [`Snapshot`] that are not apples or power-ups. <br>
+/// It takes the name of the [kind](`crate::snake::EntityKind`) registered for it on the client game,
+/// so renderers can still find its glyph, or `RemoteEntity` if the client does not know about it.
+#[derive(Debug, Copy, Clone)]
+pub struct RemoteEntity {
+    pub x: i16,
+    pub y: i16,
+    pub z: i16,
+    pub name: &'static str,
+}
+
+impl Entity for RemoteEntity {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn x(&self) -> i16 {
+        self.x
+    }
+
+    fn y(&self) -> i16 {
+        self.y
+    }
+
+    fn z(&self) -> i16 {
+        self.z
+    }
+
+    fn set_position(&mut self, x: i16, y: i16) {
+        self.x = x;
+        self.y = y;
+    }
+
+    fn set_z(&mut self, z: i16) {
+        self.z = z;
+    }
+
+    fn name(&self) -> &'static str {
+        self.name
+    }
+}
+
+/// Everything a client needs to draw a game simulated on the server.
+/// # Examples
+/// ```
+/// # use snake3::{SnakeGame, GameState};
+/// use snake3::net::{ServerMessage, Snapshot};
+/// let mut game = SnakeGame::new(10, 10, None, None);
+/// game.set_state(GameState::Playing);
+/// game.tick();
+/// let line = ServerMessage::State(Snapshot::of(&game)).to_string();
+/// let Ok(ServerMessage::State(snapshot)) = line.parse() else { panic!() };
+/// assert_eq!(snapshot.to_game().snake.body, game.snake.body);
+/// ```
+#[derive(Debug, PartialEq, Clone)]
+pub struct Snapshot {
+    /// Every tick counted so far, see [`SnakeGame::total_ticks`].
+    pub tick: u64,
+    pub columns: i16,
+    pub rows: i16,
+    pub state: GameState,
+    pub result: Option<GameResult>,
+    pub countdown_left: u64,
+    pub topology: Topology,
+    /// See [`SnakeGame::layers`].
+    pub layers: i16,
+    /// Wall cells, sorted.
+    pub walls: Vec<(i16, i16)>,
+    pub portals: Vec<PortalPair>,
+    /// Hazard cells as they are on this tick, sorted.
+    pub hazards: Vec<(i16, i16)>,
+    pub snakes: Vec<SnakeSnapshot>,
+    pub entities: Vec<EntitySnapshot>,
+}
+
+impl Snapshot {
+    pub fn of(game: &SnakeGame) -> Self {
+        let mut snakes = vec![SnakeSnapshot::of(Player::One, &game.snake, game.score())];
+        if let Some(rival) = &game.rival {
+            snakes.push(SnakeSnapshot::of(Player::Two, rival, game.rival_score()));
+        }
+        let sorted = |cells: &HashSet<(i16, i16)>| {
+            let mut cells: Vec<(i16, i16)> = cells.iter().copied().collect();
+            cells.sort_unstable();
+            cells
+        };
+        Snapshot {
+            tick: game.total_ticks(),
+            columns: game.columns,
+            rows: game.rows,
+            state: game.get_state(),
+            result: game.result(),
+            countdown_left: game.countdown_left(),
+            topology: game.topology(),
+            layers: game.layers(),
+            walls: sorted(game.walls()),
+            portals: game.portals().pairs().to_vec(),
+            hazards: sorted(game.hazards().cells()),
+            snakes,
+            entities: game
+                .entities
+                .iter()
+                .map(|entity| EntitySnapshot {
+                    name: entity.name().to_string(),
+                    x: entity.x(),
+                    y: entity.y(),
+                    z: entity.z(),
+                    effect: entity.effect(),
+                })
+                .collect(),
+        }
+    }
+    /// A new game of the size of the snapshot with [`Snapshot::apply_to`] already called.
+    pub fn to_game(&self) -> SnakeGame {
+        let mut game = SnakeGame::new(self.columns, self.rows, None, Some((0, 0)));
+        self.apply_to(&mut game);
+        game
+    }
+    /// Makes `game` look like the snapshot: board, snakes, entities, state and scores. <br>
+    /// Entities are rebuilt as [`Apple`]s, [`PowerUp`]s when they have an effect, or [`RemoteEntity`]
+    /// named after the kind `game` has [registered](`SnakeGame::register_entity`) for them.
+    pub fn apply_to(&self, game: &mut SnakeGame) {
+        game.set_topology(self.topology);
+        game.set_layers(self.layers);
+        game.mirror_board(&self.walls, &self.portals, &self.hazards);
+        let mut scores = (0, 0);
+        for snake in &self.snakes {
+            let target = match snake.player {
+                Player::One => &mut game.snake,
+                Player::Two => game
+                    .rival
+                    .get_or_insert_with(|| Snake::new((0, 0), snake.direction)),
+            };
+            target.body = snake.body.clone();
+            target.face(snake.direction);
+            match snake.player {
+                Player::One => scores.0 = snake.score,
+                Player::Two => scores.1 = snake.score,
+            }
+        }
+        let registry = game.entity_registry();
+        let entities = self
+            .entities
+            .iter()
+            .map(|entity| -> Box<dyn Entity> {
+                match (entity.name.as_str(), entity.effect) {
+                    (_, Some((effect, duration))) => {
+                        let mut power_up = PowerUp::new(entity.x, entity.y, effect, duration);
+                        power_up.z = entity.z;
+                        Box::new(power_up)
+                    }
+                    ("Apple", None) => Box::new(Apple {
+                        x: entity.x,
+                        y: entity.y,
+                        z: entity.z,
+                    }),
+                    (name, None) => Box::new(RemoteEntity {
+                        x: entity.x,
+                        y: entity.y,
+                        z: entity.z,
+                        name: registry
+                            .get_by_name(name)
+                            .map_or("RemoteEntity", |kind| kind.name),
+                    }),
+                }
+            })
+            .collect();
+        game.entities = entities;
+        game.mirror(
+            self.state,
+            self.result,
+            self.tick,
+            self.countdown_left,
+            scores,
+        );
+    }
+}
+
+impl SnakeSnapshot {
+    fn of(player: Player, snake: &Snake, score: u64) -> Self {
+        SnakeSnapshot {
+            player,
+            score,
+            direction: snake.get_direction(),
+            body: snake.body.clone(),
+        }
+    }
+}
+
+impl fmt::Display for ClientMessage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ClientMessage::Hello { version, join } => {
+                let join = match join {
+                    Join::Play => "play",
+                    Join::Watch => "watch",
+                };
+                write!(f, "HELLO {version} {join}")
+            }
+            ClientMessage::Input { tick, direction } => write!(f, "INPUT {tick} {direction}"),
+            ClientMessage::Bye => f.write_str("BYE"),
+        }
+    }
+}
+
+impl FromStr for ClientMessage {
+    type Err = ProtocolError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut tokens = s.split_whitespace();
+        match tokens.next() {
+            Some("HELLO") => {
+                let version = parse(tokens.next(), "version")?;
+                let join = match tokens.next() {
+                    Some("play") => Join::Play,
+                    Some("watch") => Join::Watch,
+                    _ => return Err(error(s)),
+                };
+                Ok(ClientMessage::Hello { version, join })
+            }
+            Some("INPUT") => Ok(ClientMessage::Input {
+                tick: parse(tokens.next(), "tick")?,
+                direction: parse(tokens.next(), "direction")?,
+            }),
+            Some("BYE") => Ok(ClientMessage::Bye),
+            _ => Err(error(s)),
+        }
+    }
+}
+
+impl fmt::Display for ServerMessage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ServerMessage::Welcome { version, seat } => match seat {
+                Seat::Player(player) => {
+                    write!(f, "WELCOME {version} player {}", player_number(*player))
+                }
+                Seat::Spectator => write!(f, "WELCOME {version} spectator"),
+            },
+            ServerMessage::Error(message) => write!(f, "ERROR {}", message.replace('\n', " ")),
+            ServerMessage::Joined(player) => write!(f, "JOINED {}", player_number(*player)),
+            ServerMessage::Left(player) => write!(f, "LEFT {}", player_number(*player)),
+            ServerMessage::State(snapshot) => {
+                write!(
+                    f,
+                    "STATE {} {} {} {} {} {}",
+                    snapshot.tick,
+                    snapshot.columns,
+                    snapshot.rows,
+                    state_name(snapshot.state),
+                    result_name(snapshot.result),
+                    snapshot.countdown_left
+                )?;
+                write!(
+                    f,
+                    " | BOARD {} {}",
+                    topology_name(snapshot.topology),
+                    snapshot.layers
+                )?;
+                if !snapshot.walls.is_empty() {
+                    f.write_str(" | WALLS")?;
+                    for (x, y) in &snapshot.walls {
+                        write!(f, " {x},{y}")?;
+                    }
+                }
+                if !snapshot.portals.is_empty() {
+                    f.write_str(" | PORTALS")?;
+                    for ((ax, ay), (bx, by)) in &snapshot.portals {
+                        write!(f, " {ax},{ay}:{bx},{by}")?;
+                    }
+                }
+                if !snapshot.hazards.is_empty() {
+                    f.write_str(" | HAZARDS")?;
+                    for (x, y) in &snapshot.hazards {
+                        write!(f, " {x},{y}")?;
+                    }
+                }
+                for snake in &snapshot.snakes {
+                    write!(
+                        f,
+                        " | SNAKE {} {} {}",
+                        player_number(snake.player),
+                        snake.score,
+                        snake.direction
+                    )?;
+                    for point in &snake.body {
+                        write!(f, " {},{},{}", point.x, point.y, point.z)?;
+                    }
+                }
+                for entity in &snapshot.entities {
+                    write!(
+                        f,
+                        " | ENTITY {} {},{},{}",
+                        entity.name, entity.x, entity.y, entity.z
+                    )?;
+                    if let Some((effect, duration)) = entity.effect {
+                        write!(f, " {effect:?} {duration}")?;
+                    }
+                }
+                Ok(())
+            }
+            ServerMessage::Bye => f.write_str("BYE"),
+        }
+    }
+}
+
+impl FromStr for ServerMessage {
+    type Err = ProtocolError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut tokens = s.split_whitespace();
+        match tokens.next() {
+            Some("WELCOME") => {
+                let version = parse(tokens.next(), "version")?;
+                let seat = match tokens.next() {
+                    Some("player") => Seat::Player(parse_player(tokens.next())?),
+                    Some("spectator") => Seat::Spectator,
+                    _ => return Err(error(s)),
+                };
+                Ok(ServerMessage::Welcome { version, seat })
+            }
+            Some("ERROR") => Ok(ServerMessage::Error(
+                s.trim().trim_start_matches("ERROR").trim().to_string(),
+            )),
+            Some("JOINED") => Ok(ServerMessage::Joined(parse_player(tokens.next())?)),
+            Some("LEFT") => Ok(ServerMessage::Left(parse_player(tokens.next())?)),
+            Some("STATE") => parse_snapshot(s).map(ServerMessage::State),
+            Some("BYE") => Ok(ServerMessage::Bye),
+            _ => Err(error(s)),
+        }
+    }
+}
+
+fn parse_snapshot(s: &str) -> Result<Snapshot, ProtocolError> {
+    let mut sections = s.split(" | ");
+    let mut header = sections.next().unwrap_or("").split_whitespace().skip(1);
+    let mut snapshot = Snapshot {
+        tick: parse(header.next(), "tick")?,
+        columns: parse(header.next(), "columns")?,
+        rows: parse(header.next(), "rows")?,
+        state: parse_state(header.next())?,
+        result: parse_result(header.next())?,
+        countdown_left: parse(header.next(), "countdown")?,
+        topology: Topology::Square,
+        layers: 1,
+        walls: Vec::new(),
+        portals: Vec::new(),
+        hazards: Vec::new(),
+        snakes: Vec::new(),
+        entities: Vec::new(),
+    };
+    if snapshot.columns < 1 || snapshot.rows < 1 {
+        return Err(ProtocolError(format!(
+            "Invalid board size {}x{}.",
+            snapshot.columns, snapshot.rows
+        )));
+    }
+    for section in sections {
+        let mut tokens = section.split_whitespace();
+        match tokens.next() {
+            Some("BOARD") => {
+                snapshot.topology = parse_topology(tokens.next())?;
+                snapshot.layers = parse(tokens.next(), "layers")?;
+                if snapshot.layers < 1 {
+                    return Err(ProtocolError(format!(
+                        "Invalid layers '{}'.",
+                        snapshot.layers
+                    )));
+                }
+            }
+            Some("WALLS") => snapshot.walls = tokens.map(parse_cell).collect::<Result<_, _>>()?,
+            Some("HAZARDS") => {
+                snapshot.hazards = tokens.map(parse_cell).collect::<Result<_, _>>()?
+            }
+            Some("PORTALS") => {
+                let mut ends = HashSet::new();
+                for token in tokens {
+                    let (a, b) = token.split_once(':').ok_or_else(|| error(token))?;
+                    let (a, b) = (parse_cell(a)?, parse_cell(b)?);
+                    // Same rules as Portals::add_pair, which panics on them
+                    if a == b || !ends.insert(a) || !ends.insert(b) {
+                        return Err(ProtocolError(format!("Invalid portal '{token}'.")));
+                    }
+                    snapshot.portals.push((a, b));
+                }
+            }
+            Some("SNAKE") => {
+                let snake = SnakeSnapshot {
+                    player: parse_player(tokens.next())?,
+                    score: parse(tokens.next(), "score")?,
+                    direction: parse(tokens.next(), "direction")?,
+                    body: tokens.map(parse_point).collect::<Result<_, _>>()?,
+                };
+                // The game can't hold a snake without a head
+                if snake.body.is_empty() {
+                    return Err(ProtocolError(format!(
+                        "The snake of player {} has no body.",
+                        player_number(snake.player)
+                    )));
+                }
+                snapshot.snakes.push(snake);
+            }
+            Some("ENTITY") => {
+                let name = tokens.next().ok_or_else(|| error(section))?.to_string();
+                let point = parse_point(tokens.next().unwrap_or(""))?;
+                let effect = match tokens.next() {
+                    Some(effect) => Some((
+                        StatusEffect::ALL
+                            .into_iter()
+                            .find(|known| format!("{known:?}") == effect)
+                            .ok_or_else(|| error(effect))?,
+                        parse(tokens.next(), "duration")?,
+                    )),
+                    None => None,
+                };
+                snapshot.entities.push(EntitySnapshot {
+                    name,
+                    x: point.x,
+                    y: point.y,
+                    z: point.z,
+                    effect,
+                });
+            }
+            _ => return Err(error(section)),
+        }
+    }
+    Ok(snapshot)
+}
+
+fn parse<T: FromStr>(token: Option<&str>, what: &str) -> Result<T, ProtocolError> {
+    token
+        .and_then(|token| token.parse().ok())
+        .ok_or_else(|| ProtocolError(format!("Invalid {what} '{}'.", token.unwrap_or(""))))
+}
+
+fn parse_point(token: &str) -> Result<SnakeBodyPoint, ProtocolError> {
+    let mut parts = token.split(',');
+    Ok(SnakeBodyPoint {
+        x: parse(parts.next(), "x")?,
+        y: parse(parts.next(), "y")?,
+        z: parse(parts.next(), "z")?,
+    })
+}
+
+fn parse_cell(token: &str) -> Result<(i16, i16), ProtocolError> {
+    let mut parts = token.split(',');
+    let cell = (parse(parts.next(), "x")?, parse(parts.next(), "y")?);
+    match parts.next() {
+        Some(_) => Err(ProtocolError(format!("Invalid cell '{token}'."))),
+        None => Ok(cell),
+    }
+}
+
+fn error(line: &str) -> ProtocolError {
+    ProtocolError(format!("Unexpected message '{}'.", line.trim()))
+}
+
+fn player_number(player: Player) -> u8 {
+    match player {
+        Player::One => 1,
+        Player::Two => 2,
+    }
+}
+
+fn parse_player(token: Option<&str>) -> Result<Player, ProtocolError> {
+    match token {
+        Some("1") => Ok(Player::One),
+        Some("2") => Ok(Player::Two),
+        _ => Err(ProtocolError(format!(
+            "Invalid player '{}'.",
+            token.unwrap_or("")
+        ))),
+    }
+}
+
+const STATES: [(GameState, &str); 6] = [
+    (GameState::New, "new"),
+    (GameState::Countdown, "countdown"),
+    (GameState::Playing, "playing"),
+    (GameState::Paused, "paused"),
+    (GameState::GameOver, "game-over"),
+    (GameState::Won, "won"),
+];
+
+const CAUSES: [(DeathCause, &str); 6] = [
+    (DeathCause::Wall, "wall"),
+    (DeathCause::SelfCollision, "self"),
+    (DeathCause::Starvation, "starvation"),
+    (DeathCause::Hazard, "hazard"),
+    (DeathCause::Rival, "rival"),
+    (DeathCause::Rule, "rule"),
+];
+
+const TOPOLOGIES: [(Topology, &str); 2] = [(Topology::Square, "square"), (Topology::Hex, "hex")];
+
+fn topology_name(topology: Topology) -> &'static str {
+    TOPOLOGIES
+        .iter()
+        .find(|(known, _)| *known == topology)
+        .map_or("square", |(_, name)| name)
+}
+
+fn parse_topology(token: Option<&str>) -> Result<Topology, ProtocolError> {
+    TOPOLOGIES
+        .iter()
+        .find(|(_, name)| Some(*name) == token)
+        .map(|(topology, _)| *topology)
+        .ok_or_else(|| ProtocolError(format!("Invalid topology '{}'.", token.unwrap_or(""))))
+}
+
+fn state_name(state: GameState) -> &'static str {
+    STATES
+        .iter()
+        .find(|(known, _)| *known == state)
+        .map_or("new", |(_, name)| name)
+}
+
+fn parse_state(token: Option<&str>) -> Result<GameState, ProtocolError> {
+    STATES
+        .iter()
+        .find(|(_, name)| Some(*name) == token)
+        .map(|(state, _)| *state)
+        .ok_or_else(|| ProtocolError(format!("Invalid state '{}'.", token.unwrap_or(""))))
+}
+
+fn cause_name(cause: DeathCause) -> &'static str {
+    CAUSES
+        .iter()
+        .find(|(known, _)| *known == cause)
+        .map_or("wall", |(_, name)| name)
+}
+
+fn result_name(result: Option<GameResult>) -> String {
+    match result {
+        None => "none".to_string(),
+        Some(GameResult::Won) => "won".to_string(),
+        Some(GameResult::Aborted) => "aborted".to_string(),
+        Some(GameResult::Draw) => "draw".to_string(),
+        Some(GameResult::Died(cause)) => format!("died:{}", cause_name(cause)),
+        Some(GameResult::RivalDied(cause)) => format!("rival-died:{}", cause_name(cause)),
+    }
+}
+
+fn parse_result(token: Option<&str>) -> Result<Option<GameResult>, ProtocolError> {
+    let invalid = || ProtocolError(format!("Invalid result '{}'.", token.unwrap_or("")));
+    let cause = |name: &str| {
+        CAUSES
+            .iter()
+            .find(|(_, known)| *known == name)
+            .map(|(cause, _)| *cause)
+            .ok_or_else(invalid)
+    };
+    match token.ok_or_else(invalid)? {
+        "none" => Ok(None),
+        "won" => Ok(Some(GameResult::Won)),
+        "aborted" => Ok(Some(GameResult::Aborted)),
+        "draw" => Ok(Some(GameResult::Draw)),
+        other => match other.split_once(':') {
+            Some(("died", name)) => Ok(Some(GameResult::Died(cause(name)?))),
+            Some(("rival-died", name)) => Ok(Some(GameResult::RivalDied(cause(name)?))),
+            _ => Err(invalid()),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::GameState;
+    use crate::snake::EntityKind;
+
+    #[test]
+    fn protocol_client_messages() {
+        for message in [
+            ClientMessage::Hello {
+                version: PROTOCOL_VERSION,
+                join: Join::Watch,
+            },
+            ClientMessage::Input {
+                tick: 42,
+                direction: SnakeDirection::DownLeft,
+            },
+            ClientMessage::Bye,
+        ] {
+            assert_eq!(message.to_string().parse(), Ok(message));
+        }
+        assert!("HELLO one play".parse::<ClientMessage>().is_err());
+        assert!("JUMP".parse::<ClientMessage>().is_err());
+    }
+
+    #[test]
+    fn protocol_snapshot_round_trip() {
+        let mut game = SnakeGame::new(12, 8, None, Some((3, 3)));
+        game.add_rival((8, 3), SnakeDirection::Left);
+        game.entities.push(Box::new(Apple::new(5, 5)));
+        game.entities
+            .push(Box::new(PowerUp::new(1, 1, StatusEffect::Magnet, 30)));
+        game.set_state(GameState::Playing);
+        game.snake.grow();
+        game.tick();
+        let snapshot = Snapshot::of(&game);
+        let message = ServerMessage::State(snapshot.clone());
+        assert_eq!(message.to_string().parse(), Ok(message));
+
+        let mirror = snapshot.to_game();
+        assert_eq!(mirror.snake.body, game.snake.body);
+        assert_eq!(
+            mirror.rival.as_ref().unwrap().body,
+            game.rival.unwrap().body
+        );
+        assert_eq!(mirror.get_state(), GameState::Playing);
+        assert_eq!(mirror.total_ticks(), 1);
+        assert_eq!(
+            mirror.entities[1].effect(),
+            Some((StatusEffect::Magnet, 30))
+        );
+    }
+
+    #[test]
+    fn protocol_snapshot_names_entities_from_the_registry() {
+        struct Cherry {
+            x: i16,
+            y: i16,
+        }
+        crate::impl_entity!(Cherry);
+        struct Mystery {
+            x: i16,
+            y: i16,
+        }
+        crate::impl_entity!(Mystery);
+
+        let mut game = SnakeGame::new(12, 8, None, Some((3, 3)));
+        game.entities.push(Box::new(Cherry::new(5, 5)));
+        game.entities.push(Box::new(Mystery::new(6, 6)));
+        let snapshot = Snapshot::of(&game);
+
+        let mut mirror = SnakeGame::new(12, 8, None, Some((0, 0)));
+        mirror.register_entity(EntityKind::new("cherry", "Cherry").with_glyph('%'));
+        snapshot.apply_to(&mut mirror);
+        assert_eq!(mirror.entities[0].name(), "Cherry");
+        let kind = mirror.entity_kind(mirror.entities[0].as_ref()).unwrap();
+        assert_eq!(kind.glyph, '%');
+        assert_eq!(mirror.entities[1].name(), "RemoteEntity");
+        assert_eq!((mirror.entities[1].x(), mirror.entities[1].y()), (6, 6));
+    }
+
+    #[test]
+    fn protocol_snapshot_board() {
+        let mut game = SnakeGame::new(12, 8, None, Some((3, 3)));
+        game.set_topology(Topology::Hex);
+        game.set_layers(2);
+        game.add_wall((0, 0));
+        game.add_wall((0, 1));
+        game.add_portal((2, 2), (9, 6));
+        game.hazards_mut().add((11, 7));
+        let snapshot = Snapshot::of(&game);
+        let message = ServerMessage::State(snapshot.clone());
+        let line = message.to_string();
+        assert!(
+            line.contains(
+                " | BOARD hex 2 | WALLS 0,0 0,1 | PORTALS 2,2:9,6 | HAZARDS 11,7 | SNAKE"
+            )
+        );
+        assert_eq!(line.parse(), Ok(message));
+
+        let mirror = snapshot.to_game();
+        assert_eq!(mirror.topology(), Topology::Hex);
+        assert_eq!(mirror.layers(), 2);
+        assert_eq!(mirror.walls(), game.walls());
+        assert_eq!(mirror.portals(), game.portals());
+        assert_eq!(mirror.hazards().cells(), game.hazards().cells());
+
+        for line in [
+            "STATE 0 10 10 new none 0 | BOARD round 1",
+            "STATE 0 10 10 new none 0 | BOARD square 0",
+            "STATE 0 10 10 new none 0 | WALLS 1,2,3",
+            "STATE 0 10 10 new none 0 | PORTALS 1,1:1,1",
+            "STATE 0 10 10 new none 0 | PORTALS 1,1:2,2 2,2:3,3",
+            "STATE 0 -5 10 new none 0",
+            "STATE 0 10 0 new none 0",
+        ] {
+            assert!(line.parse::<ServerMessage>().is_err(), "{line}");
+        }
+    }
+
+    #[test]
+    fn protocol_server_messages() {
+        for message in [
+            ServerMessage::Welcome {
+                version: 1,
+                seat: Seat::Player(Player::Two),
+            },
+            ServerMessage::Error("The game is full.".to_string()),
+            ServerMessage::Left(Player::One),
+            ServerMessage::Bye,
+        ] {
+            assert_eq!(message.to_string().parse(), Ok(message));
+        }
+        let over = "STATE 9 10 10 game-over rival-died:self 0"
+            .parse::<ServerMessage>()
+            .unwrap();
+        let ServerMessage::State(snapshot) = over else {
+            panic!("Not a state");
+        };
+        assert_eq!(
+            snapshot.result,
+            Some(GameResult::RivalDied(DeathCause::SelfCollision))
+        );
+        assert_eq!(
+            "STATE 0 10 10 playing none 0 | SNAKE 1 0 right".parse::<ServerMessage>(),
+            Err(ProtocolError(
+                "The snake of player 1 has no body.".to_string()
+            ))
+        );
+    }
+}
@@ -0,0 +1,518 @@
+use std::collections::{BTreeMap, HashMap};
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use super::{ClientMessage, Join, PROTOCOL_VERSION, Seat, ServerMessage, Snapshot};
+use crate::named;
+use crate::snake::{Apple, GameResult, Player, SnakeDirection};
+use crate::{GameState, SnakeGame};
+
+/// How often the server looks for new connections while waiting for inputs.
+const POLL_INTERVAL: Duration = Duration::from_millis(5);
+/// Slow clients are dropped instead of holding back everyone else.
+const WRITE_TIMEOUT: Duration = Duration::from_secs(1);
+/// Connections that don't say HELLO in this time are dropped.
+const HELLO_TIMEOUT: Duration = Duration::from_secs(5);
+/// Longest message a client can send, the ones in the protocol take a few bytes.
+const MAX_LINE_SIZE: u64 = 1024;
+/// How many ticks past the next one an input can be for, later ones are ignored.
+const MAX_INPUT_AHEAD: u64 = 10;
+
+/// Runs a [`SnakeGame`] for clients connecting over TCP, in lockstep. <br>
+/// The game starts once every snake has a player: the [snake](`SnakeGame::snake`) and the
+/// [rival](`SnakeGame::rival`) if there is one. Every tick waits for the input of each player
+/// up to the input timeout, players that don't send one keep going in their last direction.
+/// Inputs arriving late are applied on the next tick, the ones for more than a few ticks ahead are ignored.
+/// After every tick all clients, spectators included, get a [`Snapshot`] of the game. <br>
+/// Connections that don't say HELLO within five seconds, or send overly long lines, are dropped.
+/// # Examples
+/// ```no_run
+/// use snake3::SnakeGame;
+/// use snake3::net::Server;
+/// let game = SnakeGame::new(20, 20, None, None);
+/// let server = Server::bind("0.0.0.0:7070", game).unwrap();
+/// let result = server.run().unwrap();
+/// ```
+pub struct Server {
+    listener: TcpListener,
+    game: SnakeGame,
+    interval: Duration,
+    input_timeout: Duration,
+    hello_timeout: Duration,
+    step: Box<dyn FnMut(&mut SnakeGame)>,
+    connections: HashMap<usize, Connection>,
+    next_id: usize,
+    seats: [Option<usize>; 2],
+    inputs: BTreeMap<(u64, usize), SnakeDirection>,
+}
+
+struct Connection {
+    stream: TcpStream,
+    seat: Option<Seat>,
+    since: Instant,
+}
+
+enum Incoming {
+    Message(usize, ClientMessage),
+    Invalid(usize, String),
+    Gone(usize),
+}
+
+impl Server {
+    /// Listens on `address` for the players of `game`, with a tick every 150 milliseconds
+    /// and inputs waited for up to a second.
+    pub fn bind<A: ToSocketAddrs>(address: A, game: SnakeGame) -> io::Result<Self> {
+        let listener = TcpListener::bind(address)?;
+        listener.set_nonblocking(true)?;
+        Ok(Server {
+            listener,
+            game,
+            interval: Duration::from_millis(150),
+            input_timeout: Duration::from_secs(1),
+            hello_timeout: HELLO_TIMEOUT,
+            step: Box::new(step),
+            connections: HashMap::new(),
+            next_id: 0,
+            seats: [None, None],
+            inputs: BTreeMap::new(),
+        })
+    }
+    /// Shortest time between two ticks.
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+    /// Longest time a tick waits for the inputs of the players.
+    pub fn with_input_timeout(mut self, input_timeout: Duration) -> Self {
+        self.input_timeout = input_timeout;
+        self
+    }
+    /// Longest time a new connection has to say HELLO before it is dropped.
+    pub fn with_hello_timeout(mut self, hello_timeout: Duration) -> Self {
+        self.hello_timeout = hello_timeout;
+        self
+    }
+    /// Replaces what the server does every tick. By default it calls [`SnakeGame::tick`],
+    /// grows the snakes that ate an [`Apple`] and keeps an apple on the board.
+    pub fn with_step<F: FnMut(&mut SnakeGame) + 'static>(mut self, step: F) -> Self {
+        self.step = Box::new(step);
+        self
+    }
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.listener.local_addr()
+    }
+    pub fn game(&self) -> &SnakeGame {
+        &self.game
+    }
+    /// Plays the game until it finishes and returns how it ended,
+    /// the game is aborted if every player leaves once it started.
+    pub fn run(mut self) -> io::Result<Option<GameResult>> {
+        let (sender, receiver) = mpsc::channel();
+        let mut last_tick = Instant::now();
+        loop {
+            self.accept(&sender)?;
+            self.receive(&receiver, POLL_INTERVAL);
+            self.drop_silent();
+            match self.game.get_state() {
+                GameState::New if self.seats_filled() => {
+                    self.game.set_state(GameState::Playing);
+                    self.broadcast(&ServerMessage::State(Snapshot::of(&self.game)));
+                    last_tick = Instant::now();
+                }
+                GameState::New => {}
+                state if state.is_finished() => break,
+                _ if self.seats.iter().all(Option::is_none) => self.game.abort(),
+                _ => {
+                    let elapsed = last_tick.elapsed();
+                    if elapsed >= self.input_timeout
+                        || (elapsed >= self.interval && self.inputs_ready())
+                    {
+                        self.apply_inputs();
+                        (self.step)(&mut self.game);
+                        self.broadcast(&ServerMessage::State(Snapshot::of(&self.game)));
+                        last_tick = Instant::now();
+                    }
+                }
+            }
+        }
+        self.broadcast(&ServerMessage::State(Snapshot::of(&self.game)));
+        self.broadcast(&ServerMessage::Bye);
+        Ok(self.game.result())
+    }
+    fn accept(&mut self, sender: &Sender<Incoming>) -> io::Result<()> {
+        loop {
+            let stream = match self.listener.accept() {
+                Ok((stream, _)) => stream,
+                Err(error) if error.kind() == io::ErrorKind::WouldBlock => return Ok(()),
+                Err(error) => return Err(error),
+            };
+            stream.set_nonblocking(false)?;
+            stream.set_write_timeout(Some(WRITE_TIMEOUT))?;
+            let id = self.next_id;
+            self.next_id += 1;
+            let reader = stream.try_clone()?;
+            let sender = sender.clone();
+            thread::spawn(move || read_messages(id, reader, sender));
+            self.connections.insert(
+                id,
+                Connection {
+                    stream,
+                    seat: None,
+                    since: Instant::now(),
+                },
+            );
+        }
+    }
+    /// Handles the messages of the clients for up to `timeout`.
+    fn receive(&mut self, receiver: &Receiver<Incoming>, timeout: Duration) {
+        let mut incoming = receiver.recv_timeout(timeout).ok();
+        while let Some(message) = incoming {
+            match message {
+                Incoming::Message(id, message) => self.handle(id, message),
+                Incoming::Invalid(id, error) => self.send(id, &ServerMessage::Error(error)),
+                Incoming::Gone(id) => self.disconnect(id),
+            }
+            incoming = receiver.try_recv().ok();
+        }
+    }
+    fn handle(&mut self, id: usize, message: ClientMessage) {
+        let Some(seat) = self.connections.get(&id).map(|connection| connection.seat) else {
+            return;
+        };
+        match (message, seat) {
+            (ClientMessage::Hello { version, .. }, None) if version != PROTOCOL_VERSION => {
+                self.refuse(
+                    id,
+                    format!("Protocol version {version} is not supported, the server speaks {PROTOCOL_VERSION}."),
+                );
+            }
+            (ClientMessage::Hello { join, .. }, None) => {
+                let seat = match join {
+                    Join::Play => match self.free_seat() {
+                        Some(player) => Seat::Player(player),
+                        None => return self.refuse(id, "The game is full.".to_string()),
+                    },
+                    Join::Watch => Seat::Spectator,
+                };
+                if let Some(connection) = self.connections.get_mut(&id) {
+                    connection.seat = Some(seat);
+                }
+                self.send(
+                    id,
+                    &ServerMessage::Welcome {
+                        version: PROTOCOL_VERSION,
+                        seat,
+                    },
+                );
+                if let Seat::Player(player) = seat {
+                    self.seats[index(player)] = Some(id);
+                    self.broadcast(&ServerMessage::Joined(player));
+                }
+                self.send(id, &ServerMessage::State(Snapshot::of(&self.game)));
+            }
+            (ClientMessage::Hello { .. }, Some(_)) => {
+                self.send(id, &ServerMessage::Error("Already joined.".to_string()));
+            }
+            (ClientMessage::Input { tick, direction }, Some(Seat::Player(player))) => {
+                // Inputs far ahead would pile up until their tick comes
+                if tick <= self.game.total_ticks() + 1 + MAX_INPUT_AHEAD {
+                    self.inputs.insert((tick, index(player)), direction);
+                }
+            }
+            (ClientMessage::Input { .. }, _) => {
+                self.send(
+                    id,
+                    &ServerMessage::Error("Only players send inputs.".to_string()),
+                );
+            }
+            (ClientMessage::Bye, _) => self.disconnect(id),
+        }
+    }
+    /// Drops the connections that didn't say HELLO in time.
+    fn drop_silent(&mut self) {
+        let silent: Vec<usize> = self
+            .connections
+            .iter()
+            .filter(|(_, connection)| {
+                connection.seat.is_none() && connection.since.elapsed() >= self.hello_timeout
+            })
+            .map(|(id, _)| *id)
+            .collect();
+        for id in silent {
+            self.disconnect(id);
+        }
+    }
+    fn refuse(&mut self, id: usize, error: String) {
+        self.send(id, &ServerMessage::Error(error));
+        self.disconnect(id);
+    }
+    fn disconnect(&mut self, id: usize) {
+        let Some(connection) = self.connections.remove(&id) else {
+            return;
+        };
+        let _ = connection.stream.shutdown(std::net::Shutdown::Both);
+        if let Some(Seat::Player(player)) = connection.seat {
+            self.seats[index(player)] = None;
+            self.broadcast(&ServerMessage::Left(player));
+        }
+    }
+    fn send(&mut self, id: usize, message: &ServerMessage) {
+        let Some(connection) = self.connections.get_mut(&id) else {
+            return;
+        };
+        if writeln!(connection.stream, "{message}").is_err() {
+            self.disconnect(id);
+        }
+    }
+    fn broadcast(&mut self, message: &ServerMessage) {
+        let line = format!("{message}\n");
+        let failed: Vec<usize> = self
+            .connections
+            .iter_mut()
+            .filter(|(_, connection)| connection.seat.is_some())
+            .filter_map(|(id, connection)| {
+                connection
+                    .stream
+                    .write_all(line.as_bytes())
+                    .is_err()
+                    .then_some(*id)
+            })
+            .collect();
+        for id in failed {
+            self.disconnect(id);
+        }
+    }
+    fn players(&self) -> &[Player] {
+        match self.game.rival {
+            Some(_) => &[Player::One, Player::Two],
+            None => &[Player::One],
+        }
+    }
+    fn free_seat(&self) -> Option<Player> {
+        self.players()
+            .iter()
+            .copied()
+            .find(|player| self.seats[index(*player)].is_none())
+    }
+    fn seats_filled(&self) -> bool {
+        self.free_seat().is_none()
+    }
+    /// Whether every seated player sent an input for the next tick.
+    fn inputs_ready(&self) -> bool {
+        let next = self.game.total_ticks() + 1;
+        self.players()
+            .iter()
+            .filter(|player| self.seats[index(**player)].is_some())
+            .all(|player| {
+                self.inputs
+                    .keys()
+                    .any(|(tick, seat)| *tick <= next && *seat == index(*player))
+            })
+    }
+    /// Turns the snakes with the latest inputs up to the next tick, later ones are kept.
+    fn apply_inputs(&mut self) {
+        let next = self.game.total_ticks() + 1;
+        let future = self.inputs.split_off(&(next + 1, 0));
+        for ((_, seat), direction) in std::mem::replace(&mut self.inputs, future) {
+            let snake = match seat {
+                0 => Some(&mut self.game.snake),
+                _ => self.game.rival.as_mut(),
+            };
+            if let Some(snake) = snake {
+                snake.set_direction(direction);
+            }
+        }
+    }
+}
+
+fn index(player: Player) -> usize {
+    match player {
+        Player::One => 0,
+        Player::Two => 1,
+    }
+}
+
+fn read_messages(id: usize, stream: TcpStream, sender: Sender<Incoming>) {
+    let mut reader = BufReader::new(stream);
+    loop {
+        let mut line = String::new();
+        match reader.by_ref().take(MAX_LINE_SIZE).read_line(&mut line) {
+            Ok(0) | Err(_) => break,
+            Ok(size) if size as u64 == MAX_LINE_SIZE && !line.ends_with('\n') => {
+                let error = "The message is too long.".to_string();
+                let _ = sender.send(Incoming::Invalid(id, error));
+                break;
+            }
+            Ok(_) => {}
+        }
+        if line.trim().is_empty() {
+            continue;
+        }
+        let incoming = match line.trim_end().parse() {
+            Ok(message) => Incoming::Message(id, message),
+            Err(error) => Incoming::Invalid(id, error.to_string()),
+        };
+        if sender.send(incoming).is_err() {
+            return;
+        }
+    }
+    let _ = sender.send(Incoming::Gone(id));
+}
+
+/// What the [`Server`] does every tick by default.
+fn step(game: &mut SnakeGame) {
+    if let Some(hit) = game.tick()
+        && hit.downcast_ref::<Apple>().is_some()
+    {
+        game.snake.grow();
+    }
+    if let Some(hit) = game.take_rival_hit()
+        && hit.downcast_ref::<Apple>().is_some()
+        && let Some(rival) = &mut game.rival
+    {
+        rival.grow();
+    }
+    if game.get_state() == GameState::Playing
+        && !game
+            .entities
+            .iter()
+            .any(|entity| entity.downcast_ref::<Apple>().is_some())
+    {
+        game.generate_entity(named!(Apple));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::mpsc;
+
+    use super::*;
+    use crate::net::Client;
+    use crate::snake::DeathCause;
+
+    fn start_server(
+        game: fn() -> SnakeGame,
+    ) -> (SocketAddr, thread::JoinHandle<Option<GameResult>>) {
+        let (sender, receiver) = mpsc::channel();
+        let handle = thread::spawn(move || {
+            // The game is not Send, it has to be built on the thread running it
+            let server = Server::bind("127.0.0.1:0", game())
+                .unwrap()
+                .with_interval(Duration::from_millis(10))
+                .with_input_timeout(Duration::from_millis(40));
+            sender.send(server.local_addr().unwrap()).unwrap();
+            server.run().unwrap()
+        });
+        (receiver.recv().unwrap(), handle)
+    }
+
+    #[test]
+    fn server_plays_with_player_and_spectator() {
+        let (address, server) = start_server(|| SnakeGame::new(6, 6, None, Some((2, 2))));
+        let mut spectator = Client::connect(address, Join::Watch).unwrap();
+        assert_eq!(spectator.seat(), Seat::Spectator);
+        let mut player = Client::connect(address, Join::Play).unwrap();
+        assert_eq!(player.seat(), Seat::Player(Player::One));
+        assert!(Client::connect(address, Join::Play).is_err());
+
+        // Up on the first tick, then nothing so the snake keeps going up into the wall
+        player.send_input(1, SnakeDirection::Up).unwrap();
+        let mut snapshots = Vec::new();
+        while let Some(message) = spectator.recv_timeout(Duration::from_secs(5)).unwrap() {
+            match message {
+                ServerMessage::State(snapshot) => snapshots.push(snapshot),
+                ServerMessage::Bye => break,
+                _ => {}
+            }
+        }
+        let last = snapshots.last().unwrap();
+        assert_eq!(last.result, Some(GameResult::Died(DeathCause::Wall)));
+        assert_eq!(last.snakes[0].direction, SnakeDirection::Up);
+        assert_eq!(
+            server.join().unwrap(),
+            Some(GameResult::Died(DeathCause::Wall))
+        );
+    }
+
+    #[test]
+    fn server_turns_away_other_versions() {
+        let (address, server) = start_server(|| SnakeGame::new(6, 6, None, None));
+        let mut stream = TcpStream::connect(address).unwrap();
+        writeln!(stream, "HELLO 0 play").unwrap();
+        let mut line = String::new();
+        BufReader::new(&stream).read_line(&mut line).unwrap();
+        assert!(matches!(line.parse(), Ok(ServerMessage::Error(_))));
+
+        let mut player = Client::connect(address, Join::Play).unwrap();
+        while let Some(message) = player.recv_timeout(Duration::from_secs(5)).unwrap() {
+            if let ServerMessage::State(snapshot) = message
+                && snapshot.state == GameState::Playing
+            {
+                break;
+            }
+        }
+        drop(player);
+        assert_eq!(server.join().unwrap(), Some(GameResult::Aborted));
+    }
+
+    #[test]
+    fn server_drops_long_lines_and_silent_connections() {
+        let (sender, receiver) = mpsc::channel();
+        let handle = thread::spawn(move || {
+            let server = Server::bind("127.0.0.1:0", SnakeGame::new(6, 6, None, None))
+                .unwrap()
+                .with_input_timeout(Duration::from_millis(40))
+                .with_hello_timeout(Duration::from_millis(200));
+            sender.send(server.local_addr().unwrap()).unwrap();
+            server.run().unwrap()
+        });
+        let address = receiver.recv().unwrap();
+
+        let mut long = TcpStream::connect(address).unwrap();
+        long.write_all(&[b'A'; 2048]).unwrap();
+        let mut line = String::new();
+        let mut reader = BufReader::new(&long);
+        reader.read_line(&mut line).unwrap();
+        assert!(matches!(line.parse(), Ok(ServerMessage::Error(_))));
+        assert_eq!(reader.read_line(&mut line).unwrap(), 0);
+
+        let silent = TcpStream::connect(address).unwrap();
+        silent
+            .set_read_timeout(Some(Duration::from_secs(5)))
+            .unwrap();
+        let started = Instant::now();
+        assert_eq!(BufReader::new(&silent).read_line(&mut line).unwrap(), 0);
+        assert!(started.elapsed() < Duration::from_secs(5));
+
+        drop(Client::connect(address, Join::Play).unwrap());
+        assert_eq!(handle.join().unwrap(), Some(GameResult::Aborted));
+    }
+
+    #[test]
+    fn server_ignores_inputs_far_ahead() {
+        let mut server = Server::bind("127.0.0.1:0", SnakeGame::new(6, 6, None, None)).unwrap();
+        let (sender, _receiver) = mpsc::channel();
+        let _stream = TcpStream::connect(server.local_addr().unwrap()).unwrap();
+        while server.connections.is_empty() {
+            server.accept(&sender).unwrap();
+        }
+        let id = *server.connections.keys().next().unwrap();
+        server.handle(
+            id,
+            ClientMessage::Hello {
+                version: PROTOCOL_VERSION,
+                join: Join::Play,
+            },
+        );
+        let far = 2 + MAX_INPUT_AHEAD;
+        for tick in [1, 1 + MAX_INPUT_AHEAD, far, u64::MAX] {
+            let direction = SnakeDirection::Up;
+            server.handle(id, ClientMessage::Input { tick, direction });
+        }
+        let ticks: Vec<u64> = server.inputs.keys().map(|(tick, _)| *tick).collect();
+        assert_eq!(ticks, vec![1, 1 + MAX_INPUT_AHEAD]);
+    }
+}
@@ -0,0 +1,156 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use rhai::{Engine, EvalAltResult};
+
+use super::{ScriptedEntity, ScriptedEntityType};
+use crate::SnakeGame;
+use crate::snake::{DeathCause, StatusEffect};
+
+type ApiResult<T> = Result<T, Box<EvalAltResult>>;
+
+/// Most body points a single `game.grow(n)` or `game.shrink(n)` changes.
+const MAX_GROWTH: i64 = 1000;
+/// Most points a single `game.add_score(points)` gives.
+const MAX_BONUS: i64 = 1_000_000;
+
+/// The `game` scripts get on their hooks, the only way they can see or change the [`SnakeGame`]. <br>
+/// The game is lent to the script for the length of one hook, a copy of the handle kept past it fails on every call.
+///
+/// | Script                       | Does                                                           |
+/// |------------------------------|----------------------------------------------------------------|
+/// | `game.score`, `game.ticks`   | [`SnakeGame::score`], [`SnakeGame::ticks`]                     |
+/// | `game.columns`, `game.rows`  | Size of the board                                              |
+/// | `game.length`                | Length of the snake                                            |
+/// | `game.head_x`, `game.head_y` | Position of the head                                           |
+/// | `game.direction`             | [Name](`crate::snake::SnakeDirection::name`) of the direction  |
+/// | `game.count(name)`           | Entities called `name` on the board                            |
+/// | `game.random(min, max)`      | [`SnakeGame::random_range`], seeded games stay the same        |
+/// | `game.place(name)`           | Places an entity of a scripted type on a free cell             |
+/// | `game.remove(name)`          | [Expires](`SnakeGame::expire_entities`) the entities called `name` |
+/// | `game.grow(n)`               | [Grows](`crate::snake::Snake::grow_by`) the snake `n` body points |
+/// | `game.shrink(n)`             | [Shrinks](`crate::snake::Snake::shrink_by`) the snake `n` body points |
+/// | `game.add_score(points)`     | Adds up to a million bonus points to the score                 |
+/// | `game.apply_effect(name, n)` | Gives the snake a [`StatusEffect`] for `n` ticks               |
+/// | `game.kill()`                | [Kills](`SnakeGame::kill`) the snake with [`DeathCause::Rule`] |
+#[derive(Clone)]
+pub struct GameApi {
+    game: Rc<RefCell<Option<SnakeGame>>>,
+    types: Rc<[ScriptedEntityType]>,
+}
+
+impl GameApi {
+    /// Lends `game` to `hook` behind a handle and takes it back once the hook returns.
+    pub(crate) fn lend<T>(
+        game: &mut SnakeGame,
+        types: &Rc<[ScriptedEntityType]>,
+        hook: impl FnOnce(GameApi) -> T,
+    ) -> T {
+        let lent = std::mem::replace(game, SnakeGame::new(1, 1, None, None));
+        let cell = Rc::new(RefCell::new(Some(lent)));
+        let result = hook(GameApi {
+            game: Rc::clone(&cell),
+            types: Rc::clone(types),
+        });
+        if let Some(lent) = cell.borrow_mut().take() {
+            *game = lent;
+        }
+        result
+    }
+    fn with<T>(&self, action: impl FnOnce(&mut SnakeGame) -> T) -> ApiResult<T> {
+        match self.game.borrow_mut().as_mut() {
+            Some(game) => Ok(action(game)),
+            None => Err("The game can only be used during the hook it was given to.".into()),
+        }
+    }
+    fn kind(&self, name: &str) -> ApiResult<ScriptedEntityType> {
+        self.types
+            .iter()
+            .find(|kind| kind.name == name)
+            .copied()
+            .ok_or_else(|| format!("Unknown entity type '{name}'.").into())
+    }
+    /// Registers the `Game` type and its API on `engine`.
+    pub(crate) fn register(engine: &mut Engine) {
+        engine.register_type_with_name::<GameApi>("Game");
+        engine
+            .register_get("score", |api: &mut GameApi| {
+                api.with(|game| game.score() as i64)
+            })
+            .register_get("ticks", |api: &mut GameApi| {
+                api.with(|game| game.ticks() as i64)
+            })
+            .register_get("columns", |api: &mut GameApi| {
+                api.with(|game| i64::from(game.columns))
+            })
+            .register_get("rows", |api: &mut GameApi| {
+                api.with(|game| i64::from(game.rows))
+            })
+            .register_get("length", |api: &mut GameApi| {
+                api.with(|game| game.snake.body.len() as i64)
+            })
+            .register_get("head_x", |api: &mut GameApi| {
+                api.with(|game| i64::from(game.snake.body[0].x))
+            })
+            .register_get("head_y", |api: &mut GameApi| {
+                api.with(|game| i64::from(game.snake.body[0].y))
+            })
+            .register_get("direction", |api: &mut GameApi| {
+                api.with(|game| game.snake.get_direction().name().to_string())
+            });
+        engine
+            .register_fn("count", |api: &mut GameApi, name: &str| {
+                api.with(|game| {
+                    game.entities
+                        .iter()
+                        .filter(|entity| entity.name() == name)
+                        .count() as i64
+                })
+            })
+            .register_fn("random", |api: &mut GameApi, min: i64, max: i64| {
+                let clamp = |value: i64| value.clamp(i16::MIN.into(), i16::MAX.into()) as i16;
+                let (min, max) = (clamp(min), clamp(max));
+                if min >= max {
+                    return Err(format!("random({min}, {max}) needs min below max.").into());
+                }
+                api.with(|game| i64::from(game.random_range(min, max)))
+            })
+            .register_fn("place", |api: &mut GameApi, name: &str| {
+                let kind = api.kind(name)?;
+                api.with(|game| {
+                    game.generate_entity(|x, y| Box::new(ScriptedEntity::new(x, y, kind)))
+                })
+            })
+            .register_fn("remove", |api: &mut GameApi, name: &str| {
+                api.with(|game| game.expire_entities(|entity| entity.name() == name) as i64)
+            })
+            .register_fn("grow", |api: &mut GameApi, points: i64| {
+                api.with(|game| game.snake.grow_by(points.clamp(0, MAX_GROWTH) as u16))
+            })
+            .register_fn("shrink", |api: &mut GameApi, points: i64| {
+                api.with(|game| game.snake.shrink_by(points.clamp(0, MAX_GROWTH) as u16))
+            })
+            .register_fn("add_score", |api: &mut GameApi, points: i64| {
+                api.with(|game| {
+                    game.scoring_mut()
+                        .add_bonus(points.clamp(0, MAX_BONUS) as u64)
+                })
+            })
+            .register_fn(
+                "apply_effect",
+                |api: &mut GameApi, name: &str, ticks: i64| {
+                    let effect = StatusEffect::ALL
+                        .into_iter()
+                        .find(|effect| effect.name().eq_ignore_ascii_case(name))
+                        .ok_or_else(|| format!("Unknown effect '{name}'."))?;
+                    let ticks = ticks.clamp(0, u32::MAX.into()) as u32;
+                    api.with(|game| {
+                        game.snake.effects_mut().apply(effect, ticks);
+                    })
+                },
+            )
+            .register_fn("kill", |api: &mut GameApi| {
+                api.with(|game| game.kill(DeathCause::Rule))
+            });
+    }
+}
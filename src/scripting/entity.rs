@@ -0,0 +1,68 @@
+use std::any::Any;
+
+use crate::snake::{Entity, EntityColor, StatusEffect};
+
+/// Entity type declared by a script with `entity(name, properties)`.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub struct ScriptedEntityType {
+    /// Name of the type, also the [name](`Entity::name`) of its entities.
+    pub name: &'static str,
+    /// Character renderers can draw its entities with, `o` by default.
+    pub glyph: char,
+    /// Colour renderers can draw its entities with, green by default.
+    pub color: EntityColor,
+    /// Points the snake scores eating one, see [`ScoringRules::points`](`crate::snake::ScoringRules::points`).
+    pub points: Option<u64>,
+    /// The [`StatusEffect`] and its duration in ticks the snake gets when eating one.
+    pub effect: Option<(StatusEffect, u32)>,
+}
+
+/// An entity of a [`ScriptedEntityType`], placed on the board by a script with `game.place(name)`.
+#[derive(Debug, Copy, Clone)]
+pub struct ScriptedEntity {
+    pub x: i16,
+    pub y: i16,
+    pub z: i16,
+    pub kind: ScriptedEntityType,
+}
+
+impl ScriptedEntity {
+    pub fn new(x: i16, y: i16, kind: ScriptedEntityType) -> Self {
+        ScriptedEntity { x, y, z: 0, kind }
+    }
+}
+
+impl Entity for ScriptedEntity {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn x(&self) -> i16 {
+        self.x
+    }
+
+    fn y(&self) -> i16 {
+        self.y
+    }
+
+    fn z(&self) -> i16 {
+        self.z
+    }
+
+    fn set_position(&mut self, x: i16, y: i16) {
+        self.x = x;
+        self.y = y;
+    }
+
+    fn set_z(&mut self, z: i16) {
+        self.z = z;
+    }
+
+    fn effect(&self) -> Option<(StatusEffect, u32)> {
+        self.kind.effect
+    }
+
+    fn name(&self) -> &'static str {
+        self.kind.name
+    }
+}
@@ -0,0 +1,40 @@
+//! Entity types and rules written in [Rhai](<https://rhai.rs>) scripts loaded at runtime, enabled with the
+//! `scripting` feature, so new game modes can be tried without writing Rust. <br>
+//! A script declares its entity types at the top and defines hooks that the [`ScriptRules`] run on the game,
+//! the hooks only reach the game through the [`GameApi`].
+//!
+//! ```
+//! use snake3::{GameState, SnakeGame};
+//! use snake3::scripting::ScriptRules;
+//! let rules: ScriptRules = r#"
+//!     entity("Bomb", #{ glyph: "*" });
+//!     entity("Cherry", #{ glyph: "%", points: 3 });
+//!
+//!     fn on_eaten(game, name) {
+//!         if name == "Bomb" { game.kill(); }
+//!         if name == "Cherry" { game.grow(2); }
+//!     }
+//!     fn on_spawn(game) {
+//!         if game.count("Cherry") == 0 { game.place("Cherry"); }
+//!         if game.ticks % 50 == 0 { game.place("Bomb"); }
+//!     }
+//! "#
+//! .parse()
+//! .unwrap();
+//!
+//! let mut game = SnakeGame::new(20, 20, None, None);
+//! rules.install(&mut game);
+//! rules.spawn(&mut game).unwrap();
+//! game.set_state(GameState::Playing);
+//! while !game.get_state().is_finished() && game.ticks() < 10 {
+//!     game.tick();
+//!     rules.update(&mut game).unwrap();
+//! }
+//! ```
+pub mod api;
+pub mod entity;
+pub mod rules;
+
+pub use api::GameApi;
+pub use entity::{ScriptedEntity, ScriptedEntityType};
+pub use rules::{ScriptError, ScriptRules};
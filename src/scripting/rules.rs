@@ -0,0 +1,431 @@
+use std::cell::{Cell, RefCell};
+use std::collections::HashSet;
+use std::path::Path;
+use std::rc::Rc;
+use std::sync::{Mutex, OnceLock};
+use std::{fmt, fs, str::FromStr};
+
+use rhai::module_resolvers::DummyModuleResolver;
+use rhai::{AST, CallFnOptions, Dynamic, Engine, EvalAltResult, FuncArgs, Map, Scope};
+
+use super::{GameApi, ScriptedEntity, ScriptedEntityType};
+use crate::SnakeGame;
+use crate::snake::{EntityColor, EntityKind, GameEvent, GameState, StatusEffect};
+
+/// Limits that keep a script from hanging the game or eating its memory.
+const MAX_OPERATIONS: u64 = 100_000;
+const MAX_CALL_LEVELS: usize = 32;
+const MAX_STRING_SIZE: usize = 4096;
+const MAX_ARRAY_SIZE: usize = 4096;
+const MAX_MAP_SIZE: usize = 256;
+
+/// A script that failed to load or one of its hooks that failed to run.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ScriptError(pub String);
+
+impl fmt::Display for ScriptError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for ScriptError {}
+
+impl From<Box<EvalAltResult>> for ScriptError {
+    fn from(error: Box<EvalAltResult>) -> Self {
+        ScriptError(error.to_string())
+    }
+}
+
+/// Entity types and rules of a game written in a [Rhai](<https://rhai.rs>) script. <br>
+/// The top of the script declares the entity types with `entity(name, properties)`, names without spaces or `|`, where every property is optional:
+/// `glyph`, a one character string, `color`, one of the [`EntityColor`]s, `points` and `effect` with its `duration` in ticks. The script can also define
+/// any of these hooks, all of them get the [`GameApi`] as `game`:
+/// * `on_eaten(game, name)` after the snake eats any entity, scripted or not.
+/// * `on_tick(game)` after every tick of the game.
+/// * `on_spawn(game)` after every tick too and once before the game starts, to place entities.
+///
+/// Scripts can't read files or import modules and every hook stops after a fixed number of operations.
+pub struct ScriptRules {
+    engine: Engine,
+    ast: AST,
+    types: Rc<[ScriptedEntityType]>,
+    /// Names of the entities eaten since the last update, filled by the listener of [`ScriptRules::install`].
+    eaten: Rc<RefCell<Vec<&'static str>>>,
+    log: Rc<RefCell<Vec<String>>>,
+    last_tick: Cell<Option<u64>>,
+}
+
+impl ScriptRules {
+    /// Reads and runs the script at `path`.
+    pub fn load(path: &Path) -> Result<Self, ScriptError> {
+        let source = fs::read_to_string(path)
+            .map_err(|error| ScriptError(format!("Can't read {}: {error}", path.display())))?;
+        source.parse()
+    }
+    pub fn entity_types(&self) -> &[ScriptedEntityType] {
+        &self.types
+    }
+    pub fn entity_type(&self, name: &str) -> Option<&ScriptedEntityType> {
+        self.types.iter().find(|kind| kind.name == name)
+    }
+    /// Gets `game` ready to be played with these rules: [registers](`SnakeGame::register_entity`) the scripted
+    /// entity types, with their name as id, adds their points to its [`ScoringRules`](`crate::snake::ScoringRules`)
+    /// and starts listening to what the snake eats. <br>
+    /// Install the rules on a single game, it keeps them through [resets](`SnakeGame::reset`).
+    pub fn install(&self, game: &mut SnakeGame) {
+        let mut rules = game.scoring().rules().clone();
+        for &kind in self.types.iter() {
+            game.register_entity(
+                EntityKind::new(kind.name, kind.name)
+                    .with_glyph(kind.glyph)
+                    .with_color(kind.color)
+                    .with_serde(
+                        |_| Some(String::new()),
+                        move |x, y, data| {
+                            data.is_empty()
+                                .then(|| Box::new(ScriptedEntity::new(x, y, kind)) as _)
+                        },
+                    ),
+            );
+            if let Some(points) = kind.points {
+                rules.points.retain(|(name, _)| *name != kind.name);
+                rules.points.push((kind.name, points));
+            }
+        }
+        game.set_scoring_rules(rules);
+        let eaten = Rc::clone(&self.eaten);
+        game.on_event(move |event| {
+            if let GameEvent::Ate { entity, .. } = event {
+                eaten.borrow_mut().push(entity);
+            }
+        });
+    }
+    /// Runs `on_spawn`, call it before the game starts to place the first entities.
+    pub fn spawn(&self, game: &mut SnakeGame) -> Result<(), ScriptError> {
+        self.call(game, "on_spawn", 1, |api| (api,))
+    }
+    /// Runs the hooks for what happened since the last call: `on_eaten` for every entity eaten and, if the game
+    /// [ticked](`SnakeGame::tick`) while playing, `on_tick` and `on_spawn`. Call it after every tick.
+    pub fn update(&self, game: &mut SnakeGame) -> Result<(), ScriptError> {
+        let eaten = std::mem::take(&mut *self.eaten.borrow_mut());
+        for name in eaten {
+            self.call(game, "on_eaten", 2, |api| (api, name.to_string()))?;
+        }
+        let tick = Some(game.ticks());
+        if game.get_state() != GameState::Playing || self.last_tick.replace(tick) == tick {
+            return Ok(());
+        }
+        self.call(game, "on_tick", 1, |api| (api,))?;
+        self.spawn(game)
+    }
+    /// Everything the script printed with `print` and `debug` since the last call.
+    pub fn drain_log(&self) -> Vec<String> {
+        std::mem::take(&mut *self.log.borrow_mut())
+    }
+    /// Runs `hook` if the script defines it taking `arity` parameters.
+    fn call<A: FuncArgs>(
+        &self,
+        game: &mut SnakeGame,
+        hook: &str,
+        arity: usize,
+        args: impl FnOnce(GameApi) -> A,
+    ) -> Result<(), ScriptError> {
+        if !self
+            .ast
+            .iter_functions()
+            .any(|function| function.name == hook && function.params.len() == arity)
+        {
+            return Ok(());
+        }
+        GameApi::lend(game, &self.types, |api| {
+            let options = CallFnOptions::new().eval_ast(false);
+            self.engine
+                .call_fn_with_options::<Dynamic>(
+                    options,
+                    &mut Scope::new(),
+                    &self.ast,
+                    hook,
+                    args(api),
+                )
+                .map(|_| ())
+                .map_err(|error| ScriptError(format!("{hook}: {error}")))
+        })
+    }
+}
+
+impl FromStr for ScriptRules {
+    type Err = ScriptError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let log = Rc::new(RefCell::new(Vec::new()));
+        let mut engine = sandboxed_engine(&log);
+        GameApi::register(&mut engine);
+        // Entity types can only be declared while the top of the script runs
+        let declared = Rc::new(RefCell::new(Some(Vec::<ScriptedEntityType>::new())));
+        let declaring = Rc::clone(&declared);
+        engine.register_fn(
+            "entity",
+            move |name: &str, properties: Map| -> Result<(), Box<EvalAltResult>> {
+                let mut declaring = declaring.borrow_mut();
+                let Some(types) = declaring.as_mut() else {
+                    return Err(
+                        "Entity types can only be declared at the top of the script.".into(),
+                    );
+                };
+                if types.iter().any(|kind| kind.name == name) {
+                    return Err(format!("The entity type '{name}' is declared twice.").into());
+                }
+                types.push(entity_type(name, &properties)?);
+                Ok(())
+            },
+        );
+        engine.register_fn("entity", |name: &str| -> Result<(), Box<EvalAltResult>> {
+            Err(format!("entity(\"{name}\") needs its properties, use #{{}} for none.").into())
+        });
+        let ast = engine
+            .compile(s)
+            .map_err(|error| ScriptError(error.to_string()))?;
+        engine.run_ast(&ast)?;
+        let types = declared.borrow_mut().take().unwrap_or_default();
+        Ok(ScriptRules {
+            engine,
+            ast,
+            types: types.into(),
+            eaten: Rc::new(RefCell::new(Vec::new())),
+            log,
+            last_tick: Cell::new(None),
+        })
+    }
+}
+
+/// An engine that can't reach outside of the game, with its prints going to `log`.
+fn sandboxed_engine(log: &Rc<RefCell<Vec<String>>>) -> Engine {
+    let mut engine = Engine::new();
+    engine
+        .set_module_resolver(DummyModuleResolver::new())
+        .set_max_operations(MAX_OPERATIONS)
+        .set_max_call_levels(MAX_CALL_LEVELS)
+        .set_max_string_size(MAX_STRING_SIZE)
+        .set_max_array_size(MAX_ARRAY_SIZE)
+        .set_max_map_size(MAX_MAP_SIZE)
+        .disable_symbol("eval");
+    let printed = Rc::clone(log);
+    engine.on_print(move |text| printed.borrow_mut().push(text.to_string()));
+    let debugged = Rc::clone(log);
+    engine.on_debug(move |text, _, _| debugged.borrow_mut().push(text.to_string()));
+    engine
+}
+
+/// The `&'static` copy of an entity name, leaked once for every different name however many scripts are loaded.
+fn intern(name: &str) -> &'static str {
+    static NAMES: OnceLock<Mutex<HashSet<&'static str>>> = OnceLock::new();
+    let mut names = NAMES
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    match names.get(name) {
+        Some(interned) => interned,
+        None => {
+            let interned: &'static str = Box::leak(name.to_string().into_boxed_str());
+            names.insert(interned);
+            interned
+        }
+    }
+}
+
+fn entity_type(name: &str, properties: &Map) -> Result<ScriptedEntityType, Box<EvalAltResult>> {
+    // Names travel in the lines of saves and of the network protocol, which split on these
+    if name.is_empty() || name.contains(|c: char| c.is_whitespace() || c == '|') {
+        return Err(format!(
+            "Invalid entity type name '{name}', it can't be empty or have spaces or '|'."
+        )
+        .into());
+    }
+    let invalid = |property: &str| format!("Invalid {property} for the entity type '{name}'.");
+    let mut kind = ScriptedEntityType {
+        // Entity names live as long as the program, like the ones of the types written in Rust
+        name: intern(name),
+        glyph: 'o',
+        color: EntityColor::Green,
+        points: None,
+        effect: None,
+    };
+    let mut effect = None;
+    let mut duration = None;
+    for (property, value) in properties {
+        match property.as_str() {
+            "glyph" => {
+                let glyph = value.clone().into_string().map_err(|_| invalid("glyph"))?;
+                let mut chars = glyph.chars();
+                kind.glyph = match (chars.next(), chars.next()) {
+                    (Some(glyph), None) => glyph,
+                    _ => return Err(invalid("glyph").into()),
+                };
+            }
+            "color" => {
+                let color = value.clone().into_string().map_err(|_| invalid("color"))?;
+                kind.color = color.parse().map_err(|_| invalid("color"))?;
+            }
+            "points" => match value.as_int() {
+                Ok(points) if points >= 0 => kind.points = Some(points as u64),
+                _ => return Err(invalid("points").into()),
+            },
+            "effect" => {
+                let name = value.clone().into_string().map_err(|_| invalid("effect"))?;
+                effect = StatusEffect::ALL
+                    .into_iter()
+                    .find(|effect| effect.name().eq_ignore_ascii_case(&name));
+                if effect.is_none() {
+                    return Err(invalid("effect").into());
+                }
+            }
+            "duration" => match value.as_int() {
+                Ok(ticks) if ticks > 0 && ticks <= i64::from(u32::MAX) => {
+                    duration = Some(ticks as u32)
+                }
+                _ => return Err(invalid("duration").into()),
+            },
+            other => return Err(format!("Unknown property '{other}' of '{name}'.").into()),
+        }
+    }
+    kind.effect = match (effect, duration) {
+        (Some(effect), Some(duration)) => Some((effect, duration)),
+        (None, None) => None,
+        _ => return Err(format!("'{name}' needs both an effect and its duration.").into()),
+    };
+    Ok(kind)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::snake::{DeathCause, GameResult};
+
+    const RULES: &str = r#"
+        entity("Bomb", #{ glyph: "*" });
+        entity("Cherry", #{ glyph: "%", color: "magenta", points: 5, effect: "Magnet", duration: 10 });
+
+        fn on_eaten(game, name) {
+            if name == "Bomb" { game.kill(); }
+            if name == "Cherry" { game.grow(2); }
+        }
+        fn on_tick(game) {
+            if game.ticks % 2 == 0 { print(`tick ${game.ticks}`); }
+        }
+        fn on_spawn(game) {
+            if game.count("Cherry") == 0 { game.place("Cherry"); }
+        }
+    "#;
+
+    fn place(game: &mut SnakeGame, rules: &ScriptRules, name: &str, x: i16, y: i16) {
+        let kind = *rules.entity_type(name).unwrap();
+        game.entities
+            .push(Box::new(ScriptedEntity::new(x, y, kind)));
+    }
+
+    #[test]
+    fn scripts_declare_entity_types() {
+        let rules: ScriptRules = RULES.parse().unwrap();
+        let names: Vec<&str> = rules.entity_types().iter().map(|kind| kind.name).collect();
+        assert_eq!(names, ["Bomb", "Cherry"]);
+        let again: ScriptRules = RULES.parse().unwrap();
+        assert!(std::ptr::eq(
+            again.entity_types()[0].name,
+            rules.entity_types()[0].name
+        ));
+        let cherry = rules.entity_type("Cherry").unwrap();
+        assert_eq!(cherry.glyph, '%');
+        assert_eq!(cherry.color, EntityColor::Magenta);
+        assert_eq!(cherry.points, Some(5));
+        assert_eq!(cherry.effect, Some((StatusEffect::Magnet, 10)));
+
+        let invalid = [
+            r#"entity("Bomb", #{ colour: "red" });"#,
+            r#"entity("Bomb", #{ effect: "Magnet" });"#,
+            r#"entity("Bomb", #{ color: "pink" });"#,
+            r#"entity("Bomb", #{}); entity("Bomb", #{});"#,
+            r#"entity("Bomb");"#,
+            r#"entity("Big Bomb", #{});"#,
+            r#"entity("Bomb|2", #{});"#,
+            r#"entity("", #{});"#,
+            "fn broken( {",
+        ];
+        for script in invalid {
+            assert!(script.parse::<ScriptRules>().is_err(), "{script}");
+        }
+    }
+
+    #[test]
+    fn hooks_change_the_game() {
+        let rules: ScriptRules = RULES.parse().unwrap();
+        let mut game = SnakeGame::new(10, 10, None, Some((2, 2)));
+        rules.install(&mut game);
+        rules.spawn(&mut game).unwrap();
+        assert_eq!(game.entities.len(), 1);
+        assert_eq!(game.entities[0].name(), "Cherry");
+        let kind = game.entity_kind(game.entities[0].as_ref()).unwrap();
+        assert_eq!((kind.glyph, kind.color), ('%', EntityColor::Magenta));
+        let saved = game
+            .entity_registry()
+            .save(game.entities[0].as_ref())
+            .unwrap();
+        let loaded = game.entity_registry().load(&saved).unwrap();
+        assert_eq!(loaded.effect(), Some((StatusEffect::Magnet, 10)));
+        game.entities.clear();
+        place(&mut game, &rules, "Cherry", 3, 2);
+        game.set_state(GameState::Playing);
+        game.tick();
+        rules.update(&mut game).unwrap();
+        assert_eq!(game.score(), 5);
+        assert_eq!(game.snake.pending_growth(), 2);
+        assert!(game.snake.effects().is_active(StatusEffect::Magnet));
+        let cherries = game
+            .entities
+            .iter()
+            .filter(|entity| entity.name() == "Cherry");
+        assert_eq!(cherries.count(), 1);
+        game.tick();
+        rules.update(&mut game).unwrap();
+        assert_eq!(rules.drain_log(), ["tick 2"]);
+
+        place(&mut game, &rules, "Bomb", 5, 2);
+        game.tick();
+        rules.update(&mut game).unwrap();
+        assert_eq!(game.result(), Some(GameResult::Died(DeathCause::Rule)));
+    }
+
+    #[test]
+    fn scripts_are_sandboxed() {
+        let rules: ScriptRules = r#"
+            fn on_tick(game) { loop {} }
+            fn on_spawn(game) { game.place("Nothing"); }
+        "#
+        .parse()
+        .unwrap();
+        let mut game = SnakeGame::new(10, 10, None, None);
+        game.set_state(GameState::Playing);
+        game.tick();
+        assert!(rules.update(&mut game).is_err());
+        assert!(rules.spawn(&mut game).is_err());
+        // The game is given back even when a hook fails
+        assert_eq!(game.ticks(), 1);
+
+        let rules: ScriptRules = r#"
+            fn on_tick(game) {
+                game.add_score(9223372036854775807);
+                game.add_score(9223372036854775807);
+            }
+        "#
+        .parse()
+        .unwrap();
+        game.tick();
+        rules.update(&mut game).unwrap();
+        assert_eq!(game.score(), 2_000_000);
+
+        let escapes = [r#"import "secrets" as s;"#, r#"eval("1 + 1");"#];
+        for script in escapes {
+            assert!(script.parse::<ScriptRules>().is_err(), "{script}");
+        }
+    }
+}
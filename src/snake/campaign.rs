@@ -0,0 +1,448 @@
+use std::{fs, io, path::Path};
+
+use crate::named;
+
+use super::{Apple, Difficulty, DifficultyCurve, GameResult, Map, SnakeGame, WinCondition};
+
+/// One stage of a [`Campaign`].
+#[derive(Debug, Clone)]
+pub struct Level {
+    pub name: String,
+    pub map: Map,
+    /// Points to score to complete the level, one per apple with the default [`ScoringRules`](`super::ScoringRules`).
+    pub target_apples: u16,
+    /// How many apples are on the board at the same time.
+    pub max_apples: usize,
+    pub difficulty: DifficultyCurve,
+}
+
+impl Level {
+    /// Returns a level with one apple at a time and the [`Difficulty::Normal`] curve.
+    pub fn new(name: &str, map: Map, target_apples: u16) -> Self {
+        Level {
+            name: name.to_string(),
+            map,
+            target_apples,
+            max_apples: 1,
+            difficulty: DifficultyCurve::preset(Difficulty::Normal),
+        }
+    }
+    pub fn with_max_apples(mut self, max_apples: usize) -> Self {
+        self.max_apples = max_apples.max(1);
+        self
+    }
+    pub fn with_difficulty(mut self, difficulty: DifficultyCurve) -> Self {
+        self.difficulty = difficulty;
+        self
+    }
+    /// Places apples until there are [`Level::max_apples`] on the board.
+    pub fn spawn_apples(&self, game: &mut SnakeGame) {
+        while game.entities.len() < self.max_apples {
+            if !game.generate_entity(named!(Apple)) {
+                break;
+            }
+        }
+    }
+}
+
+/// What to do after a level of a [`Campaign`] finished, see [`Campaign::finish_level`].
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum CampaignStep {
+    /// The level was completed, play the level at this index.
+    NextLevel(usize),
+    /// The last level was completed.
+    Completed,
+    /// The snake died but there are lives left, play the same level again.
+    Retry,
+    /// No lives left or the game was aborted.
+    GameOver,
+}
+
+/// An ordered list of [`Level`]s played with a limited amount of lives, the score carries over from one level to the next.
+/// # Examples
+/// ```
+/// # use snake3::GameState;
+/// use snake3::snake::{Campaign, CampaignStep};
+/// let mut campaign = Campaign::builtin();
+/// let mut game = campaign.start_level();
+/// game.start();
+/// // ... play until the game is finished
+/// # game.abort();
+/// match campaign.finish_level(&game) {
+///     CampaignStep::NextLevel(_) | CampaignStep::Retry => game = campaign.start_level(),
+///     CampaignStep::Completed | CampaignStep::GameOver => {}
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Campaign {
+    levels: Vec<Level>,
+    lives: u8,
+    lives_left: u8,
+    current: usize,
+    score: u64,
+}
+
+impl Campaign {
+    /// Returns a campaign starting at the first level.
+    /// # Panics
+    /// - If there are no levels or lives.
+    pub fn new(levels: Vec<Level>, lives: u8) -> Self {
+        if levels.is_empty() || lives == 0 {
+            panic!("A campaign needs at least one level and one life.")
+        }
+        Campaign {
+            levels,
+            lives,
+            lives_left: lives,
+            current: 0,
+            score: 0,
+        }
+    }
+    /// The campaign that comes with the crate.
+    pub fn builtin() -> Self {
+        let levels = BUILTIN_LEVELS
+            .iter()
+            .map(|(name, map, target, max_apples, difficulty)| {
+                Level::new(name, map.parse().expect("Builtin maps are valid."), *target)
+                    .with_max_apples(*max_apples)
+                    .with_difficulty(DifficultyCurve::preset(*difficulty))
+            })
+            .collect();
+        Campaign::new(levels, 3)
+    }
+    pub fn levels(&self) -> &[Level] {
+        &self.levels
+    }
+    pub fn current_index(&self) -> usize {
+        self.current
+    }
+    pub fn current_level(&self) -> &Level {
+        &self.levels[self.current]
+    }
+    pub fn lives_left(&self) -> u8 {
+        self.lives_left
+    }
+    /// Score carried over from the completed levels.
+    pub fn score(&self) -> u64 {
+        self.score
+    }
+    /// Restarts the campaign from `index` with full lives and no score. <br>
+    /// Returns `false` if the level does not exist or is not unlocked in `progress`.
+    pub fn select_level(&mut self, index: usize, progress: &CampaignProgress) -> bool {
+        if index >= self.levels.len() || !progress.is_unlocked(index) {
+            return false;
+        }
+        self.current = index;
+        self.lives_left = self.lives;
+        self.score = 0;
+        true
+    }
+    /// Returns a new game for the current level, with the carried score and the
+    /// win condition of scoring [`Level::target_apples`] more points.
+    pub fn start_level(&self) -> SnakeGame {
+        let level = self.current_level();
+        let mut game = SnakeGame::from_map(&level.map);
+        game.scoring_mut().carry(self.score);
+        game.set_win_conditions(vec![WinCondition::TargetScore(
+            self.score.saturating_add(level.target_apples as u64),
+        )]);
+        level.spawn_apples(&mut game);
+        game
+    }
+    /// Moves the campaign forward using the [result](`SnakeGame::result`) of a finished level game.
+    pub fn finish_level(&mut self, game: &SnakeGame) -> CampaignStep {
+        match game.result() {
+            Some(GameResult::Won | GameResult::RivalDied(_)) => {
+                self.score = game.score();
+                if self.current + 1 < self.levels.len() {
+                    self.current += 1;
+                    CampaignStep::NextLevel(self.current)
+                } else {
+                    CampaignStep::Completed
+                }
+            }
+            Some(GameResult::Died(_) | GameResult::Draw) => {
+                self.lives_left = self.lives_left.saturating_sub(1);
+                if self.lives_left == 0 {
+                    CampaignStep::GameOver
+                } else {
+                    CampaignStep::Retry
+                }
+            }
+            Some(GameResult::Aborted) | None => CampaignStep::GameOver,
+        }
+    }
+}
+
+/// Levels unlocked and best scores of a [`Campaign`], it can be saved to disk between runs.
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct CampaignProgress {
+    /// How many levels can be selected, the first one is always unlocked.
+    pub unlocked: usize,
+    /// Best score at the end of each level.
+    pub best_scores: Vec<u64>,
+}
+
+impl CampaignProgress {
+    pub fn is_unlocked(&self, index: usize) -> bool {
+        index == 0 || index < self.unlocked
+    }
+    /// Records that `level` was completed with `score`, unlocking the next one.
+    pub fn record(&mut self, level: usize, score: u64) {
+        self.unlocked = self.unlocked.max(level + 2);
+        if self.best_scores.len() <= level {
+            self.best_scores.resize(level + 1, 0);
+        }
+        self.best_scores[level] = self.best_scores[level].max(score);
+    }
+    /// Reads the progress from `path`, a missing file is an empty progress.
+    pub fn load(path: &Path) -> io::Result<Self> {
+        match fs::read_to_string(path) {
+            Ok(text) => Self::parse(&text).ok_or_else(|| {
+                io::Error::new(io::ErrorKind::InvalidData, "Invalid progress file.")
+            }),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(error) => Err(error),
+        }
+    }
+    /// Writes the progress to `path`, creating the parent directories.
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, self.serialize())
+    }
+    fn serialize(&self) -> String {
+        let best: Vec<String> = self.best_scores.iter().map(u64::to_string).collect();
+        format!("unlocked={}\nbest={}\n", self.unlocked, best.join(","))
+    }
+    fn parse(text: &str) -> Option<Self> {
+        let mut progress = Self::default();
+        for line in text.lines().filter(|line| !line.trim().is_empty()) {
+            let (key, value) = line.split_once('=')?;
+            match key.trim() {
+                "unlocked" => progress.unlocked = value.trim().parse().ok()?,
+                "best" if value.trim().is_empty() => {}
+                "best" => {
+                    progress.best_scores = value
+                        .split(',')
+                        .map(|score| score.trim().parse().ok())
+                        .collect::<Option<_>>()?
+                }
+                _ => return None,
+            }
+        }
+        Some(progress)
+    }
+}
+
+type BuiltinLevel = (&'static str, &'static str, u16, usize, Difficulty);
+
+const BUILTIN_LEVELS: [BuiltinLevel; 4] = [
+    (
+        "Garden",
+        "
+########################################
+#......................................#
+#......................................#
+#......................................#
+#......................................#
+#......................................#
+#..................>...................#
+#......................................#
+#......................................#
+#......................................#
+#......................................#
+#......................................#
+########################################
+",
+        5,
+        1,
+        Difficulty::Easy,
+    ),
+    (
+        "Pillars",
+        "
+########################################
+#......................................#
+#......##..........................##..#
+#......##..........................##..#
+#......................................#
+#......................................#
+#..................>...................#
+#......................................#
+#......................................#
+#......##..........................##..#
+#......##..........................##..#
+#......................................#
+########################################
+",
+        8,
+        2,
+        Difficulty::Normal,
+    ),
+    (
+        "Corridors",
+        "
+########################################
+#......................................#
+#..#################..###############..#
+#......................................#
+#......................................#
+#..#################..###############..#
+#..................>...................#
+#..#################..###############..#
+#......................................#
+#......................................#
+#..#################..###############..#
+#......................................#
+########################################
+",
+        10,
+        2,
+        Difficulty::Normal,
+    ),
+    (
+        "Cross",
+        "
+########################################
+#...................#..................#
+#...................#..................#
+#..1................#..................#
+#...................#..................#
+#......................................#
+#.......>..............................#
+#......................................#
+#...................#..................#
+#...................#...............1..#
+#...................#..................#
+#...................#..................#
+########################################
+",
+        12,
+        1,
+        Difficulty::Hard,
+    ),
+];
+
+#[cfg(test)]
+mod tests {
+    use crate::snake::{DeathCause, GameState};
+
+    use super::*;
+
+    fn finished_game(campaign: &Campaign, won: bool) -> SnakeGame {
+        let mut game = campaign.start_level();
+        game.start();
+        if won {
+            let target = campaign.current_level().target_apples;
+            game.scoring_mut().add_bonus(target as u64);
+            game.check_win_conditions();
+        } else {
+            game.snake.body[0].x = -1;
+            game.tick();
+        }
+        game
+    }
+
+    #[test]
+    fn campaign_builtin_levels_are_valid() {
+        let campaign = Campaign::builtin();
+        assert_eq!(campaign.levels().len(), 4);
+        for level in campaign.levels() {
+            assert!(level.map.start.is_some());
+        }
+    }
+
+    #[test]
+    fn campaign_start_level() {
+        let campaign = Campaign::builtin();
+        let game = campaign.start_level();
+        assert_eq!(game.dimensions(), (40, 13));
+        assert_eq!(game.entities.len(), 1);
+        assert_eq!(game.win_conditions(), [WinCondition::TargetScore(5)]);
+    }
+
+    #[test]
+    fn campaign_win_carries_score_over() {
+        let mut campaign = Campaign::builtin();
+        let game = finished_game(&campaign, true);
+        assert_eq!(game.get_state(), GameState::Won);
+        assert_eq!(campaign.finish_level(&game), CampaignStep::NextLevel(1));
+        assert_eq!(campaign.score(), 5);
+        let game = campaign.start_level();
+        assert_eq!(game.score(), 5);
+        assert_eq!(game.entities.len(), 2);
+        assert_eq!(game.win_conditions(), [WinCondition::TargetScore(13)]);
+    }
+
+    #[test]
+    fn campaign_lives() {
+        let mut campaign = Campaign::builtin();
+        let game = finished_game(&campaign, false);
+        assert_eq!(game.result(), Some(GameResult::Died(DeathCause::Wall)));
+        assert_eq!(campaign.finish_level(&game), CampaignStep::Retry);
+        assert_eq!(campaign.finish_level(&game), CampaignStep::Retry);
+        assert_eq!(campaign.finish_level(&game), CampaignStep::GameOver);
+        assert_eq!(campaign.lives_left(), 0);
+    }
+
+    #[test]
+    fn campaign_completed() {
+        let mut campaign = Campaign::builtin();
+        for index in 1..4 {
+            let game = finished_game(&campaign, true);
+            assert_eq!(campaign.finish_level(&game), CampaignStep::NextLevel(index));
+        }
+        let game = finished_game(&campaign, true);
+        assert_eq!(campaign.finish_level(&game), CampaignStep::Completed);
+    }
+
+    #[test]
+    fn campaign_select_level() {
+        let mut campaign = Campaign::builtin();
+        let mut progress = CampaignProgress::default();
+        assert!(campaign.select_level(0, &progress));
+        assert!(!campaign.select_level(1, &progress));
+        progress.record(0, 5);
+        assert!(campaign.select_level(1, &progress));
+        assert_eq!(campaign.current_index(), 1);
+        assert!(!campaign.select_level(9, &progress));
+    }
+
+    #[test]
+    fn campaign_progress_record_and_parse() {
+        let mut progress = CampaignProgress::default();
+        progress.record(1, 12);
+        progress.record(1, 10);
+        progress.record(0, 4);
+        assert_eq!(progress.unlocked, 3);
+        assert_eq!(progress.best_scores, vec![4, 12]);
+        let text = progress.serialize();
+        assert_eq!(text, "unlocked=3\nbest=4,12\n");
+        assert_eq!(CampaignProgress::parse(&text), Some(progress));
+        assert_eq!(
+            CampaignProgress::parse("unlocked=1\nbest=\n")
+                .unwrap()
+                .unlocked,
+            1
+        );
+        assert_eq!(CampaignProgress::parse("what=1"), None);
+    }
+
+    #[test]
+    fn campaign_progress_save_and_load() {
+        let path = std::env::temp_dir()
+            .join(format!("snake3-progress-{}", std::process::id()))
+            .join("progress");
+        assert_eq!(
+            CampaignProgress::load(&path).unwrap(),
+            CampaignProgress::default()
+        );
+        let mut progress = CampaignProgress::default();
+        progress.record(0, 7);
+        progress.save(&path).unwrap();
+        assert_eq!(CampaignProgress::load(&path).unwrap(), progress);
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+}
@@ -0,0 +1,183 @@
+use std::time::Duration;
+
+/// Fixed timestep clock that turns real elapsed time into logical game ticks. <br>
+/// Feed it the time since the last frame with [`GameClock::update`] and call
+/// [`SnakeGame::tick`](`super::SnakeGame::tick`) as many times as it returns, rendering
+/// can happen as often as we want in between.
+/// # Examples
+/// ```
+/// use std::time::Duration;
+/// use snake3::snake::GameClock;
+/// let mut clock = GameClock::new(Duration::from_millis(100));
+/// assert_eq!(clock.update(Duration::from_millis(250)), 2);
+/// assert_eq!(clock.alpha(), 0.5);
+/// ```
+#[derive(Debug, Clone)]
+pub struct GameClock {
+    interval: Duration,
+    accumulator: Duration,
+    time_scale: f32,
+    paused: bool,
+    max_ticks_per_update: u32,
+}
+
+impl GameClock {
+    /// Returns a clock that emits one tick every `interval`.
+    /// # Panics
+    /// - If `interval` is zero.
+    pub fn new(interval: Duration) -> Self {
+        if interval.is_zero() {
+            panic!("The clock interval can't be zero.")
+        }
+        GameClock {
+            interval,
+            accumulator: Duration::ZERO,
+            time_scale: 1.0,
+            paused: false,
+            max_ticks_per_update: 5,
+        }
+    }
+    /// Adds `elapsed` real time (scaled by [`GameClock::time_scale`]) and returns how many
+    /// ticks should run now, at most [`GameClock::set_max_ticks_per_update`]. <br>
+    /// A paused clock never ticks.
+    pub fn update(&mut self, elapsed: Duration) -> u32 {
+        if self.paused {
+            return 0;
+        }
+        self.accumulator += self.scaled(elapsed);
+        let mut ticks = 0;
+        while self.accumulator >= self.interval {
+            self.accumulator -= self.interval;
+            ticks += 1;
+            if ticks == self.max_ticks_per_update {
+                // Drop the backlog instead of trying to catch up forever
+                self.accumulator = Duration::ZERO;
+                break;
+            }
+        }
+        ticks
+    }
+    /// Returns the real time left until the next tick, useful as a timeout when waiting for input.
+    pub fn until_next_tick(&self) -> Duration {
+        let left = self.interval.saturating_sub(self.accumulator);
+        if self.time_scale == 1.0 || self.time_scale == 0.0 {
+            left
+        } else {
+            left.div_f64(self.time_scale as f64)
+        }
+    }
+    fn scaled(&self, elapsed: Duration) -> Duration {
+        // Avoid float rounding on the common case
+        if self.time_scale == 1.0 {
+            elapsed
+        } else {
+            elapsed.mul_f64(self.time_scale as f64)
+        }
+    }
+    /// Returns how far we are into the current tick, from `0.0` to `1.0`, to interpolate rendering.
+    pub fn alpha(&self) -> f32 {
+        self.accumulator.as_secs_f32() / self.interval.as_secs_f32()
+    }
+    /// Returns the time between ticks.
+    pub fn interval(&self) -> Duration {
+        self.interval
+    }
+    /// Changes the time between ticks, progress into the current tick is kept.
+    /// # Panics
+    /// - If `interval` is zero.
+    pub fn set_interval(&mut self, interval: Duration) {
+        if interval.is_zero() {
+            panic!("The clock interval can't be zero.")
+        }
+        self.interval = interval;
+    }
+    /// Returns the speed at which time passes, `1.0` by default.
+    pub fn time_scale(&self) -> f32 {
+        self.time_scale
+    }
+    /// Changes the speed at which time passes, `0.5` is slow motion and `2.0` double speed. <br>
+    /// Negative values are treated as `0.0`.
+    pub fn set_time_scale(&mut self, time_scale: f32) {
+        self.time_scale = time_scale.max(0.0);
+    }
+    /// Limits how many ticks a single [`GameClock::update`] can return, `5` by default.
+    pub fn set_max_ticks_per_update(&mut self, max_ticks: u32) {
+        self.max_ticks_per_update = max_ticks.max(1);
+    }
+    /// Stops the clock from ticking.
+    pub fn pause(&mut self) {
+        self.paused = true;
+    }
+    /// Lets the clock tick again.
+    pub fn resume(&mut self) {
+        self.paused = false;
+    }
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ms(millis: u64) -> Duration {
+        Duration::from_millis(millis)
+    }
+
+    #[test]
+    fn game_clock_update() {
+        let mut clock = GameClock::new(ms(100));
+        assert_eq!(clock.update(ms(50)), 0);
+        assert_eq!(clock.update(ms(50)), 1);
+        assert_eq!(clock.update(ms(230)), 2);
+        assert_eq!(clock.until_next_tick(), ms(70));
+    }
+
+    #[test]
+    fn game_clock_max_ticks_per_update() {
+        let mut clock = GameClock::new(ms(100));
+        clock.set_max_ticks_per_update(3);
+        assert_eq!(clock.update(ms(1000)), 3);
+        assert_eq!(clock.alpha(), 0.0);
+    }
+
+    #[test]
+    fn game_clock_pause() {
+        let mut clock = GameClock::new(ms(100));
+        clock.pause();
+        assert!(clock.is_paused());
+        assert_eq!(clock.update(ms(500)), 0);
+        clock.resume();
+        assert_eq!(clock.update(ms(100)), 1);
+    }
+
+    #[test]
+    fn game_clock_time_scale() {
+        let mut clock = GameClock::new(ms(100));
+        clock.set_time_scale(0.5);
+        assert_eq!(clock.update(ms(100)), 0);
+        assert_eq!(clock.until_next_tick(), ms(100));
+        assert_eq!(clock.update(ms(100)), 1);
+        clock.set_time_scale(2.0);
+        assert_eq!(clock.update(ms(100)), 2);
+        clock.set_time_scale(-1.0);
+        assert_eq!(clock.time_scale(), 0.0);
+        assert_eq!(clock.update(ms(1000)), 0);
+    }
+
+    #[test]
+    fn game_clock_set_interval() {
+        let mut clock = GameClock::new(ms(100));
+        clock.update(ms(60));
+        clock.set_interval(ms(50));
+        assert_eq!(clock.update(ms(0)), 1);
+        assert_eq!(clock.interval(), ms(50));
+    }
+
+    #[test]
+    #[should_panic(expected = "The clock interval can't be zero.")]
+    fn game_clock_zero_interval() {
+        GameClock::new(Duration::ZERO);
+    }
+}
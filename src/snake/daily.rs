@@ -0,0 +1,330 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::{fmt, str::FromStr};
+
+use super::{
+    HazardDamage, HazardGrowth, HazardRules, Health, Movement, Recording, SeededRng, SnakeGame,
+};
+
+/// Size of the board of every daily challenge, small enough for the smallest supported terminal.
+pub const DAILY_COLUMNS: i16 = 60;
+pub const DAILY_ROWS: i16 = 18;
+
+/// A day of the calendar, in UTC so everyone gets the same day at the same time.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Copy, Clone)]
+pub struct Date {
+    pub year: i32,
+    pub month: u8,
+    pub day: u8,
+}
+
+impl Date {
+    /// The day `days` after the 1st of January of 1970.
+    /// # Examples
+    /// ```
+    /// use snake3::snake::Date;
+    /// assert_eq!(Date::from_unix_days(11016).to_string(), "2000-02-29");
+    /// ```
+    pub fn from_unix_days(days: i64) -> Self {
+        // Days to civil date, from Howard Hinnant's date algorithms
+        let z = days + 719_468;
+        let era = z.div_euclid(146_097);
+        let day_of_era = z.rem_euclid(146_097);
+        let year_of_era =
+            (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let shifted_month = (5 * day_of_year + 2) / 153;
+        let day = (day_of_year - (153 * shifted_month + 2) / 5 + 1) as u8;
+        let month = if shifted_month < 10 {
+            shifted_month + 3
+        } else {
+            shifted_month - 9
+        } as u8;
+        let year = (year_of_era + era * 400 + i64::from(month <= 2)) as i32;
+        Date { year, month, day }
+    }
+    /// Days since the 1st of January of 1970, the opposite of [`Date::from_unix_days`].
+    pub fn unix_days(&self) -> i64 {
+        let year = i64::from(self.year) - i64::from(self.month <= 2);
+        let era = year.div_euclid(400);
+        let year_of_era = year.rem_euclid(400);
+        let month = i64::from(self.month);
+        let day_of_year = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5
+            + i64::from(self.day)
+            - 1;
+        let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+        era * 146_097 + day_of_era - 719_468
+    }
+    /// The current day in UTC.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn today() -> Self {
+        let seconds = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_secs());
+        Date::from_unix_days((seconds / 86_400) as i64)
+    }
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+impl FromStr for Date {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("Invalid date '{s}', use YYYY-MM-DD.");
+        let mut parts = s.trim().splitn(3, '-');
+        let mut next = || parts.next().ok_or_else(invalid);
+        let date = Date {
+            year: next()?.parse().map_err(|_| invalid())?,
+            month: next()?.parse().map_err(|_| invalid())?,
+            day: next()?.parse().map_err(|_| invalid())?,
+        };
+        // Days that don't exist come back as another one
+        if Date::from_unix_days(date.unix_days()) != date {
+            return Err(invalid());
+        }
+        Ok(date)
+    }
+}
+
+/// Rules that change from one daily challenge to another.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum DailyVariant {
+    /// Just apples.
+    Classic,
+    /// The snake has [`Health`] and starves if it does not eat.
+    Survival,
+    /// The borders of the board close in and kill the snake.
+    Shrink,
+    /// Turning back [reverses](`super::Snake::reverse`) the snake.
+    Reverse,
+    /// The snake moves in [eight directions](`Movement::EightWay`).
+    EightWay,
+}
+
+impl DailyVariant {
+    pub const ALL: [DailyVariant; 5] = [
+        DailyVariant::Classic,
+        DailyVariant::Survival,
+        DailyVariant::Shrink,
+        DailyVariant::Reverse,
+        DailyVariant::EightWay,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            DailyVariant::Classic => "Classic",
+            DailyVariant::Survival => "Survival",
+            DailyVariant::Shrink => "Shrink",
+            DailyVariant::Reverse => "Reverse",
+            DailyVariant::EightWay => "Eight way",
+        }
+    }
+}
+
+/// The game of the day: the same seed, rules and board for everyone playing on the same [`Date`].
+/// # Examples
+/// ```
+/// use snake3::snake::{DailyChallenge, Date};
+/// let date: Date = "2026-10-18".parse().unwrap();
+/// let challenge = DailyChallenge::for_date(date);
+/// assert_eq!(challenge, DailyChallenge::for_date(date));
+/// let game = challenge.new_game();
+/// assert_eq!(game.seed(), Some(challenge.seed));
+/// ```
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub struct DailyChallenge {
+    pub date: Date,
+    pub seed: u64,
+    pub variant: DailyVariant,
+}
+
+impl DailyChallenge {
+    pub fn for_date(date: Date) -> Self {
+        let seed = SeededRng::new(date.unix_days() as u64).next_u64();
+        let variant = DailyVariant::ALL[(seed % DailyVariant::ALL.len() as u64) as usize];
+        DailyChallenge {
+            date,
+            seed,
+            variant,
+        }
+    }
+    /// A game of [`DAILY_COLUMNS`] by [`DAILY_ROWS`] with the seed and rules of the day.
+    pub fn new_game(&self) -> SnakeGame {
+        let mut game = SnakeGame::new(DAILY_COLUMNS, DAILY_ROWS, None, None);
+        game.set_seed(Some(self.seed));
+        match self.variant {
+            DailyVariant::Classic => {}
+            DailyVariant::Survival => game.snake.set_health(Some(Health::new(120))),
+            DailyVariant::Shrink => game.set_hazard_rules(HazardRules {
+                growth: HazardGrowth::Shrink,
+                start: 100,
+                every: 50,
+                damage: HazardDamage::Deadly,
+            }),
+            DailyVariant::Reverse => game.snake.set_reverse_on_opposite(true),
+            DailyVariant::EightWay => game.snake.set_movement(Movement::EightWay),
+        }
+        game
+    }
+    /// Describes the challenge for [`SnakeGame::start_recording`].
+    pub fn config(&self) -> String {
+        format!("daily {} {}", self.date, self.variant.name())
+    }
+    /// A line to paste in a chat to compare runs of the day, with the [hash](`Recording::hash`) of the run.
+    pub fn export_line(&self, result: &DailyResult) -> String {
+        format!(
+            "snake3 daily {} ({}): {} points, replay {:016x}",
+            self.date,
+            self.variant.name(),
+            result.score,
+            result.replay_hash
+        )
+    }
+}
+
+/// Best run of a day.
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Default)]
+pub struct DailyResult {
+    pub score: u64,
+    /// [Hash](`Recording::hash`) of the best run, tells apart runs with the same score.
+    pub replay_hash: u64,
+    /// Runs played that day.
+    pub attempts: u32,
+}
+
+/// Results of the daily challenges played, it can be saved to disk between runs.
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct DailyResults {
+    results: BTreeMap<Date, DailyResult>,
+}
+
+impl DailyResults {
+    pub fn get(&self, date: Date) -> Option<&DailyResult> {
+        self.results.get(&date)
+    }
+    /// Every day played, oldest first.
+    pub fn iter(&self) -> impl Iterator<Item = (&Date, &DailyResult)> {
+        self.results.iter()
+    }
+    /// Adds a finished run of the challenge of `date` and returns the result of the day.
+    pub fn record(&mut self, date: Date, recording: &Recording) -> DailyResult {
+        let result = self.results.entry(date).or_default();
+        if result.attempts == 0 || recording.score > result.score {
+            result.score = recording.score;
+            result.replay_hash = recording.hash();
+        }
+        result.attempts += 1;
+        *result
+    }
+    /// Reads the results from `path`, a missing file means no results.
+    pub fn load(path: &Path) -> io::Result<Self> {
+        match fs::read_to_string(path) {
+            Ok(text) => Self::parse(&text).ok_or_else(|| {
+                io::Error::new(io::ErrorKind::InvalidData, "Invalid daily results file.")
+            }),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(error) => Err(error),
+        }
+    }
+    /// Writes the results to `path`, creating the parent directories.
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, self.serialize())
+    }
+    fn serialize(&self) -> String {
+        self.results
+            .iter()
+            .map(|(date, result)| {
+                format!(
+                    "{date}={},{:016x},{}\n",
+                    result.score, result.replay_hash, result.attempts
+                )
+            })
+            .collect()
+    }
+    fn parse(text: &str) -> Option<Self> {
+        let mut results = Self::default();
+        for line in text.lines().filter(|line| !line.trim().is_empty()) {
+            let (date, values) = line.split_once('=')?;
+            let mut values = values.split(',').map(str::trim);
+            let result = DailyResult {
+                score: values.next()?.parse().ok()?,
+                replay_hash: u64::from_str_radix(values.next()?, 16).ok()?,
+                attempts: values.next()?.parse().ok()?,
+            };
+            results.results.insert(date.parse().ok()?, result);
+        }
+        Some(results)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::snake::SnakeDirection;
+
+    #[test]
+    fn dates_and_days() {
+        assert_eq!(Date::from_unix_days(0).to_string(), "1970-01-01");
+        assert_eq!(Date::from_unix_days(-1).to_string(), "1969-12-31");
+        let date: Date = "2026-10-18".parse().unwrap();
+        assert_eq!(Date::from_unix_days(date.unix_days()), date);
+        assert_eq!(
+            date.unix_days() - "2026-01-01".parse::<Date>().unwrap().unix_days(),
+            290
+        );
+        assert!("2026-02-29".parse::<Date>().is_err());
+        assert!("yesterday".parse::<Date>().is_err());
+    }
+
+    #[test]
+    fn daily_challenges_change_every_day() {
+        let first = Date::from_unix_days(20_000);
+        let challenges: Vec<DailyChallenge> = (0..30)
+            .map(|day| DailyChallenge::for_date(Date::from_unix_days(20_000 + day)))
+            .collect();
+        assert_eq!(challenges[0], DailyChallenge::for_date(first));
+        assert!(
+            challenges
+                .iter()
+                .all(|challenge| challenge.seed != challenges[1].seed
+                    || challenge.date == challenges[1].date)
+        );
+        for variant in DailyVariant::ALL {
+            assert!(
+                challenges
+                    .iter()
+                    .any(|challenge| challenge.variant == variant)
+            );
+        }
+    }
+
+    #[test]
+    fn daily_results_keep_the_best_run() {
+        let date = Date::from_unix_days(20_379);
+        let mut recording = Recording::new(Some(1), "daily");
+        recording.record(4, SnakeDirection::Up);
+        recording.score = 7;
+        let mut results = DailyResults::default();
+        results.record(date, &recording);
+        let best_hash = recording.hash();
+        recording.score = 3;
+        let result = results.record(date, &recording);
+        assert_eq!(result.score, 7);
+        assert_eq!(result.replay_hash, best_hash);
+        assert_eq!(result.attempts, 2);
+        assert_eq!(DailyResults::parse(&results.serialize()), Some(results));
+        let line = DailyChallenge::for_date(date).export_line(&result);
+        assert!(line.starts_with(&format!("snake3 daily {date} (")));
+        assert!(line.ends_with(&format!("7 points, replay {best_hash:016x}")));
+    }
+}
@@ -0,0 +1,222 @@
+use std::{fmt, str::FromStr, time::Duration};
+
+/// Named [`DifficultyCurve`] presets.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum Difficulty {
+    Easy,
+    Normal,
+    Hard,
+    Insane,
+}
+
+impl Difficulty {
+    pub const ALL: [Difficulty; 4] = [
+        Difficulty::Easy,
+        Difficulty::Normal,
+        Difficulty::Hard,
+        Difficulty::Insane,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Difficulty::Easy => "easy",
+            Difficulty::Normal => "normal",
+            Difficulty::Hard => "hard",
+            Difficulty::Insane => "insane",
+        }
+    }
+}
+
+impl fmt::Display for Difficulty {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Difficulty {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Difficulty::ALL
+            .into_iter()
+            .find(|difficulty| difficulty.name().eq_ignore_ascii_case(s))
+            .ok_or_else(|| format!("Unknown difficulty '{s}'."))
+    }
+}
+
+/// How the time between ticks changes as the score goes up, feed the result of
+/// [`DifficultyCurve::interval`] to [`GameClock::set_interval`](`super::GameClock::set_interval`).
+/// # Examples
+/// ```
+/// use std::time::Duration;
+/// use snake3::snake::{Difficulty, DifficultyCurve};
+/// let curve = DifficultyCurve::preset(Difficulty::Normal);
+/// assert_eq!(curve.interval(0), Duration::from_millis(500));
+/// assert_eq!(curve.interval(3), Duration::from_millis(470));
+/// ```
+#[derive(Debug, PartialEq, Clone)]
+pub enum DifficultyCurve {
+    /// Starts at `start` and takes `step` off for every point, never going below `min`.
+    Linear {
+        start: Duration,
+        step: Duration,
+        min: Duration,
+    },
+    /// Starts at `start` and multiplies by `factor` for every point, never going below `min`.
+    /// A `factor` above one slows the game down instead, up to [`Duration::MAX`].
+    Exponential {
+        start: Duration,
+        factor: f64,
+        min: Duration,
+    },
+    /// Starts at `start` and takes `step` off every `points_per_level` points, never going below `min`.
+    Stepped {
+        start: Duration,
+        step: Duration,
+        points_per_level: u64,
+        min: Duration,
+    },
+    /// Uses the interval of the highest score threshold reached, the first entry applies below every threshold.
+    ScoreTable(Vec<(u64, Duration)>),
+}
+
+impl DifficultyCurve {
+    /// Returns the curve for a named [`Difficulty`].
+    pub fn preset(difficulty: Difficulty) -> Self {
+        let ms = Duration::from_millis;
+        match difficulty {
+            Difficulty::Easy => DifficultyCurve::Linear {
+                start: ms(600),
+                step: ms(5),
+                min: ms(200),
+            },
+            Difficulty::Normal => DifficultyCurve::Linear {
+                start: ms(500),
+                step: ms(10),
+                min: ms(100),
+            },
+            Difficulty::Hard => DifficultyCurve::Exponential {
+                start: ms(300),
+                factor: 0.97,
+                min: ms(70),
+            },
+            Difficulty::Insane => DifficultyCurve::Stepped {
+                start: ms(150),
+                step: ms(15),
+                points_per_level: 3,
+                min: ms(40),
+            },
+        }
+    }
+    /// Returns the time between ticks for the given score.
+    pub fn interval(&self, score: u64) -> Duration {
+        match self {
+            DifficultyCurve::Linear { start, step, min } => start
+                .saturating_sub(step.saturating_mul(score.min(u32::MAX as u64) as u32))
+                .max(*min),
+            DifficultyCurve::Exponential { start, factor, min } => {
+                // Large scores overflow the float, a curve that slows down then stays as slow as it gets
+                let seconds = start.as_secs_f64() * factor.powf(score as f64);
+                Duration::try_from_secs_f64(seconds.max(0.0))
+                    .unwrap_or(Duration::MAX)
+                    .max(*min)
+            }
+            DifficultyCurve::Stepped {
+                start,
+                step,
+                points_per_level,
+                min,
+            } => {
+                let level = score / (*points_per_level).max(1);
+                start
+                    .saturating_sub(step.saturating_mul(level.min(u32::MAX as u64) as u32))
+                    .max(*min)
+            }
+            DifficultyCurve::ScoreTable(table) => table
+                .iter()
+                .rev()
+                .find(|(threshold, _)| score >= *threshold)
+                .or(table.first())
+                .map(|(_, interval)| *interval)
+                .unwrap_or(Duration::from_millis(500)),
+        }
+    }
+}
+
+impl Default for DifficultyCurve {
+    fn default() -> Self {
+        DifficultyCurve::preset(Difficulty::Normal)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ms(millis: u64) -> Duration {
+        Duration::from_millis(millis)
+    }
+
+    #[test]
+    fn difficulty_from_str() {
+        assert_eq!("hard".parse(), Ok(Difficulty::Hard));
+        assert_eq!("INSANE".parse(), Ok(Difficulty::Insane));
+        assert!("medium".parse::<Difficulty>().is_err());
+    }
+
+    #[test]
+    fn difficulty_curve_linear() {
+        let curve = DifficultyCurve::preset(Difficulty::Normal);
+        assert_eq!(curve.interval(0), ms(500));
+        assert_eq!(curve.interval(10), ms(400));
+        assert_eq!(curve.interval(40), ms(100));
+        assert_eq!(curve.interval(u64::MAX), ms(100));
+    }
+
+    #[test]
+    fn difficulty_curve_exponential() {
+        let curve = DifficultyCurve::Exponential {
+            start: ms(400),
+            factor: 0.5,
+            min: ms(60),
+        };
+        assert_eq!(curve.interval(0), ms(400));
+        assert_eq!(curve.interval(2), ms(100));
+        assert_eq!(curve.interval(5), ms(60));
+        assert_eq!(curve.interval(1 << 32), ms(60));
+        assert_eq!(curve.interval(u64::MAX), ms(60));
+
+        let slower = DifficultyCurve::Exponential {
+            start: ms(100),
+            factor: 1.5,
+            min: ms(60),
+        };
+        assert_eq!(slower.interval(2), ms(225));
+        assert_eq!(slower.interval(2000), Duration::MAX);
+        assert_eq!(slower.interval(u64::MAX), Duration::MAX);
+        let steady = DifficultyCurve::Exponential {
+            start: ms(100),
+            factor: 1.0,
+            min: ms(60),
+        };
+        assert_eq!(steady.interval(u64::MAX), ms(100));
+    }
+
+    #[test]
+    fn difficulty_curve_stepped() {
+        let curve = DifficultyCurve::preset(Difficulty::Insane);
+        assert_eq!(curve.interval(2), ms(150));
+        assert_eq!(curve.interval(3), ms(135));
+        assert_eq!(curve.interval(1000), ms(40));
+    }
+
+    #[test]
+    fn difficulty_curve_score_table() {
+        let curve = DifficultyCurve::ScoreTable(vec![(5, ms(300)), (10, ms(200)), (20, ms(100))]);
+        assert_eq!(curve.interval(0), ms(300));
+        assert_eq!(curve.interval(9), ms(300));
+        assert_eq!(curve.interval(10), ms(200));
+        assert_eq!(curve.interval(25), ms(100));
+        assert_eq!(DifficultyCurve::ScoreTable(Vec::new()).interval(3), ms(500));
+    }
+}
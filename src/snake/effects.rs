@@ -0,0 +1,236 @@
+/// Timed status effects a [`Snake`](`super::Snake`) can have, usually given by eating a [`PowerUp`](`super::PowerUp`).
+#[derive(Debug, PartialEq, Eq, Hash, Copy, Clone)]
+pub enum StatusEffect {
+    /// The snake can pass through its own body.
+    Ghost,
+    /// The snake goes through walls, coming out on the other side of the board when it leaves it.
+    InvincibleWalls,
+    /// Time goes slower, see [`StatusEffects::time_scale`].
+    SlowMotion,
+    /// Points are worth double, see [`StatusEffects::score_multiplier`].
+    DoubleScore,
+    /// Entities close to the head move towards it, see [`StatusEffects::magnet_radius`].
+    Magnet,
+    /// Up is down and left is right.
+    ReverseControls,
+    /// Turning back reverses the snake, see [`Snake::reverse`](`super::Snake::reverse`).
+    Reversible,
+}
+
+impl StatusEffect {
+    pub const ALL: [StatusEffect; 7] = [
+        StatusEffect::Ghost,
+        StatusEffect::InvincibleWalls,
+        StatusEffect::SlowMotion,
+        StatusEffect::DoubleScore,
+        StatusEffect::Magnet,
+        StatusEffect::ReverseControls,
+        StatusEffect::Reversible,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            StatusEffect::Ghost => "Ghost",
+            StatusEffect::InvincibleWalls => "Invincible walls",
+            StatusEffect::SlowMotion => "Slow motion",
+            StatusEffect::DoubleScore => "Double score",
+            StatusEffect::Magnet => "Magnet",
+            StatusEffect::ReverseControls => "Reverse controls",
+            StatusEffect::Reversible => "Reversible",
+        }
+    }
+    /// How the effect stacks when it is applied while still active.
+    pub fn stacking(&self) -> Stacking {
+        match self {
+            StatusEffect::DoubleScore | StatusEffect::SlowMotion => Stacking::Extend,
+            _ => Stacking::Refresh,
+        }
+    }
+}
+
+/// What happens when an effect is applied while it is still active.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum Stacking {
+    /// The remaining ticks become the longest of the two.
+    Refresh,
+    /// The new ticks are added to the remaining ones.
+    Extend,
+    /// The new effect is ignored.
+    Ignore,
+}
+
+/// An effect and how many ticks it has left.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub struct ActiveEffect {
+    pub effect: StatusEffect,
+    pub remaining: u32,
+}
+
+/// The status effects currently on a [`Snake`](`super::Snake`).
+/// # Examples
+/// ```
+/// use snake3::snake::{Snake, SnakeDirection, StatusEffect};
+/// let mut snake = Snake::new((5, 5), SnakeDirection::Right);
+/// snake.effects_mut().apply(StatusEffect::Ghost, 20);
+/// assert_eq!(snake.effects().remaining(StatusEffect::Ghost), 20);
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct StatusEffects {
+    active: Vec<ActiveEffect>,
+    magnet_radius: i16,
+}
+
+impl Default for StatusEffects {
+    fn default() -> Self {
+        StatusEffects {
+            active: Vec::new(),
+            magnet_radius: 4,
+        }
+    }
+}
+
+impl StatusEffects {
+    /// Applies `effect` for `ticks` using its default [`Stacking`]. Returns `true` if it was not active before.
+    pub fn apply(&mut self, effect: StatusEffect, ticks: u32) -> bool {
+        self.apply_with(effect, ticks, effect.stacking())
+    }
+    /// Applies `effect` for `ticks` with a custom [`Stacking`]. Returns `true` if it was not active before.
+    pub fn apply_with(&mut self, effect: StatusEffect, ticks: u32, stacking: Stacking) -> bool {
+        if ticks == 0 {
+            return false;
+        }
+        match self
+            .active
+            .iter_mut()
+            .find(|active| active.effect == effect)
+        {
+            Some(active) => {
+                match stacking {
+                    Stacking::Refresh => active.remaining = active.remaining.max(ticks),
+                    Stacking::Extend => active.remaining = active.remaining.saturating_add(ticks),
+                    Stacking::Ignore => {}
+                }
+                false
+            }
+            None => {
+                self.active.push(ActiveEffect {
+                    effect,
+                    remaining: ticks,
+                });
+                true
+            }
+        }
+    }
+    /// Removes `effect` right away. Returns `true` if it was active.
+    pub fn remove(&mut self, effect: StatusEffect) -> bool {
+        let before = self.active.len();
+        self.active.retain(|active| active.effect != effect);
+        before != self.active.len()
+    }
+    pub fn clear(&mut self) {
+        self.active.clear();
+    }
+    pub fn is_active(&self, effect: StatusEffect) -> bool {
+        self.remaining(effect) > 0
+    }
+    /// Ticks left for `effect`, `0` if it is not active.
+    pub fn remaining(&self, effect: StatusEffect) -> u32 {
+        self.active
+            .iter()
+            .find(|active| active.effect == effect)
+            .map_or(0, |active| active.remaining)
+    }
+    /// Every active effect, in the order they were applied.
+    pub fn active(&self) -> &[ActiveEffect] {
+        &self.active
+    }
+    /// Takes one tick off every effect and returns the ones that ran out.
+    pub fn tick(&mut self) -> Vec<StatusEffect> {
+        for active in self.active.iter_mut() {
+            active.remaining -= 1;
+        }
+        let expired = self
+            .active
+            .iter()
+            .filter(|active| active.remaining == 0)
+            .map(|active| active.effect)
+            .collect();
+        self.active.retain(|active| active.remaining > 0);
+        expired
+    }
+    /// `0.5` while [`StatusEffect::SlowMotion`] is active, `1.0` otherwise, feed it to
+    /// [`GameClock::set_time_scale`](`super::GameClock::set_time_scale`).
+    pub fn time_scale(&self) -> f32 {
+        if self.is_active(StatusEffect::SlowMotion) {
+            0.5
+        } else {
+            1.0
+        }
+    }
+    /// `2` while [`StatusEffect::DoubleScore`] is active, `1` otherwise.
+    pub fn score_multiplier(&self) -> u16 {
+        if self.is_active(StatusEffect::DoubleScore) {
+            2
+        } else {
+            1
+        }
+    }
+    /// Distance in cells at which [`StatusEffect::Magnet`] pulls entities, `4` by default.
+    pub fn magnet_radius(&self) -> i16 {
+        self.magnet_radius
+    }
+    pub fn set_magnet_radius(&mut self, radius: i16) {
+        self.magnet_radius = radius;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn status_effects_apply_and_tick() {
+        let mut effects = StatusEffects::default();
+        assert!(effects.apply(StatusEffect::Ghost, 2));
+        assert!(effects.apply(StatusEffect::Magnet, 1));
+        assert!(effects.is_active(StatusEffect::Ghost));
+        assert_eq!(effects.tick(), vec![StatusEffect::Magnet]);
+        assert_eq!(effects.remaining(StatusEffect::Ghost), 1);
+        assert_eq!(effects.tick(), vec![StatusEffect::Ghost]);
+        assert!(effects.active().is_empty());
+        assert!(!effects.apply(StatusEffect::Ghost, 0));
+    }
+
+    #[test]
+    fn status_effects_stacking() {
+        let mut effects = StatusEffects::default();
+        effects.apply(StatusEffect::Ghost, 10);
+        assert!(!effects.apply(StatusEffect::Ghost, 5));
+        assert_eq!(effects.remaining(StatusEffect::Ghost), 10);
+        effects.apply(StatusEffect::Ghost, 15);
+        assert_eq!(effects.remaining(StatusEffect::Ghost), 15);
+
+        effects.apply(StatusEffect::DoubleScore, 10);
+        effects.apply(StatusEffect::DoubleScore, 5);
+        assert_eq!(effects.remaining(StatusEffect::DoubleScore), 15);
+
+        effects.apply_with(StatusEffect::Magnet, 3, Stacking::Ignore);
+        effects.apply_with(StatusEffect::Magnet, 30, Stacking::Ignore);
+        assert_eq!(effects.remaining(StatusEffect::Magnet), 3);
+    }
+
+    #[test]
+    fn status_effects_modifiers() {
+        let mut effects = StatusEffects::default();
+        assert_eq!(effects.time_scale(), 1.0);
+        assert_eq!(effects.score_multiplier(), 1);
+        effects.apply(StatusEffect::SlowMotion, 1);
+        effects.apply(StatusEffect::DoubleScore, 1);
+        assert_eq!(effects.time_scale(), 0.5);
+        assert_eq!(effects.score_multiplier(), 2);
+        assert!(effects.remove(StatusEffect::SlowMotion));
+        assert!(!effects.remove(StatusEffect::SlowMotion));
+        effects.clear();
+        assert_eq!(effects.score_multiplier(), 1);
+    }
+}
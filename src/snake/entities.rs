@@ -2,6 +2,8 @@ use std::any::Any;
 
 use crate::impl_entity;
 
+use super::StatusEffect;
+
 /// Allows new entities to be created and added to the game. <br>
 /// # Examples
 /// ```
@@ -20,6 +22,24 @@ pub trait Entity: Any {
     fn as_any(&self) -> &dyn Any;
     fn x(&self) -> i16;
     fn y(&self) -> i16;
+    /// Layer of the board the entity is on, `0` by default.
+    fn z(&self) -> i16 {
+        0
+    }
+    /// Moves the entity, used by [`StatusEffect::Magnet`]. Entities that can't move can leave the default, which does nothing.
+    fn set_position(&mut self, _x: i16, _y: i16) {}
+    /// Moves the entity to another layer when it gets placed on the board.
+    /// Entities that leave the default, which does nothing, stay on the first layer.
+    fn set_z(&mut self, _z: i16) {}
+    /// The [`StatusEffect`] and its duration in ticks the snake gets when eating this entity, none by default.
+    fn effect(&self) -> Option<(StatusEffect, u32)> {
+        None
+    }
+    /// Name used to identify the entity on [`GameEvent`](`super::GameEvent`)s, defaults to the type name.
+    fn name(&self) -> &'static str {
+        let full_name = std::any::type_name::<Self>();
+        full_name.rsplit("::").next().unwrap_or(full_name)
+    }
 }
 
 impl dyn Entity {
@@ -32,9 +52,71 @@ impl dyn Entity {
 pub struct Apple {
     pub x: i16,
     pub y: i16,
+    pub z: i16,
+}
+
+impl_entity!(Apple, layered);
+
+/// Entity that gives the snake a [`StatusEffect`] when eaten.
+/// # Examples
+/// ```
+/// # use snake3::SnakeGame;
+/// use snake3::snake::{PowerUp, StatusEffect};
+/// let mut new_game = SnakeGame::new(10, 10, None, None);
+/// new_game.generate_entity(|x, y| Box::new(PowerUp::new(x, y, StatusEffect::Ghost, 30)));
+/// ```
+#[derive(Debug, Copy, Clone)]
+pub struct PowerUp {
+    pub x: i16,
+    pub y: i16,
+    pub z: i16,
+    pub effect: StatusEffect,
+    /// Ticks the effect lasts.
+    pub duration: u32,
+}
+
+impl PowerUp {
+    pub fn new(x: i16, y: i16, effect: StatusEffect, duration: u32) -> Self {
+        Self {
+            x,
+            y,
+            z: 0,
+            effect,
+            duration,
+        }
+    }
 }
 
-impl_entity!(Apple);
+impl Entity for PowerUp {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn x(&self) -> i16 {
+        self.x
+    }
+
+    fn y(&self) -> i16 {
+        self.y
+    }
+
+    fn z(&self) -> i16 {
+        self.z
+    }
+
+    fn set_position(&mut self, x: i16, y: i16) {
+        self.x = x;
+        self.y = y;
+    }
+
+    fn set_z(&mut self, z: i16) {
+        self.z = z;
+    }
+
+    fn effect(&self) -> Option<(StatusEffect, u32)> {
+        Some((self.effect, self.duration))
+    }
+}
 
 #[cfg(test)]
 mod tests {
@@ -42,7 +124,7 @@ mod tests {
 
     #[test]
     fn test_downcast_ref_correct_type() {
-        let apple = Apple { x: 3, y: 7 };
+        let apple = Apple { x: 3, y: 7, z: 0 };
         let entity: &dyn Entity = &apple;
 
         let downcasted = entity.downcast_ref::<Apple>();
@@ -75,4 +157,22 @@ mod tests {
         let downcasted = entity.downcast_ref::<Apple>();
         assert!(downcasted.is_none());
     }
+
+    #[test]
+    fn test_entity_name() {
+        let apple = Apple { x: 0, y: 0, z: 0 };
+        let entity: &dyn Entity = &apple;
+        assert_eq!(entity.name(), "Apple");
+    }
+
+    #[test]
+    fn test_entity_effect_and_position() {
+        let mut apple = Apple::new(1, 1);
+        assert_eq!(apple.effect(), None);
+        apple.set_position(2, 3);
+        assert_eq!((apple.x, apple.y), (2, 3));
+
+        let power_up = PowerUp::new(0, 0, StatusEffect::Magnet, 12);
+        assert_eq!(power_up.effect(), Some((StatusEffect::Magnet, 12)));
+    }
 }
@@ -0,0 +1,84 @@
+use super::{GameState, StatusEffect};
+
+/// Reason why the snake died.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum DeathCause {
+    /// The head went outside of the board.
+    Wall,
+    /// The head hit another part of the body.
+    SelfCollision,
+    /// The snake ran out of [`Health`](`super::Health`).
+    Starvation,
+    /// The head was on a [hazard](`super::Hazards`).
+    Hazard,
+    /// The head hit the other snake of a versus game, see [`SnakeGame::rival`](`super::SnakeGame::rival`).
+    Rival,
+    /// A rule of the game outside of [`SnakeGame::tick`](`super::SnakeGame::tick`) ended it, see
+    /// [`SnakeGame::kill`](`super::SnakeGame::kill`).
+    Rule,
+}
+
+/// Something that happened inside a [`SnakeGame`](`super::SnakeGame`). <br>
+/// Events get queued while the game runs and can be read with [`SnakeGame::drain_events`](`super::SnakeGame::drain_events`)
+/// or as they happen with [`SnakeGame::on_event`](`super::SnakeGame::on_event`).
+/// # Examples
+/// ```
+/// # use snake3::{SnakeGame, GameState};
+/// use snake3::snake::GameEvent;
+/// let mut new_game = SnakeGame::new(10, 10, None, None);
+/// new_game.set_state(GameState::Playing);
+/// new_game.tick();
+/// for event in new_game.drain_events() {
+///     if let GameEvent::Moved { x, y } = event {
+///         // Play a sound, draw some particles...
+///     }
+/// }
+/// ```
+#[derive(Debug, PartialEq, Clone)]
+pub enum GameEvent {
+    /// The snake head moved to a new position.
+    Moved { x: i16, y: i16 },
+    /// The snake head collided with an entity and it got removed from the game.
+    Ate {
+        entity: &'static str,
+        x: i16,
+        y: i16,
+    },
+    /// The snake scored `points` by eating, leaving the score at `total`.
+    Scored { points: u64, total: u64 },
+    /// The snake body got longer.
+    Grew { length: usize },
+    /// Eating gave the snake back `amount` of [`Health`](`super::Health`), leaving it at `health`.
+    Healed { amount: u32, health: u32 },
+    /// The snake hit a wall or itself, or starved.
+    Died { cause: DeathCause },
+    /// A new entity was placed on the board.
+    EntitySpawned {
+        entity: &'static str,
+        x: i16,
+        y: i16,
+    },
+    /// An entity was removed from the board without being eaten.
+    EntityExpired {
+        entity: &'static str,
+        x: i16,
+        y: i16,
+    },
+    /// The [rival](`super::SnakeGame::rival`) scored `points` by eating, leaving its score at `total`.
+    RivalScored { points: u64, total: u64 },
+    /// The [rival](`super::SnakeGame::rival`) hit a wall, itself or the snake.
+    RivalDied { cause: DeathCause },
+    /// `cells` more cells of the board became [hazards](`super::Hazards`).
+    HazardsGrew { cells: usize },
+    /// The [`GameState`] changed.
+    StateChanged { from: GameState, to: GameState },
+    /// A [`StatusEffect`] that was not active was applied to the snake.
+    EffectStarted { effect: StatusEffect },
+    /// A [`StatusEffect`] ran out.
+    EffectEnded { effect: StatusEffect },
+    /// The snake met a win condition.
+    Won,
+}
+
+/// Callback registered with [`SnakeGame::on_event`](`super::SnakeGame::on_event`).
+pub type EventListener = Box<dyn FnMut(&GameEvent)>;
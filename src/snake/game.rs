@@ -1,34 +1,59 @@
-use std::collections::HashSet;
-
-use super::random_range;
-
-use super::{Snake, SnakeDirection, entities::Entity};
-
-/// Represents the state of the game.
-#[derive(Debug, PartialEq, Copy, Clone)]
-pub enum GameState {
-    /// A new game that has not started yet.
-    New,
-    /// The game is currently in progress and the ticker should be counting.
-    Playing,
-    /// The game is paused; the ticker should not be counting.
-    Paused,
-    /// The game has ended due to player failure or a perfect run
-    /// at this point, points should be saved and a new game can start.
-    Ended,
-}
+use std::collections::{HashSet, VecDeque};
+
+use super::{
+    EntityKind, EntityRegistry, GamePlugin, HazardDamage, HazardRules, Hazards, Map, Player,
+    PortalPair, Portals, Recording, Scoring, ScoringRules, SeededRng, StatusEffect, Topology,
+    Verdict, random_index, random_range,
+};
+
+use super::{
+    Snake, SnakeDirection,
+    entities::Entity,
+    events::{DeathCause, EventListener, GameEvent},
+    outcome::{GameResult, WinCondition},
+    state::{GameState, InvalidTransition, StateTransition, TransitionHook},
+};
+
+/// How many [`GameEvent`]s wait for [`SnakeGame::drain_events`] at most, the oldest ones are dropped first.
+pub const MAX_QUEUED_EVENTS: usize = 1024;
 
 /// Holds all the data related to a game.
 #[allow(unused)]
 pub struct SnakeGame {
     state: GameState,
-    pub score: u16,
     pub columns: i16,
     pub rows: i16,
+    layers: i16,
     pub snake: Snake,
+    /// Second snake of a versus game, added with [`SnakeGame::add_rival`].
+    pub rival: Option<Snake>,
     private_value: &'static str, // Just for fun on docs.
     pub entities: Vec<Box<dyn Entity>>,
     game_board: Vec<(i16, i16)>,
+    walls: HashSet<(i16, i16)>,
+    portals: Portals,
+    hazards: Hazards,
+    scoring: Scoring,
+    rival_scoring: Scoring,
+    rival_start: Option<((i16, i16), SnakeDirection)>,
+    rival_hit: Option<Box<dyn Entity>>,
+    ticks: u64,
+    events: VecDeque<GameEvent>,
+    listeners: Vec<EventListener>,
+    win_conditions: Vec<WinCondition>,
+    result: Option<GameResult>,
+    starting_position: (i16, i16),
+    starting_direction: SnakeDirection,
+    countdown: u64,
+    countdown_left: u64,
+    paused_ticks: u64,
+    countdown_ticks: u64,
+    history: Vec<StateTransition>,
+    transition_hooks: Vec<TransitionHook>,
+    rng: Option<SeededRng>,
+    recording: Option<Recording>,
+    plugins: Vec<Box<dyn GamePlugin>>,
+    registry: EntityRegistry,
 }
 
 impl SnakeGame {
@@ -59,19 +84,171 @@ impl SnakeGame {
         if starting_position.0 > columns || starting_position.1 > rows {
             panic!("You can't create a snake outside of columns or rows range.")
         }
+        let snake_direction = snake_direction.unwrap_or(SnakeDirection::Right);
         SnakeGame {
             state: GameState::New,
-            score: 0,
             private_value: "easter_egg",
-            snake: Snake::new(
-                starting_position,
-                snake_direction.unwrap_or(SnakeDirection::Right),
-            ),
+            snake: Snake::new(starting_position, snake_direction),
+            rival: None,
             entities: Vec::new(),
             game_board: SnakeGame::game_board(&columns, &rows),
+            walls: HashSet::new(),
+            portals: Portals::default(),
+            hazards: Hazards::default(),
+            scoring: Scoring::default(),
+            rival_scoring: Scoring::default(),
+            rival_start: None,
+            rival_hit: None,
             columns,
             rows,
+            layers: 1,
+            ticks: 0,
+            events: VecDeque::new(),
+            listeners: Vec::new(),
+            win_conditions: vec![WinCondition::BoardFilled],
+            result: None,
+            starting_position,
+            starting_direction: snake_direction,
+            countdown: 0,
+            countdown_left: 0,
+            paused_ticks: 0,
+            countdown_ticks: 0,
+            history: Vec::new(),
+            transition_hooks: Vec::new(),
+            rng: None,
+            recording: None,
+            plugins: Vec::new(),
+            registry: EntityRegistry::builtin(),
+        }
+    }
+    /// Returns a new game with the size, walls and starting position of a [`Map`].
+    /// # Examples
+    /// ```
+    /// # use snake3::SnakeGame;
+    /// use snake3::snake::Map;
+    /// let map: Map = "#####\n#.>.#\n#####".parse().unwrap();
+    /// let new_game = SnakeGame::from_map(&map);
+    /// ```
+    pub fn from_map(map: &Map) -> Self {
+        let mut game = SnakeGame::new(map.columns, map.rows, map.direction, map.start);
+        for wall in &map.walls {
+            game.add_wall(*wall);
+        }
+        for (a, b) in &map.portals {
+            game.add_portal(*a, *b);
+        }
+        game
+    }
+    /// Adds a wall cell the snake can't go through, entities are never placed on walls.
+    pub fn add_wall(&mut self, position: (i16, i16)) {
+        self.walls.insert(position);
+    }
+    /// Returns every wall cell added with [`SnakeGame::add_wall`] or [`SnakeGame::from_map`].
+    pub fn walls(&self) -> &HashSet<(i16, i16)> {
+        &self.walls
+    }
+    /// Links two cells with a portal, the snake entering one comes out of the other. <br>
+    /// Entities are never placed on portals.
+    /// # Panics
+    /// - If both ends are the same cell or one of them already belongs to a portal.
+    pub fn add_portal(&mut self, a: (i16, i16), b: (i16, i16)) {
+        self.portals.add_pair(a, b);
+    }
+    /// Returns the portals added with [`SnakeGame::add_portal`] or [`SnakeGame::from_map`].
+    pub fn portals(&self) -> &Portals {
+        &self.portals
+    }
+    /// Adds a second snake for a versus game at `position` heading to `direction`, it moves with the same
+    /// rules as [`SnakeGame::snake`] and gets its own score. <br>
+    /// Each snake dies when its head runs into the other one, see [`GameResult::RivalDied`] and [`GameResult::Draw`].
+    /// # Examples
+    /// ```
+    /// # use snake3::{SnakeGame, GameState};
+    /// use snake3::snake::{GameResult, SnakeDirection};
+    /// let mut versus = SnakeGame::new(20, 10, Some(SnakeDirection::Right), Some((5, 5)));
+    /// versus.add_rival((14, 5), SnakeDirection::Left);
+    /// versus.set_state(GameState::Playing);
+    /// while versus.get_state() == GameState::Playing {
+    ///     versus.tick();
+    /// }
+    /// assert_eq!(versus.result(), Some(GameResult::Draw));
+    /// ```
+    /// # Panics
+    /// - If the position is outside of ([columns](`SnakeGame::columns`), [rows](`SnakeGame::rows`)).
+    pub fn add_rival(&mut self, position: (i16, i16), direction: SnakeDirection) {
+        if position.0 > self.columns || position.1 > self.rows {
+            panic!("You can't create a snake outside of columns or rows range.")
+        }
+        self.rival_start = Some((position, direction));
+        self.rival = Some(self.new_rival(position, direction));
+    }
+    /// A snake moving like [`SnakeGame::snake`].
+    fn new_rival(&self, position: (i16, i16), direction: SnakeDirection) -> Snake {
+        let mut rival = Snake::new(position, direction);
+        rival.set_reverse_on_opposite(self.snake.reverse_on_opposite());
+        rival.set_movement(self.snake.movement());
+        rival.set_topology(self.snake.topology());
+        rival.set_layered(self.layers > 1);
+        rival
+            .effects_mut()
+            .set_magnet_radius(self.snake.effects().magnet_radius());
+        rival
+    }
+    /// The [`Entity`] the [rival](`SnakeGame::rival`) ate on the last tick, like [`SnakeGame::tick`] returns the one the snake ate.
+    pub fn take_rival_hit(&mut self) -> Option<Box<dyn Entity>> {
+        self.rival_hit.take()
+    }
+    /// Cells that hurt the snake and grow over time following their [`HazardRules`].
+    pub fn hazards(&self) -> &Hazards {
+        &self.hazards
+    }
+    /// Use it to [add](`Hazards::add`) hazards at the start of the game.
+    pub fn hazards_mut(&mut self) -> &mut Hazards {
+        &mut self.hazards
+    }
+    pub fn set_hazard_rules(&mut self, rules: HazardRules) {
+        self.hazards.set_rules(rules);
+    }
+    /// Changes the [`Topology`] of the board, the snake moves, collides and gets entities pulled following it.
+    /// # Examples
+    /// ```
+    /// # use snake3::SnakeGame;
+    /// use snake3::snake::Topology;
+    /// let mut new_game = SnakeGame::new(10, 10, None, None);
+    /// new_game.set_topology(Topology::Hex);
+    /// ```
+    pub fn set_topology(&mut self, topology: Topology) {
+        self.snake.set_topology(topology);
+    }
+    pub fn topology(&self) -> Topology {
+        self.snake.topology()
+    }
+    /// Stacks `layers` boards on top of each other, the snake starts on layer `0` and can
+    /// [ascend](`SnakeDirection::Ascend`) and [descend](`SnakeDirection::Descend`) between them. <br>
+    /// Walls and portals go through every layer, entities are placed on any of them.
+    /// # Examples
+    /// ```
+    /// # use snake3::{SnakeGame, GameState};
+    /// use snake3::snake::SnakeDirection;
+    /// let mut new_game = SnakeGame::new(10, 10, None, None);
+    /// new_game.set_layers(3);
+    /// new_game.snake.set_direction(SnakeDirection::Ascend);
+    /// new_game.set_state(GameState::Playing);
+    /// new_game.tick();
+    /// assert_eq!(new_game.snake.body[0].z, 1);
+    /// ```
+    /// # Panics
+    /// - If `layers` is lower than one.
+    pub fn set_layers(&mut self, layers: i16) {
+        if layers < 1 {
+            panic!("A game needs at least one layer.")
         }
+        self.layers = layers;
+        self.snake.set_layered(layers > 1);
+    }
+    /// Returns how many layers the board has, `1` unless [`SnakeGame::set_layers`] was used.
+    pub fn layers(&self) -> i16 {
+        self.layers
     }
     /// Returns a tuple ([columns](`SnakeGame::columns`), [rows](`SnakeGame::rows`)).
     /// # Examples
@@ -91,20 +268,131 @@ impl SnakeGame {
     /// new_game.set_state(GameState::Playing);
     /// ```
     /// # Panics
-    /// - Trying to set the state to [`GameState::New`].
-    /// - Trying to set the game to anything after is beeing set to [`GameState::Ended`].
-    /// - Trying to set twice the same state.
+    /// - If the transition is not allowed by [`GameState::can_transition_to`], use
+    ///   [`SnakeGame::try_set_state`] to get an error instead.
     pub fn set_state(&mut self, state: GameState) {
-        if state == GameState::New {
-            panic!("Can't set to New.")
+        if let Err(error) = self.try_set_state(state) {
+            panic!("{error}")
+        }
+    }
+    /// Change the game [state](`GameState`) to a new one if the transition is allowed.
+    /// # Examples
+    /// ```
+    /// # use snake3::{SnakeGame, GameState};
+    /// # let mut new_game = SnakeGame::new(10, 10, None, None);
+    /// assert!(new_game.try_set_state(GameState::Paused).is_err());
+    /// assert!(new_game.try_set_state(GameState::Playing).is_ok());
+    /// ```
+    pub fn try_set_state(&mut self, state: GameState) -> Result<(), InvalidTransition> {
+        if !self.state.can_transition_to(state) {
+            return Err(InvalidTransition {
+                from: self.state,
+                to: state,
+            });
+        }
+        self.transition(state);
+        Ok(())
+    }
+    fn transition(&mut self, state: GameState) {
+        let transition = StateTransition {
+            from: self.state,
+            to: state,
+            at_tick: self.total_ticks(),
+        };
+        self.state = state;
+        if state == GameState::Countdown {
+            self.countdown_left = self.countdown;
         }
-        if self.state == GameState::Ended {
-            panic!("Can't set the sate after it is beeing set to Ended.")
+        if state.is_finished() {
+            self.scoring.finish(self.ticks);
         }
-        if self.state == state {
-            panic!("Can't set the same state twice.")
+        for hook in self.transition_hooks.iter_mut() {
+            hook(&transition);
         }
-        self.state = state
+        self.history.push(transition);
+        self.emit(GameEvent::StateChanged {
+            from: transition.from,
+            to: state,
+        });
+        if state.is_finished() {
+            let result = self.result;
+            self.run_plugins(|plugin, game| {
+                plugin.on_end(game, result);
+                Verdict::Allow
+            });
+        }
+    }
+    /// Starts a [`GameState::New`] game, going through [`GameState::Countdown`] if
+    /// [`SnakeGame::set_countdown`] was used.
+    pub fn start(&mut self) {
+        if self.countdown > 0 {
+            self.set_state(GameState::Countdown);
+        } else {
+            self.set_state(GameState::Playing);
+        }
+    }
+    /// Sets how many ticks [`GameState::Countdown`] lasts before the game starts playing, `0` by default.
+    pub fn set_countdown(&mut self, ticks: u64) {
+        self.countdown = ticks;
+    }
+    /// Returns how many ticks are left before the countdown ends.
+    pub fn countdown_left(&self) -> u64 {
+        self.countdown_left
+    }
+    /// Registers a hook that gets called every time the [`GameState`] changes.
+    /// # Examples
+    /// ```
+    /// # use snake3::{SnakeGame, GameState};
+    /// # let mut new_game = SnakeGame::new(10, 10, None, None);
+    /// new_game.on_transition(|transition| {
+    ///     println!("{:?} -> {:?}", transition.from, transition.to);
+    /// });
+    /// ```
+    pub fn on_transition<F>(&mut self, hook: F)
+    where
+        F: FnMut(&StateTransition) + 'static,
+    {
+        self.transition_hooks.push(Box::new(hook));
+    }
+    /// Returns every state change since the game was created or [reset](`SnakeGame::reset`).
+    pub fn history(&self) -> &[StateTransition] {
+        &self.history
+    }
+    /// Puts the game back to [`GameState::New`] with the snake at its starting position
+    /// and no score, entities or ticks. <br>
+    /// The configuration (walls, portals, hazard rules, topology, scoring rules, win conditions, countdown, listeners and hooks) is kept,
+    /// and the snake [health](`Snake::health`) is back to full. <br>
+    /// A [seeded](`SnakeGame::set_seed`) game starts its random numbers over, so it plays the same again,
+    /// and a [recording](`SnakeGame::start_recording`) starts over too.
+    pub fn reset(&mut self) {
+        self.snake = self
+            .snake
+            .respawn(self.starting_position, self.starting_direction);
+        if let Some((position, direction)) = self.rival_start {
+            self.rival = Some(self.new_rival(position, direction));
+        }
+        self.rival_scoring.reset();
+        self.rival_hit = None;
+        self.entities.clear();
+        self.hazards.reset();
+        self.scoring.reset();
+        self.ticks = 0;
+        self.paused_ticks = 0;
+        self.countdown_ticks = 0;
+        self.countdown_left = 0;
+        self.result = None;
+        if let Some(rng) = &mut self.rng {
+            rng.reset();
+        }
+        if let Some(recording) = &mut self.recording {
+            recording.inputs.clear();
+        }
+        self.history.clear();
+        if self.state != GameState::New {
+            self.transition(GameState::New);
+        }
+        // Nothing happened yet on the new game
+        self.history.clear();
     }
     /// Returns the current state of the game.
     pub fn get_state(&self) -> GameState {
@@ -112,15 +400,470 @@ impl SnakeGame {
     }
     /// Check if our snake is in contact with the wall or itself.
     pub fn check_collisions(&self) -> bool {
+        self.collision_cause().is_some()
+    }
+    /// Same as [`SnakeGame::check_collisions`] but tells us what the snake hit.
+    /// [`StatusEffect::InvincibleWalls`] skips the wall checks and [`StatusEffect::Ghost`] the self and rival ones,
+    /// neither skips the [hazards](`SnakeGame::hazards`).
+    pub fn collision_cause(&self) -> Option<DeathCause> {
+        self.collision_cause_of(&self.snake, self.rival.as_ref())
+    }
+    /// Same as [`SnakeGame::collision_cause`] for the [rival](`SnakeGame::rival`), `None` if there is no rival.
+    pub fn rival_collision_cause(&self) -> Option<DeathCause> {
+        let rival = self.rival.as_ref()?;
+        self.collision_cause_of(rival, Some(&self.snake))
+    }
+    fn collision_cause_of(&self, snake: &Snake, other: Option<&Snake>) -> Option<DeathCause> {
+        let effects = snake.effects();
         // Are we hitting a wall
-        let head = &self.snake.body[0];
-        if head.x > self.columns || head.y > self.rows || head.x < 0 || head.y < 0 {
-            return true;
+        let head = &snake.body[0];
+        if !effects.is_active(StatusEffect::InvincibleWalls) {
+            if head.x > self.columns || head.y > self.rows || head.x < 0 || head.y < 0 {
+                return Some(DeathCause::Wall);
+            }
+            if head.z < 0 || head.z >= self.layers {
+                return Some(DeathCause::Wall);
+            }
+            if self.walls.contains(&(head.x, head.y)) {
+                return Some(DeathCause::Wall);
+            }
+        }
+        // Draining hazards are dealt with on the tick, with the rest of the health
+        if self.hazards.contains((head.x, head.y))
+            && (self.hazards.rules().damage == HazardDamage::Deadly || snake.health().is_none())
+        {
+            return Some(DeathCause::Hazard);
         }
-        // Is the snake eating itself
-        self.snake.body[1..]
+        if effects.is_active(StatusEffect::Ghost) {
+            return None;
+        }
+        // Is the snake running into the other one, head to head included, or going diagonally through it
+        if other.is_some_and(|other| other.body.contains(head) || snake.crosses(&other.body)) {
+            return Some(DeathCause::Rival);
+        }
+        // Is the snake eating itself or going diagonally through its body
+        (snake.body[1..].iter().any(|point| point == head) || snake.crosses_itself())
+            .then_some(DeathCause::SelfCollision)
+    }
+    /// Runs one step of the game while it is [`GameState::Playing`]: <br>
+    /// advances the snake, ends the game if it collided, [starved](`Snake::health`) or met a win condition, scores what it ate following the
+    /// [`ScoringRules`] and returns the [`Entity`] it ate, if any, so that we can check what action to take.
+    /// # Examples
+    /// ```
+    /// # use snake3::{SnakeGame, GameState, named};
+    /// # use snake3::snake::{self, Apple};
+    /// # let mut new_game = SnakeGame::new(10, 10, None, None);
+    /// new_game.set_state(GameState::Playing);
+    /// if let Some(hit) = new_game.tick() {
+    ///     if let Some(apple) = hit.downcast_ref::<Apple>() {
+    ///         new_game.snake.grow();
+    ///     }
+    /// }
+    /// ```
+    pub fn tick(&mut self) -> Option<Box<dyn Entity>> {
+        match self.state {
+            GameState::Playing => {}
+            GameState::Paused => {
+                self.paused_ticks += 1;
+                return None;
+            }
+            GameState::Countdown => {
+                self.countdown_ticks += 1;
+                self.countdown_left = self.countdown_left.saturating_sub(1);
+                if self.countdown_left == 0 {
+                    self.set_state(GameState::Playing);
+                }
+                return None;
+            }
+            _ => return None,
+        }
+        self.run_plugins(|plugin, game| {
+            plugin.pre_move(game);
+            Verdict::Allow
+        });
+        if self.state != GameState::Playing {
+            return None;
+        }
+        let length = self.snake.body.len();
+        let heads = (
+            self.snake.body[0],
+            self.rival.as_ref().map(|rival| rival.body[0]),
+        );
+        self.snake.advance_through(&self.portals);
+        if let Some(rival) = &mut self.rival {
+            rival.advance_through(&self.portals);
+        }
+        self.ticks += 1;
+        let grown = self
+            .hazards
+            .update(self.ticks, self.columns, self.rows, self.topology());
+        if grown > 0 {
+            self.emit(GameEvent::HazardsGrew { cells: grown });
+        }
+        if self
+            .snake
+            .effects()
+            .is_active(StatusEffect::InvincibleWalls)
+        {
+            wrap_head(&mut self.snake, self.columns, self.rows, self.layers);
+        }
+        if let Some(rival) = &mut self.rival
+            && rival.effects().is_active(StatusEffect::InvincibleWalls)
+        {
+            wrap_head(rival, self.columns, self.rows, self.layers);
+        }
+        let head = self.snake.body[0];
+        self.emit(GameEvent::Moved {
+            x: head.x,
+            y: head.y,
+        });
+        if self.snake.body.len() > length {
+            self.emit(GameEvent::Grew {
+                length: self.snake.body.len(),
+            });
+        }
+        self.run_plugins(|plugin, game| {
+            plugin.post_move(game);
+            Verdict::Allow
+        });
+        if self.state != GameState::Playing {
+            return None;
+        }
+        // Snakes swapping cells go through each other's head without touching the body
+        let swapped = self
+            .rival
+            .as_ref()
+            .is_some_and(|rival| heads.1 == Some(self.snake.body[0]) && heads.0 == rival.body[0]);
+        let swapped = swapped.then_some(DeathCause::Rival);
+        let cause = self.collision_cause().or(swapped);
+        let cause = self.allow_death(Player::One, cause);
+        let rival_cause = self.rival_collision_cause().or(swapped);
+        let rival_cause = self.allow_death(Player::Two, rival_cause);
+        if cause.is_some() || rival_cause.is_some() {
+            if let Some(cause) = cause {
+                self.emit(GameEvent::Died { cause });
+            }
+            if let Some(cause) = rival_cause {
+                self.emit(GameEvent::RivalDied { cause });
+            }
+            self.result = match (cause, rival_cause) {
+                (Some(_), Some(_)) => Some(GameResult::Draw),
+                (Some(cause), None) => Some(GameResult::Died(cause)),
+                (None, rival_cause) => rival_cause.map(GameResult::RivalDied),
+            };
+            self.set_state(GameState::GameOver);
+            return None;
+        }
+        self.tick_rival();
+        for effect in self.snake.effects_mut().tick() {
+            self.emit(GameEvent::EffectEnded { effect });
+        }
+        if self.snake.effects().is_active(StatusEffect::Magnet) {
+            self.pull_entities();
+        }
+        let head = (self.snake.body[0].x, self.snake.body[0].y);
+        let hazard_drain = match self.hazards.rules().damage {
+            HazardDamage::Drain(drain) if self.hazards.contains(head) => drain,
+            _ => 0,
+        };
+        if let Some(health) = self.snake.health_mut() {
+            health.drain();
+            health.take(hazard_drain);
+        }
+        let hit = self.check_entity_collision();
+        if let Some(entity) = &hit
+            && let Some(health) = self.snake.health_mut()
+        {
+            let amount = health.eat(entity.name());
+            let health = health.current();
+            if amount > 0 {
+                self.emit(GameEvent::Healed { amount, health });
+            }
+        }
+        if self.snake.health().is_some_and(|health| health.is_empty()) {
+            let cause = if hazard_drain > 0 {
+                DeathCause::Hazard
+            } else {
+                DeathCause::Starvation
+            };
+            self.result = Some(GameResult::Died(cause));
+            self.emit(GameEvent::Died { cause });
+            self.set_state(GameState::GameOver);
+            return None;
+        }
+        if let Some(entity) = &hit {
+            let multiplier = self.snake.effects().score_multiplier() as u64;
+            let points = self.scoring.record_meal(
+                entity.name(),
+                self.ticks,
+                self.snake.body.len(),
+                multiplier,
+            );
+            if points > 0 {
+                let total = self.scoring.total();
+                self.emit(GameEvent::Scored { points, total });
+            }
+        }
+        if let Some((effect, ticks)) = hit.as_ref().and_then(|entity| entity.effect())
+            && self.snake.effects_mut().apply(effect, ticks)
+        {
+            self.emit(GameEvent::EffectStarted { effect });
+        }
+        self.check_win_conditions();
+        hit
+    }
+    /// Lets the rival eat, score and get effects, like the snake does on [`SnakeGame::tick`].
+    fn tick_rival(&mut self) {
+        let Some(rival) = &mut self.rival else {
+            return;
+        };
+        rival.effects_mut().tick();
+        let head = rival.body[0];
+        let multiplier = rival.effects().score_multiplier() as u64;
+        let length = rival.body.len();
+        let hit = self.take_entity_at(Player::Two, head.x, head.y, head.z);
+        if let Some(entity) = &hit {
+            let points =
+                self.rival_scoring
+                    .record_meal(entity.name(), self.ticks, length, multiplier);
+            if points > 0 {
+                let total = self.rival_scoring.total();
+                self.emit(GameEvent::RivalScored { points, total });
+            }
+            if let Some((effect, ticks)) = entity.effect()
+                && let Some(rival) = &mut self.rival
+            {
+                rival.effects_mut().apply(effect, ticks);
+            }
+        }
+        self.rival_hit = hit;
+    }
+    /// Moves every entity on the layer of the head within the magnet radius one cell closer to it.
+    fn pull_entities(&mut self) {
+        let head = self.snake.body[0];
+        let radius = self.snake.effects().magnet_radius();
+        let topology = self.snake.topology();
+        let mut taken: HashSet<(i16, i16)> = self
+            .snake
+            .body
             .iter()
-            .any(|point| point.x == head.x && point.y == head.y)
+            .skip(1)
+            .filter(|point| point.z == head.z)
+            .map(|point| (point.x, point.y))
+            .chain(
+                self.entities
+                    .iter()
+                    .filter(|entity| entity.z() == head.z)
+                    .map(|entity| (entity.x(), entity.y())),
+            )
+            .collect();
+        for entity in self.entities.iter_mut() {
+            if entity.z() != head.z {
+                continue;
+            }
+            let (x, y) = (entity.x(), entity.y());
+            if topology.distance((x, y), (head.x, head.y)) > radius {
+                continue;
+            }
+            let target = topology.towards((x, y), (head.x, head.y));
+            if taken.contains(&target)
+                || self.walls.contains(&target)
+                || self.portals.contains(target)
+            {
+                continue;
+            }
+            taken.remove(&(x, y));
+            taken.insert(target);
+            entity.set_position(target.0, target.1);
+        }
+    }
+    /// Ends the game with [`GameResult::Won`] if any of the [win conditions](`SnakeGame::set_win_conditions`) is met. <br>
+    /// [`SnakeGame::tick`] already does this, call it again after changing the score or the snake outside of a tick.
+    pub fn check_win_conditions(&mut self) -> bool {
+        if self.state != GameState::Playing
+            || !self.win_conditions.iter().any(|cond| cond.is_met(self))
+        {
+            return false;
+        }
+        self.result = Some(GameResult::Won);
+        self.emit(GameEvent::Won);
+        self.set_state(GameState::Won);
+        true
+    }
+    /// Replaces the conditions that make the player win, by default only [`WinCondition::BoardFilled`].
+    pub fn set_win_conditions(&mut self, win_conditions: Vec<WinCondition>) {
+        self.win_conditions = win_conditions;
+    }
+    /// Returns the conditions that make the player win.
+    pub fn win_conditions(&self) -> &[WinCondition] {
+        &self.win_conditions
+    }
+    /// Ends the game with [`GameResult::Aborted`], for example when the player quits mid game.
+    pub fn abort(&mut self) {
+        if self.state.is_finished() {
+            return;
+        }
+        self.result = Some(GameResult::Aborted);
+        self.set_state(GameState::GameOver);
+    }
+    /// Ends the game as if the snake died of `cause`, for rules that live outside of [`SnakeGame::tick`]
+    /// like an entity that kills the snake when eaten.
+    /// # Examples
+    /// ```
+    /// # use snake3::{SnakeGame, GameState};
+    /// use snake3::snake::{DeathCause, GameResult};
+    /// let mut new_game = SnakeGame::new(10, 10, None, None);
+    /// new_game.set_state(GameState::Playing);
+    /// new_game.kill(DeathCause::Rule);
+    /// assert_eq!(new_game.result(), Some(GameResult::Died(DeathCause::Rule)));
+    /// ```
+    pub fn kill(&mut self, cause: DeathCause) {
+        if self.state.is_finished() {
+            return;
+        }
+        self.result = Some(GameResult::Died(cause));
+        self.emit(GameEvent::Died { cause });
+        self.set_state(GameState::GameOver);
+    }
+    /// Copies the state of a game simulated somewhere else, like on a [network server](`crate::net::Server`),
+    /// so it can be drawn without running [`SnakeGame::tick`].
+    pub(crate) fn mirror(
+        &mut self,
+        state: GameState,
+        result: Option<GameResult>,
+        ticks: u64,
+        countdown_left: u64,
+        scores: (u64, u64),
+    ) {
+        self.state = state;
+        self.result = result;
+        self.ticks = ticks;
+        self.countdown_left = countdown_left;
+        self.scoring.reset();
+        self.scoring.carry(scores.0);
+        self.rival_scoring.reset();
+        self.rival_scoring.carry(scores.1);
+    }
+    /// Replaces the walls, portals and hazards of the board with the ones of a game simulated somewhere else.
+    pub(crate) fn mirror_board(
+        &mut self,
+        walls: &[(i16, i16)],
+        portals: &[PortalPair],
+        hazards: &[(i16, i16)],
+    ) {
+        self.walls = walls.iter().copied().collect();
+        self.portals = Portals::default();
+        for (a, b) in portals {
+            self.portals.add_pair(*a, *b);
+        }
+        self.hazards = Hazards::new(*self.hazards.rules());
+        for cell in hazards {
+            self.hazards.add(*cell);
+        }
+    }
+    /// Makes every random choice of the game, like where [`SnakeGame::generate_entity`] places entities,
+    /// follow `seed` so the game can be played again the same way. `None` goes back to unseeded randomness.
+    /// # Examples
+    /// ```
+    /// # use snake3::{SnakeGame, named};
+    /// use snake3::snake::Apple;
+    /// let mut a = SnakeGame::new(20, 20, None, None);
+    /// let mut b = SnakeGame::new(20, 20, None, None);
+    /// a.set_seed(Some(7));
+    /// b.set_seed(Some(7));
+    /// a.generate_entity(named!(Apple));
+    /// b.generate_entity(named!(Apple));
+    /// assert_eq!(a.entities[0].x(), b.entities[0].x());
+    /// ```
+    pub fn set_seed(&mut self, seed: Option<u64>) {
+        self.rng = seed.map(SeededRng::new);
+    }
+    pub fn seed(&self) -> Option<u64> {
+        self.rng.as_ref().map(SeededRng::seed)
+    }
+    /// A number from `min` up to but not including `max`, following the [seed](`SnakeGame::set_seed`) if there is one.
+    /// Use it for the random choices made around the game to keep seeded games repeatable.
+    pub fn random_range(&mut self, min: i16, max: i16) -> i16 {
+        match &mut self.rng {
+            Some(rng) => rng.range(min, max),
+            None => random_range(min, max),
+        }
+    }
+    /// Same as [`SnakeGame::random_range`] for indexes, a number from `0` up to but not including `len`.
+    pub fn random_index(&mut self, len: usize) -> usize {
+        match &mut self.rng {
+            Some(rng) => rng.index(len),
+            None => random_index(len),
+        }
+    }
+    /// Starts recording the directions given with [`SnakeGame::steer`] along with the seed of the game
+    /// and `config`, a description of the rules to tell runs of different games apart.
+    pub fn start_recording(&mut self, config: &str) {
+        self.recording = Some(Recording::new(self.seed(), config));
+    }
+    /// The run recorded so far, if the game is [recording](`SnakeGame::start_recording`).
+    pub fn recording(&self) -> Option<&Recording> {
+        self.recording.as_ref()
+    }
+    /// Stops recording and returns the run with the score and ticks of the game.
+    pub fn take_recording(&mut self) -> Option<Recording> {
+        let mut recording = self.recording.take()?;
+        recording.score = self.score();
+        recording.ticks = self.ticks;
+        Some(recording)
+    }
+    /// Points the snake to `direction` like [`Snake::set_direction`] and records it if the game is
+    /// [recording](`SnakeGame::start_recording`), so a [`Ghost`](`super::Ghost`) can play it again.
+    pub fn steer(&mut self, direction: SnakeDirection) {
+        self.snake.set_direction(direction);
+        if let Some(recording) = &mut self.recording {
+            recording.record(self.ticks, direction);
+        }
+    }
+    /// Returns how the game finished, `None` while it is still going or if it was
+    /// ended with [`SnakeGame::set_state`] directly.
+    pub fn result(&self) -> Option<GameResult> {
+        self.result
+    }
+    /// Returns the current score.
+    pub fn score(&self) -> u64 {
+        self.scoring.total()
+    }
+    /// Returns the [`Scoring`] of the game, with the score breakdown and rules.
+    pub fn scoring(&self) -> &Scoring {
+        &self.scoring
+    }
+    pub fn scoring_mut(&mut self) -> &mut Scoring {
+        &mut self.scoring
+    }
+    /// Replaces the [`ScoringRules`], by default one point per [`Apple`](`super::Apple`).
+    pub fn set_scoring_rules(&mut self, rules: ScoringRules) {
+        self.rival_scoring.set_rules(rules.clone());
+        self.scoring.set_rules(rules);
+    }
+    /// Returns the current score of the [rival](`SnakeGame::rival`).
+    pub fn rival_score(&self) -> u64 {
+        self.rival_scoring.total()
+    }
+    /// Returns the [`Scoring`] of the [rival](`SnakeGame::rival`), it follows the same rules as the snake.
+    pub fn rival_scoring(&self) -> &Scoring {
+        &self.rival_scoring
+    }
+    /// Returns how many times [`SnakeGame::tick`] advanced the snake, that is, the ticks spent playing.
+    pub fn ticks(&self) -> u64 {
+        self.ticks
+    }
+    /// Returns how many times [`SnakeGame::tick`] was called while the game was paused.
+    pub fn paused_ticks(&self) -> u64 {
+        self.paused_ticks
+    }
+    /// Returns how many times [`SnakeGame::tick`] was called during the countdown.
+    pub fn countdown_ticks(&self) -> u64 {
+        self.countdown_ticks
+    }
+    /// Returns every tick counted so far, playing, paused or in countdown.
+    pub fn total_ticks(&self) -> u64 {
+        self.ticks + self.paused_ticks + self.countdown_ticks
     }
     /// Randomly place a struct implementing [`Entity`] into the game [`SnakeGame::entities`].<br>
     /// If there was no space left to place an entity it returns `false`.
@@ -139,8 +882,32 @@ impl SnakeGame {
         if empty_spots.is_empty() {
             return false;
         }
-        let new_position = empty_spots[random_range(0, empty_spots.len() as i16) as usize];
-        let entity = make_entity(new_position.0, new_position.1);
+        let (x, y, z) = empty_spots[self.random_index(empty_spots.len())];
+        let mut entity = make_entity(x, y);
+        entity.set_z(z);
+        if entity.z() != z {
+            // The entity can't leave its layer, so it can only go on the free cells of that one
+            let layer = entity.z();
+            let layer_spots: Vec<(i16, i16)> = empty_spots
+                .iter()
+                .filter(|spot| spot.2 == layer)
+                .map(|spot| (spot.0, spot.1))
+                .collect();
+            if layer_spots.is_empty() {
+                return false;
+            }
+            let (x, y) = layer_spots[self.random_index(layer_spots.len())];
+            entity = make_entity(x, y);
+        }
+        if self.run_plugins(|plugin, game| plugin.on_spawn(game, entity.as_ref())) == Verdict::Veto
+        {
+            return false;
+        }
+        self.emit(GameEvent::EntitySpawned {
+            entity: entity.name(),
+            x: entity.x(),
+            y: entity.y(),
+        });
         self.entities.push(entity);
         true
     }
@@ -154,40 +921,214 @@ impl SnakeGame {
     /// if let Some(hit) = new_game.check_entity_collision() {
     ///     if let Some(apple) = hit.downcast_ref::<Apple>() {
     ///         new_game.snake.grow();
-    ///         new_game.score += 1;
+    ///         new_game.scoring_mut().add_bonus(1);
     ///     }
     ///     // If we had a `Bomb` struct that implemented `Entity`
     ///     // if let Some(bomb) = hid.downcast_ref::<Bomb>() {}
     /// }
     /// ```
     pub fn check_entity_collision(&mut self) -> Option<Box<dyn Entity>> {
-        let mut remove_index = None;
-
-        for (i, entity) in self.entities.iter().enumerate() {
-            if self.snake.body[0].x == entity.x() && self.snake.body[0].y == entity.y() {
-                remove_index = Some(i);
-                break;
-            }
+        let head = self.snake.body[0];
+        self.take_entity_at(Player::One, head.x, head.y, head.z)
+    }
+    /// Removes the entity at a position and emits [`GameEvent::Ate`] for it, unless a plugin
+    /// does not let `player` eat it.
+    fn take_entity_at(
+        &mut self,
+        player: Player,
+        x: i16,
+        y: i16,
+        z: i16,
+    ) -> Option<Box<dyn Entity>> {
+        let index = self
+            .entities
+            .iter()
+            .position(|entity| entity.x() == x && entity.y() == y && entity.z() == z)?;
+        let entity = self.entities.remove(index);
+        if self.run_plugins(|plugin, game| plugin.on_eat(game, player, entity.as_ref()))
+            == Verdict::Veto
+        {
+            let index = index.min(self.entities.len());
+            self.entities.insert(index, entity);
+            return None;
         }
-
-        remove_index.map(|i| self.entities.remove(i))
+        let event = GameEvent::Ate {
+            entity: entity.name(),
+            x: entity.x(),
+            y: entity.y(),
+        };
+        self.emit(event);
+        Some(entity)
+    }
+    /// Removes from [`SnakeGame::entities`] every entity for which `should_expire` returns `true`
+    /// and returns how many were removed.
+    /// # Examples
+    /// ```
+    /// # use snake3::{SnakeGame, GameState, named};
+    /// # use snake3::snake::{self, Apple};
+    /// # let mut new_game = SnakeGame::new(10, 10, None, None);
+    /// new_game.generate_entity(named!(Apple));
+    /// let removed = new_game.expire_entities(|entity| entity.x() == 0);
+    /// ```
+    pub fn expire_entities<F>(&mut self, should_expire: F) -> usize
+    where
+        F: Fn(&dyn Entity) -> bool,
+    {
+        let (expired, kept) = std::mem::take(&mut self.entities)
+            .into_iter()
+            .partition(|entity| should_expire(entity.as_ref()));
+        self.entities = kept;
+        let expired: Vec<Box<dyn Entity>> = expired;
+        for entity in &expired {
+            self.emit(GameEvent::EntityExpired {
+                entity: entity.name(),
+                x: entity.x(),
+                y: entity.y(),
+            });
+        }
+        expired.len()
+    }
+    /// Returns all the [`GameEvent`]s that happened since the last call, leaving the queue empty. <br>
+    /// Only the last [`MAX_QUEUED_EVENTS`] are kept, games that only use [`SnakeGame::on_event`] don't need to drain.
+    pub fn drain_events(&mut self) -> Vec<GameEvent> {
+        self.events.drain(..).collect()
+    }
+    /// Registers a listener that gets called with every [`GameEvent`] as soon as it happens. <br>
+    /// Events are still queued for [`SnakeGame::drain_events`], up to [`MAX_QUEUED_EVENTS`] of them.
+    /// # Examples
+    /// ```
+    /// # use snake3::{SnakeGame, GameState};
+    /// # use snake3::snake::GameEvent;
+    /// # let mut new_game = SnakeGame::new(10, 10, None, None);
+    /// new_game.on_event(|event| {
+    ///     if let GameEvent::Died { cause } = event {
+    ///         println!("Died by {cause:?}");
+    ///     }
+    /// });
+    /// ```
+    pub fn on_event<F>(&mut self, listener: F)
+    where
+        F: FnMut(&GameEvent) + 'static,
+    {
+        self.listeners.push(Box::new(listener));
+    }
+    /// Adds a plugin that hooks into every tick, after the ones already added.
+    /// # Examples
+    /// ```
+    /// # use snake3::SnakeGame;
+    /// use snake3::snake::AppleGrowth;
+    /// let mut new_game = SnakeGame::new(10, 10, None, None);
+    /// new_game.add_plugin(AppleGrowth);
+    /// assert_eq!(new_game.plugin_names(), ["AppleGrowth"]);
+    /// ```
+    pub fn add_plugin<P: GamePlugin + 'static>(&mut self, plugin: P) {
+        self.plugins.push(Box::new(plugin));
+    }
+    /// Names of the plugins in the order they run.
+    pub fn plugin_names(&self) -> Vec<&'static str> {
+        self.plugins.iter().map(|plugin| plugin.name()).collect()
+    }
+    /// The [`EntityKind`]s of the game, [`EntityRegistry::builtin`] unless more are registered.
+    pub fn entity_registry(&self) -> &EntityRegistry {
+        &self.registry
+    }
+    pub fn entity_registry_mut(&mut self) -> &mut EntityRegistry {
+        &mut self.registry
+    }
+    /// Registers `kind` so renderers and saves know about its entities, see [`EntityRegistry::register`].
+    pub fn register_entity(&mut self, kind: EntityKind) {
+        self.registry.register(kind);
     }
-    fn empty_spots(&self) -> Vec<(i16, i16)> {
-        let snake_set: HashSet<(i16, i16)> =
-            self.snake.body.iter().map(|seg| (seg.x, seg.y)).collect();
-        self.game_board
+    /// The kind of `entity`, `None` if its type was never registered.
+    /// # Examples
+    /// ```
+    /// # use snake3::SnakeGame;
+    /// use snake3::snake::{Apple, EntityColor};
+    /// let new_game = SnakeGame::new(10, 10, None, None);
+    /// let kind = new_game.entity_kind(&Apple::new(1, 1)).unwrap();
+    /// assert_eq!((kind.id, kind.glyph, kind.color), ("apple", 'o', EntityColor::Red));
+    /// ```
+    pub fn entity_kind(&self, entity: &dyn Entity) -> Option<&EntityKind> {
+        self.registry.kind_of(entity)
+    }
+    /// Calls `hook` on every plugin in order until one of them vetoes.
+    fn run_plugins<F>(&mut self, mut hook: F) -> Verdict
+    where
+        F: FnMut(&mut dyn GamePlugin, &mut SnakeGame) -> Verdict,
+    {
+        if self.plugins.is_empty() {
+            return Verdict::Allow;
+        }
+        // The plugins are out of the game while they run, so they can get it whole
+        let mut plugins = std::mem::take(&mut self.plugins);
+        let verdict = plugins
+            .iter_mut()
+            .map(|plugin| hook(plugin.as_mut(), self))
+            .find(|verdict| *verdict == Verdict::Veto)
+            .unwrap_or(Verdict::Allow);
+        // Plugins added by the hooks run from the next call on
+        plugins.append(&mut self.plugins);
+        self.plugins = plugins;
+        verdict
+    }
+    /// `cause`, unless a plugin keeps the snake of `player` alive.
+    fn allow_death(&mut self, player: Player, cause: Option<DeathCause>) -> Option<DeathCause> {
+        let cause = cause?;
+        match self.run_plugins(|plugin, game| plugin.on_collision(game, player, cause)) {
+            Verdict::Allow => Some(cause),
+            Verdict::Veto => None,
+        }
+    }
+    fn emit(&mut self, event: GameEvent) {
+        for listener in self.listeners.iter_mut() {
+            listener(&event);
+        }
+        if self.events.len() == MAX_QUEUED_EVENTS {
+            self.events.pop_front();
+        }
+        self.events.push_back(event);
+    }
+    pub(crate) fn empty_spots(&self) -> Vec<(i16, i16, i16)> {
+        let mut spots = self.unfilled_spots();
+        spots.retain(|(x, y, _)| !self.hazards.contains((*x, *y)));
+        spots
+    }
+    /// Cells of the board the snakes could still cover, hazards included.
+    pub(crate) fn unfilled_spots(&self) -> Vec<(i16, i16, i16)> {
+        let snake_set: HashSet<(i16, i16, i16)> = self
+            .snake
+            .body
             .iter()
-            .cloned()
-            .filter(|pos| !snake_set.contains(pos))
+            .chain(self.rival.iter().flat_map(|rival| rival.body.iter()))
+            .map(|seg| (seg.x, seg.y, seg.z))
+            .collect();
+        (0..self.layers)
+            .flat_map(|z| self.game_board.iter().map(move |(x, y)| (*x, *y, z)))
+            .filter(|(x, y, z)| {
+                !snake_set.contains(&(*x, *y, *z))
+                    && !self.walls.contains(&(*x, *y))
+                    && !self.portals.contains((*x, *y))
+            })
             .collect()
     }
 }
 
+/// Brings the head back to the board from the opposite side.
+fn wrap_head(snake: &mut Snake, columns: i16, rows: i16, layers: i16) {
+    let head = &mut snake.body[0];
+    head.x = head.x.rem_euclid(columns.max(1));
+    head.y = head.y.rem_euclid(rows.max(1));
+    head.z = head.z.rem_euclid(layers);
+}
+
 #[cfg(test)]
+#[allow(clippy::bool_assert_comparison)]
 mod tests {
     use crate::{
         named,
-        snake::{Apple, snake_obj::SnakeBodyPoint},
+        snake::{
+            Apple, HazardGrowth, Health, Movement, PowerUp, Restore, snake_obj::SnakeBodyPoint,
+        },
     };
 
     use super::*;
@@ -204,7 +1145,7 @@ mod tests {
     #[test]
     fn snake_game_new() {
         let new_game = SnakeGame::new(42, 24, None, None);
-        assert_eq!(new_game.score, 0);
+        assert_eq!(new_game.score(), 0);
         assert_eq!(new_game.columns, 42);
         assert_eq!(new_game.rows, 24);
         assert_eq!(new_game.state, GameState::New);
@@ -256,33 +1197,149 @@ mod tests {
         new_game.set_state(GameState::Playing);
         assert_eq!(new_game.state, GameState::Playing);
 
-        new_game.set_state(GameState::Ended);
-        assert_eq!(new_game.state, GameState::Ended)
+        new_game.set_state(GameState::GameOver);
+        assert_eq!(new_game.state, GameState::GameOver)
     }
 
     #[test]
-    #[should_panic(expected = "Can't set to New.")]
+    #[should_panic(expected = "Can't go from New to New.")]
     fn snake_game_set_state_new() {
         let mut new_game = SnakeGame::new(42, 24, None, None);
         new_game.set_state(GameState::New)
     }
 
     #[test]
-    #[should_panic(expected = "Can't set the sate after it is beeing set to Ended.")]
+    #[should_panic(expected = "Can't go from GameOver to Playing.")]
     fn snake_game_set_state_ended() {
         let mut new_game = SnakeGame::new(42, 24, None, None);
-        new_game.set_state(GameState::Ended);
+        new_game.set_state(GameState::GameOver);
         new_game.set_state(GameState::Playing)
     }
 
     #[test]
-    #[should_panic(expected = "Can't set the same state twice.")]
+    #[should_panic(expected = "Can't go from Playing to Playing.")]
     fn snake_game_set_state_same() {
         let mut new_game = SnakeGame::new(42, 24, None, None);
         new_game.set_state(GameState::Playing);
         new_game.set_state(GameState::Playing)
     }
 
+    #[test]
+    fn snake_game_try_set_state() {
+        let mut new_game = SnakeGame::new(42, 24, None, None);
+        assert_eq!(
+            new_game.try_set_state(GameState::Won),
+            Err(InvalidTransition {
+                from: GameState::New,
+                to: GameState::Won
+            })
+        );
+        assert_eq!(new_game.get_state(), GameState::New);
+        assert_eq!(new_game.try_set_state(GameState::Playing), Ok(()));
+    }
+
+    #[test]
+    fn snake_game_countdown() {
+        let mut new_game = SnakeGame::new(42, 24, None, Some((10, 10)));
+        new_game.set_countdown(2);
+        new_game.start();
+        assert_eq!(new_game.get_state(), GameState::Countdown);
+        assert_eq!(new_game.countdown_left(), 2);
+        new_game.tick();
+        assert_eq!(new_game.get_state(), GameState::Countdown);
+        new_game.tick();
+        assert_eq!(new_game.get_state(), GameState::Playing);
+        assert_eq!(new_game.snake.body[0].x, 10);
+        new_game.tick();
+        assert_eq!(new_game.snake.body[0].x, 11);
+        assert_eq!(new_game.countdown_ticks(), 2);
+        assert_eq!(new_game.ticks(), 1);
+    }
+
+    #[test]
+    fn snake_game_start_without_countdown() {
+        let mut new_game = SnakeGame::new(42, 24, None, None);
+        new_game.start();
+        assert_eq!(new_game.get_state(), GameState::Playing);
+    }
+
+    #[test]
+    fn snake_game_tick_counters_and_history() {
+        let mut new_game = SnakeGame::new(42, 24, None, None);
+        new_game.set_state(GameState::Playing);
+        new_game.tick();
+        new_game.tick();
+        new_game.set_state(GameState::Paused);
+        new_game.tick();
+        new_game.set_state(GameState::Playing);
+        assert_eq!(new_game.ticks(), 2);
+        assert_eq!(new_game.paused_ticks(), 1);
+        assert_eq!(new_game.total_ticks(), 3);
+        assert_eq!(
+            new_game.history(),
+            [
+                StateTransition {
+                    from: GameState::New,
+                    to: GameState::Playing,
+                    at_tick: 0
+                },
+                StateTransition {
+                    from: GameState::Playing,
+                    to: GameState::Paused,
+                    at_tick: 2
+                },
+                StateTransition {
+                    from: GameState::Paused,
+                    to: GameState::Playing,
+                    at_tick: 3
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn snake_game_on_transition_hook() {
+        use std::{cell::Cell, rc::Rc};
+
+        let calls = Rc::new(Cell::new(0));
+        let mut new_game = SnakeGame::new(42, 24, None, None);
+        let calls_in_hook = Rc::clone(&calls);
+        new_game.on_transition(move |_| calls_in_hook.set(calls_in_hook.get() + 1));
+        new_game.set_state(GameState::Playing);
+        new_game.set_state(GameState::Paused);
+        assert_eq!(calls.get(), 2);
+    }
+
+    #[test]
+    fn snake_game_reset() {
+        let mut new_game = SnakeGame::new(42, 24, Some(SnakeDirection::Up), Some((10, 10)));
+        new_game.set_win_conditions(vec![WinCondition::TargetLength(5)]);
+        new_game.set_countdown(3);
+        new_game.generate_entity(named!(Apple));
+        new_game.set_state(GameState::Playing);
+        new_game.snake.set_direction(SnakeDirection::Left);
+        new_game.snake.grow();
+        new_game.tick();
+        new_game.scoring_mut().add_bonus(4);
+        new_game.abort();
+
+        new_game.reset();
+        assert_eq!(new_game.get_state(), GameState::New);
+        assert_eq!(
+            new_game.snake.body,
+            vec![SnakeBodyPoint { x: 10, y: 10, z: 0 }]
+        );
+        assert_eq!(new_game.snake.get_direction(), SnakeDirection::Up);
+        assert_eq!(new_game.score(), 0);
+        assert_eq!(new_game.ticks(), 0);
+        assert_eq!(new_game.result(), None);
+        assert!(new_game.entities.is_empty());
+        assert!(new_game.history().is_empty());
+        assert_eq!(new_game.win_conditions(), [WinCondition::TargetLength(5)]);
+        new_game.start();
+        assert_eq!(new_game.get_state(), GameState::Countdown);
+    }
+
     #[test]
     fn snake_game_check_collisions_false() {
         let new_game = SnakeGame::new(42, 24, None, None);
@@ -308,8 +1365,14 @@ mod tests {
     #[test]
     fn snake_game_check_collisions_self() {
         let mut new_game = SnakeGame::new(42, 24, Some(SnakeDirection::Left), Some((10, 10)));
-        new_game.snake.body.push(SnakeBodyPoint { x: 9, y: 10 });
-        new_game.snake.body.push(SnakeBodyPoint { x: 9, y: 11 });
+        new_game
+            .snake
+            .body
+            .push(SnakeBodyPoint { x: 9, y: 10, z: 0 });
+        new_game
+            .snake
+            .body
+            .push(SnakeBodyPoint { x: 9, y: 11, z: 0 });
         new_game.snake.advance();
         assert_eq!(true, new_game.check_collisions())
     }
@@ -346,6 +1409,636 @@ mod tests {
     #[test]
     fn snake_game_empty_spots() {
         let new_game = SnakeGame::new(2, 2, None, None);
-        assert_eq!(new_game.empty_spots(), vec![(0, 0), (0, 1), (1, 0)])
+        assert_eq!(
+            new_game.empty_spots(),
+            vec![(0, 0, 0), (0, 1, 0), (1, 0, 0)]
+        )
+    }
+
+    #[test]
+    fn snake_game_tick_only_when_playing() {
+        let mut new_game = SnakeGame::new(42, 24, None, Some((10, 10)));
+        new_game.tick();
+        assert_eq!(new_game.snake.body[0].x, 10);
+        assert_eq!(new_game.ticks(), 0);
+        new_game.set_state(GameState::Playing);
+        new_game.tick();
+        assert_eq!(new_game.snake.body[0].x, 11);
+        assert_eq!(new_game.ticks(), 1);
+    }
+
+    #[test]
+    fn snake_game_tick_events() {
+        let mut new_game = SnakeGame::new(42, 24, None, Some((10, 10)));
+        new_game.set_state(GameState::Playing);
+        new_game.entities.push(Box::new(Apple::new(11, 10)));
+        new_game.snake.grow();
+        let hit = new_game.tick();
+        assert!(hit.is_some());
+        assert_eq!(
+            new_game.drain_events(),
+            vec![
+                GameEvent::StateChanged {
+                    from: GameState::New,
+                    to: GameState::Playing
+                },
+                GameEvent::Moved { x: 11, y: 10 },
+                GameEvent::Grew { length: 2 },
+                GameEvent::Ate {
+                    entity: "Apple",
+                    x: 11,
+                    y: 10
+                },
+                GameEvent::Scored {
+                    points: 1,
+                    total: 1
+                },
+            ]
+        );
+        assert!(new_game.drain_events().is_empty());
+    }
+
+    #[test]
+    fn snake_game_event_queue_is_bounded() {
+        let mut new_game = SnakeGame::new(42, 24, None, Some((10, 10)));
+        new_game.set_state(GameState::Playing);
+        for _ in 0..MAX_QUEUED_EVENTS {
+            new_game.emit(GameEvent::Won);
+        }
+        new_game.emit(GameEvent::Moved { x: 1, y: 1 });
+        let events = new_game.drain_events();
+        assert_eq!(events.len(), MAX_QUEUED_EVENTS);
+        assert_eq!(events.last(), Some(&GameEvent::Moved { x: 1, y: 1 }));
+    }
+
+    #[test]
+    fn snake_game_tick_died() {
+        let mut new_game = SnakeGame::new(42, 24, Some(SnakeDirection::Left), Some((0, 0)));
+        new_game.set_state(GameState::Playing);
+        new_game.drain_events();
+        new_game.tick();
+        assert_eq!(new_game.get_state(), GameState::GameOver);
+        assert_eq!(
+            new_game.drain_events()[1..],
+            [
+                GameEvent::Died {
+                    cause: DeathCause::Wall
+                },
+                GameEvent::StateChanged {
+                    from: GameState::Playing,
+                    to: GameState::GameOver
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn snake_game_tick_won() {
+        let mut new_game = SnakeGame::new(2, 1, None, Some((0, 0)));
+        new_game.set_state(GameState::Playing);
+        new_game.snake.grow();
+        new_game.tick();
+        assert_eq!(new_game.get_state(), GameState::Won);
+        assert_eq!(new_game.result(), Some(GameResult::Won));
+        assert!(new_game.drain_events().contains(&GameEvent::Won));
+    }
+
+    #[test]
+    fn snake_game_tick_result_died() {
+        let mut new_game = SnakeGame::new(42, 24, Some(SnakeDirection::Left), Some((0, 0)));
+        new_game.set_state(GameState::Playing);
+        assert_eq!(new_game.result(), None);
+        new_game.tick();
+        assert_eq!(new_game.result(), Some(GameResult::Died(DeathCause::Wall)));
+    }
+
+    #[test]
+    fn snake_game_custom_win_conditions() {
+        let mut new_game = SnakeGame::new(42, 24, None, None);
+        new_game.set_win_conditions(vec![
+            WinCondition::TargetScore(2),
+            WinCondition::SurviveTicks(3),
+        ]);
+        new_game.set_state(GameState::Playing);
+        new_game.tick();
+        new_game.scoring_mut().add_bonus(2);
+        assert!(new_game.check_win_conditions());
+        assert_eq!(new_game.result(), Some(GameResult::Won));
+
+        let mut new_game = SnakeGame::new(42, 24, None, None);
+        new_game.set_win_conditions(vec![WinCondition::SurviveTicks(3)]);
+        new_game.set_state(GameState::Playing);
+        new_game.tick();
+        new_game.tick();
+        assert_eq!(new_game.result(), None);
+        new_game.tick();
+        assert_eq!(new_game.result(), Some(GameResult::Won));
+    }
+
+    #[test]
+    fn snake_game_no_win_conditions() {
+        let mut new_game = SnakeGame::new(2, 1, None, Some((0, 0)));
+        new_game.set_win_conditions(Vec::new());
+        new_game.set_state(GameState::Playing);
+        new_game.snake.grow();
+        new_game.tick();
+        assert_eq!(new_game.get_state(), GameState::Playing);
+        assert!(!new_game.check_win_conditions());
+    }
+
+    #[test]
+    fn snake_game_abort() {
+        let mut new_game = SnakeGame::new(42, 24, None, None);
+        new_game.set_state(GameState::Playing);
+        new_game.abort();
+        assert_eq!(new_game.get_state(), GameState::GameOver);
+        assert_eq!(new_game.result(), Some(GameResult::Aborted));
+        // Aborting twice does nothing
+        new_game.abort();
+        assert_eq!(new_game.result(), Some(GameResult::Aborted));
+    }
+
+    #[test]
+    fn snake_game_collision_cause() {
+        let mut new_game = SnakeGame::new(42, 24, Some(SnakeDirection::Left), Some((10, 10)));
+        assert_eq!(new_game.collision_cause(), None);
+        new_game
+            .snake
+            .body
+            .push(SnakeBodyPoint { x: 9, y: 10, z: 0 });
+        new_game
+            .snake
+            .body
+            .push(SnakeBodyPoint { x: 9, y: 11, z: 0 });
+        new_game.snake.advance();
+        assert_eq!(new_game.collision_cause(), Some(DeathCause::SelfCollision));
+        new_game.snake.body[0].x = -1;
+        assert_eq!(new_game.collision_cause(), Some(DeathCause::Wall));
+    }
+
+    #[test]
+    fn snake_game_diagonal_crossing_is_a_collision() {
+        let mut new_game = SnakeGame::new(42, 24, Some(SnakeDirection::UpRight), Some((10, 10)));
+        new_game.snake.set_movement(Movement::EightWay);
+        new_game.snake.grow_by(3);
+        new_game.set_state(GameState::Playing);
+        new_game.tick();
+        new_game.snake.set_direction(SnakeDirection::Down);
+        new_game.tick();
+        new_game.snake.set_direction(SnakeDirection::UpLeft);
+        new_game.tick();
+        assert_eq!(
+            new_game.result(),
+            Some(GameResult::Died(DeathCause::SelfCollision))
+        );
+
+        new_game.reset();
+        assert_eq!(new_game.snake.movement(), Movement::EightWay);
+    }
+
+    #[test]
+    fn snake_game_spawn_and_expire_events() {
+        let mut new_game = SnakeGame::new(42, 24, None, None);
+        new_game.generate_entity(named!(Apple));
+        let (x, y) = (new_game.entities[0].x(), new_game.entities[0].y());
+        assert_eq!(new_game.expire_entities(|_| true), 1);
+        assert!(new_game.entities.is_empty());
+        assert_eq!(
+            new_game.drain_events(),
+            vec![
+                GameEvent::EntitySpawned {
+                    entity: "Apple",
+                    x,
+                    y
+                },
+                GameEvent::EntityExpired {
+                    entity: "Apple",
+                    x,
+                    y
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn snake_game_on_event_listener() {
+        use std::{cell::RefCell, rc::Rc};
+
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let mut new_game = SnakeGame::new(42, 24, None, None);
+        let seen_by_listener = Rc::clone(&seen);
+        new_game.on_event(move |event| seen_by_listener.borrow_mut().push(event.clone()));
+        new_game.set_state(GameState::Playing);
+        assert_eq!(seen.borrow().len(), 1);
+        assert_eq!(new_game.drain_events().len(), 1);
+    }
+
+    #[test]
+    fn snake_game_from_map_walls() {
+        let map: Map = "#####\n#.>.#\n#...#\n#####".parse().unwrap();
+        let mut new_game = SnakeGame::from_map(&map);
+        assert_eq!(new_game.dimensions(), (5, 4));
+        assert_eq!(new_game.snake.body[0], SnakeBodyPoint { x: 2, y: 1, z: 0 });
+        assert_eq!(
+            new_game.empty_spots(),
+            vec![(1, 1, 0), (1, 2, 0), (2, 2, 0), (3, 1, 0), (3, 2, 0)]
+        );
+        new_game.set_state(GameState::Playing);
+        new_game.tick();
+        assert_eq!(new_game.get_state(), GameState::Playing);
+        new_game.tick();
+        assert_eq!(new_game.result(), Some(GameResult::Died(DeathCause::Wall)));
+    }
+
+    #[test]
+    fn snake_game_portals() {
+        let map: Map = "#######\n#>1..1#\n#.....#\n#######".parse().unwrap();
+        let mut new_game = SnakeGame::from_map(&map);
+        assert_eq!(new_game.portals().exit_for((2, 1)), Some((5, 1)));
+        assert!(!new_game.empty_spots().contains(&(2, 1, 0)));
+        assert!(!new_game.empty_spots().contains(&(5, 1, 0)));
+        new_game.set_state(GameState::Playing);
+        new_game.tick();
+        assert_eq!(new_game.snake.body[0], SnakeBodyPoint { x: 5, y: 1, z: 0 });
+        assert_eq!(new_game.get_state(), GameState::Playing);
+        new_game.tick();
+        assert_eq!(new_game.result(), Some(GameResult::Died(DeathCause::Wall)));
+    }
+
+    #[test]
+    fn snake_game_portal_exit_on_body() {
+        let mut new_game = SnakeGame::new(42, 24, Some(SnakeDirection::Right), Some((10, 10)));
+        new_game.add_portal((11, 10), (8, 10));
+        new_game
+            .snake
+            .body
+            .push(SnakeBodyPoint { x: 9, y: 10, z: 0 });
+        new_game
+            .snake
+            .body
+            .push(SnakeBodyPoint { x: 8, y: 10, z: 0 });
+        new_game
+            .snake
+            .body
+            .push(SnakeBodyPoint { x: 7, y: 10, z: 0 });
+        new_game.set_state(GameState::Playing);
+        new_game.tick();
+        assert_eq!(
+            new_game.result(),
+            Some(GameResult::Died(DeathCause::SelfCollision))
+        );
+    }
+
+    #[test]
+    fn snake_game_power_up_effect() {
+        let mut new_game = SnakeGame::new(42, 24, None, Some((10, 10)));
+        new_game
+            .entities
+            .push(Box::new(PowerUp::new(11, 10, StatusEffect::Ghost, 2)));
+        new_game.set_state(GameState::Playing);
+        new_game.drain_events();
+        new_game.tick();
+        assert_eq!(new_game.snake.effects().remaining(StatusEffect::Ghost), 2);
+        assert!(new_game.drain_events().contains(&GameEvent::EffectStarted {
+            effect: StatusEffect::Ghost
+        }));
+        new_game.tick();
+        assert_eq!(new_game.snake.effects().remaining(StatusEffect::Ghost), 1);
+        new_game.tick();
+        assert!(!new_game.snake.effects().is_active(StatusEffect::Ghost));
+        assert!(new_game.drain_events().contains(&GameEvent::EffectEnded {
+            effect: StatusEffect::Ghost
+        }));
+    }
+
+    #[test]
+    fn snake_game_ghost_ignores_body() {
+        let mut new_game = SnakeGame::new(42, 24, Some(SnakeDirection::Left), Some((10, 10)));
+        new_game
+            .snake
+            .body
+            .push(SnakeBodyPoint { x: 9, y: 10, z: 0 });
+        new_game
+            .snake
+            .body
+            .push(SnakeBodyPoint { x: 9, y: 11, z: 0 });
+        new_game.snake.effects_mut().apply(StatusEffect::Ghost, 5);
+        new_game.snake.advance();
+        assert!(!new_game.check_collisions());
+    }
+
+    #[test]
+    fn snake_game_invincible_walls_wrap() {
+        let mut new_game = SnakeGame::new(42, 24, Some(SnakeDirection::Left), Some((0, 5)));
+        new_game.add_wall((41, 5));
+        new_game
+            .snake
+            .effects_mut()
+            .apply(StatusEffect::InvincibleWalls, 5);
+        new_game.set_state(GameState::Playing);
+        new_game.tick();
+        assert_eq!(new_game.snake.body[0], SnakeBodyPoint { x: 41, y: 5, z: 0 });
+        assert_eq!(new_game.get_state(), GameState::Playing);
+    }
+
+    #[test]
+    fn snake_game_magnet_pulls_entities() {
+        let mut new_game = SnakeGame::new(42, 24, Some(SnakeDirection::Up), Some((10, 10)));
+        new_game.entities.push(Box::new(Apple::new(14, 14)));
+        new_game.entities.push(Box::new(Apple::new(30, 10)));
+        new_game.snake.effects_mut().apply(StatusEffect::Magnet, 5);
+        new_game.set_state(GameState::Playing);
+        new_game.tick();
+        assert_eq!(
+            (new_game.entities[0].x(), new_game.entities[0].y()),
+            (13, 13)
+        );
+        assert_eq!(
+            (new_game.entities[1].x(), new_game.entities[1].y()),
+            (30, 10)
+        );
+    }
+
+    #[test]
+    fn snake_game_hex_topology() {
+        let mut new_game = SnakeGame::new(42, 24, Some(SnakeDirection::UpRight), Some((10, 10)));
+        new_game.set_topology(Topology::Hex);
+        new_game.entities.push(Box::new(Apple::new(13, 10)));
+        new_game.snake.effects_mut().apply(StatusEffect::Magnet, 5);
+        new_game.set_state(GameState::Playing);
+        new_game.tick();
+        assert_eq!(
+            new_game.snake.body[0],
+            SnakeBodyPoint { x: 10, y: 11, z: 0 }
+        );
+        let apple = (new_game.entities[0].x(), new_game.entities[0].y());
+        assert_eq!(Topology::Hex.distance(apple, (10, 11)), 2);
+
+        new_game.reset();
+        assert_eq!(new_game.topology(), Topology::Hex);
+    }
+
+    #[test]
+    fn snake_game_layers() {
+        let mut new_game = SnakeGame::new(2, 2, Some(SnakeDirection::Ascend), Some((0, 0)));
+        new_game.set_layers(2);
+        new_game.add_wall((1, 1));
+        assert_eq!(new_game.empty_spots().len(), 5);
+        assert!(new_game.empty_spots().contains(&(0, 0, 1)));
+
+        new_game.entities.push(Box::new(Apple::new(0, 0)));
+        new_game.entities[0].set_z(1);
+        new_game.set_state(GameState::Playing);
+        assert!(new_game.tick().is_some());
+        assert_eq!(new_game.snake.body[0], SnakeBodyPoint { x: 0, y: 0, z: 1 });
+        new_game.tick();
+        assert_eq!(new_game.result(), Some(GameResult::Died(DeathCause::Wall)));
+
+        new_game.reset();
+        assert!(new_game.snake.is_layered());
+        new_game.snake.grow_by(2);
+        new_game.set_state(GameState::Playing);
+        // Right above its own body is not a collision
+        new_game.tick();
+        assert_eq!(new_game.get_state(), GameState::Playing);
+        new_game.snake.set_direction(SnakeDirection::Up);
+        new_game.tick();
+        assert_eq!(new_game.snake.body[0], SnakeBodyPoint { x: 0, y: 1, z: 1 });
+        assert!(new_game.generate_entity(named!(Apple)));
+    }
+
+    #[test]
+    fn snake_game_layers_keep_flat_entities_on_free_cells() {
+        struct Bomb {
+            x: i16,
+            y: i16,
+        }
+        crate::impl_entity!(Bomb);
+
+        for seed in 0..200 {
+            let mut new_game = SnakeGame::new(3, 1, None, Some((0, 0)));
+            new_game.set_layers(2);
+            new_game.set_seed(Some(seed));
+            new_game.add_wall((2, 0));
+            assert!(new_game.generate_entity(named!(Bomb)));
+            let bomb = &new_game.entities[0];
+            assert_eq!((bomb.x(), bomb.y(), bomb.z()), (1, 0, 0));
+        }
+        // Only the layer it can't go to is left
+        let mut new_game = SnakeGame::new(3, 1, None, Some((0, 0)));
+        new_game.set_layers(2);
+        new_game.add_wall((1, 0));
+        new_game.add_wall((2, 0));
+        assert!(!new_game.generate_entity(named!(Bomb)));
+        assert!(new_game.generate_entity(named!(Apple)));
+        assert_eq!(new_game.entities[0].z(), 1);
+    }
+
+    #[test]
+    fn snake_game_generate_entity_on_big_boards() {
+        for seed in [None, Some(3)] {
+            let mut new_game = SnakeGame::new(83, 20, None, None);
+            new_game.set_layers(20);
+            new_game.set_seed(seed);
+            assert!(new_game.empty_spots().len() > i16::MAX as usize);
+            assert!(new_game.generate_entity(named!(Apple)));
+        }
+    }
+
+    #[test]
+    fn snake_game_tick_scores_meals() {
+        let mut new_game = SnakeGame::new(42, 24, None, Some((10, 10)));
+        new_game.set_scoring_rules(ScoringRules {
+            combo_window: 3,
+            max_combo: 2,
+            ..ScoringRules::default()
+        });
+        new_game.entities.push(Box::new(Apple::new(11, 10)));
+        new_game.entities.push(Box::new(Apple::new(12, 10)));
+        new_game
+            .entities
+            .push(Box::new(PowerUp::new(13, 10, StatusEffect::DoubleScore, 5)));
+        new_game.entities.push(Box::new(Apple::new(14, 10)));
+        new_game.set_state(GameState::Playing);
+        new_game.tick();
+        assert_eq!(new_game.score(), 1);
+        new_game.tick();
+        assert_eq!(new_game.score(), 3);
+        new_game.tick();
+        assert_eq!(new_game.score(), 3);
+        new_game.tick();
+        assert_eq!(new_game.score(), 7);
+        assert_eq!(new_game.scoring().breakdown().multiplier, 2);
+    }
+
+    #[test]
+    fn snake_game_scoring_time_bonus_on_end() {
+        let mut new_game = SnakeGame::new(42, 24, None, Some((10, 10)));
+        new_game.set_scoring_rules(ScoringRules {
+            time_bonus_every: 2,
+            ..ScoringRules::default()
+        });
+        new_game.set_state(GameState::Playing);
+        for _ in 0..5 {
+            new_game.tick();
+        }
+        assert_eq!(new_game.score(), 0);
+        new_game.abort();
+        assert_eq!(new_game.score(), 2);
+        assert_eq!(new_game.scoring().breakdown().time, 2);
+    }
+
+    #[test]
+    fn snake_game_starvation() {
+        let mut new_game = SnakeGame::new(42, 24, None, Some((10, 10)));
+        let mut health = Health::new(3);
+        health.set_restore("Apple", Restore::Amount(1));
+        new_game.snake.set_health(Some(health));
+        new_game.entities.push(Box::new(Apple::new(12, 10)));
+        new_game.set_state(GameState::Playing);
+        new_game.tick();
+        new_game.tick();
+        assert_eq!(new_game.snake.health().unwrap().current(), 2);
+        new_game.tick();
+        assert_eq!(new_game.get_state(), GameState::Playing);
+        new_game.tick();
+        assert_eq!(new_game.get_state(), GameState::GameOver);
+        assert_eq!(
+            new_game.result(),
+            Some(GameResult::Died(DeathCause::Starvation))
+        );
+        let events = new_game.drain_events();
+        assert!(events.contains(&GameEvent::Healed {
+            amount: 1,
+            health: 2
+        }));
+        assert!(events.contains(&GameEvent::Died {
+            cause: DeathCause::Starvation
+        }));
+
+        new_game.reset();
+        assert_eq!(new_game.snake.health().unwrap().current(), 3);
+    }
+
+    #[test]
+    fn snake_game_hazards() {
+        let mut new_game = SnakeGame::new(6, 6, None, Some((2, 3)));
+        new_game.set_hazard_rules(HazardRules {
+            growth: HazardGrowth::Shrink,
+            start: 2,
+            every: 2,
+            damage: HazardDamage::Drain(4),
+        });
+        new_game.snake.set_health(Some(Health::new(7)));
+        new_game.set_state(GameState::Playing);
+        new_game.tick();
+        assert!(new_game.hazards().is_empty());
+        new_game.tick();
+        assert_eq!(new_game.hazards().cells().len(), 20);
+        assert!(
+            new_game
+                .drain_events()
+                .contains(&GameEvent::HazardsGrew { cells: 20 })
+        );
+        assert!(
+            new_game
+                .empty_spots()
+                .iter()
+                .all(|(x, y, _)| !new_game.hazards().contains((*x, *y)))
+        );
+        assert_eq!(new_game.snake.health().unwrap().current(), 5);
+        // (5, 3) is on the first ring
+        new_game.tick();
+        assert_eq!(
+            new_game.result(),
+            Some(GameResult::Died(DeathCause::Hazard))
+        );
+
+        new_game.reset();
+        assert!(new_game.hazards().is_empty());
+        new_game.snake.set_health(None);
+        new_game.hazards_mut().add((3, 3));
+        new_game.set_state(GameState::Playing);
+        new_game.tick();
+        assert_eq!(new_game.collision_cause(), Some(DeathCause::Hazard));
+    }
+
+    #[test]
+    fn snake_game_rival_head_to_head() {
+        for rival_x in [6, 3] {
+            let mut new_game = SnakeGame::new(20, 10, None, Some((2, 5)));
+            new_game.add_rival((rival_x, 5), SnakeDirection::Left);
+            new_game.set_state(GameState::Playing);
+            while new_game.get_state() == GameState::Playing {
+                new_game.tick();
+            }
+            assert_eq!(new_game.result(), Some(GameResult::Draw));
+            let events = new_game.drain_events();
+            assert!(events.contains(&GameEvent::Died {
+                cause: DeathCause::Rival
+            }));
+            assert!(events.contains(&GameEvent::RivalDied {
+                cause: DeathCause::Rival
+            }));
+        }
+    }
+
+    #[test]
+    fn snake_game_rival_hits_snake() {
+        let mut new_game = SnakeGame::new(20, 10, Some(SnakeDirection::Up), Some((5, 5)));
+        new_game.snake.grow_by(3);
+        new_game.add_rival((3, 6), SnakeDirection::Right);
+        new_game.set_state(GameState::Playing);
+        new_game.tick();
+        assert_eq!(new_game.get_state(), GameState::Playing);
+        new_game.tick();
+        assert_eq!(
+            new_game.result(),
+            Some(GameResult::RivalDied(DeathCause::Rival))
+        );
+
+        new_game.reset();
+        let rival = new_game.rival.as_ref().unwrap();
+        assert_eq!(rival.body, vec![SnakeBodyPoint { x: 3, y: 6, z: 0 }]);
+    }
+
+    #[test]
+    fn snake_game_rival_crossed_diagonally() {
+        let mut new_game = SnakeGame::new(20, 10, None, Some((5, 5)));
+        new_game.snake.set_movement(Movement::EightWay);
+        new_game.snake.set_direction(SnakeDirection::UpRight);
+        new_game.snake.grow();
+        new_game.add_rival((6, 5), SnakeDirection::Right);
+        let rival = new_game.rival.as_mut().unwrap();
+        rival.body.push(SnakeBodyPoint { x: 5, y: 6, z: 0 });
+        rival.grow();
+        new_game.set_state(GameState::Playing);
+        new_game.tick();
+        assert_eq!(new_game.snake.body[0], SnakeBodyPoint { x: 6, y: 6, z: 0 });
+        assert_eq!(new_game.result(), Some(GameResult::Died(DeathCause::Rival)));
+    }
+
+    #[test]
+    fn snake_game_rival_eats() {
+        let mut new_game = SnakeGame::new(20, 10, None, Some((10, 8)));
+        new_game.add_rival((2, 2), SnakeDirection::Right);
+        new_game.entities.push(Box::new(Apple::new(3, 2)));
+        new_game.set_state(GameState::Playing);
+        assert!(new_game.tick().is_none());
+        let hit = new_game.take_rival_hit().unwrap();
+        assert!(hit.downcast_ref::<Apple>().is_some());
+        assert!(new_game.take_rival_hit().is_none());
+        assert_eq!(new_game.rival_score(), 1);
+        assert_eq!(new_game.score(), 0);
+        assert!(new_game.drain_events().contains(&GameEvent::RivalScored {
+            points: 1,
+            total: 1
+        }));
+        assert!(
+            new_game
+                .empty_spots()
+                .iter()
+                .all(|(x, y, _)| (*x, *y) != (3, 2))
+        );
     }
 }
@@ -0,0 +1,197 @@
+use std::collections::HashSet;
+
+use super::Topology;
+
+/// How the hazard cells grow over time.
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Default)]
+pub enum HazardGrowth {
+    /// The hazard cells never change.
+    #[default]
+    Static,
+    /// Every step a new ring of cells along the border becomes a hazard, like a battle royale arena closing in.
+    Shrink,
+    /// Every step the cells next to a hazard become hazards too, like poison spreading.
+    Spread,
+}
+
+/// What a hazard does to a snake with its head on it.
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Default)]
+pub enum HazardDamage {
+    /// The snake dies right away.
+    #[default]
+    Deadly,
+    /// The snake loses this much [`Health`](`super::Health`) every tick on top of the usual drain,
+    /// snakes without health die right away.
+    Drain(u32),
+}
+
+/// When and how [`Hazards`] grow and how they hurt.
+/// # Examples
+/// ```
+/// use snake3::snake::{HazardDamage, HazardGrowth, HazardRules};
+/// let royale = HazardRules {
+///     growth: HazardGrowth::Shrink,
+///     start: 100,
+///     every: 25,
+///     damage: HazardDamage::Drain(10),
+/// };
+/// assert!(royale.grows_on(125));
+/// assert!(!royale.grows_on(130));
+/// ```
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub struct HazardRules {
+    pub growth: HazardGrowth,
+    /// Tick of the first growth.
+    pub start: u64,
+    /// Ticks between two growths, `0` grows only once on [`HazardRules::start`].
+    pub every: u64,
+    pub damage: HazardDamage,
+}
+
+impl Default for HazardRules {
+    fn default() -> Self {
+        HazardRules {
+            growth: HazardGrowth::Static,
+            start: 0,
+            every: 0,
+            damage: HazardDamage::Deadly,
+        }
+    }
+}
+
+impl HazardRules {
+    /// Whether the hazards grow on `tick`.
+    pub fn grows_on(&self, tick: u64) -> bool {
+        if self.growth == HazardGrowth::Static || tick < self.start {
+            return false;
+        }
+        match self.every {
+            0 => tick == self.start,
+            every => (tick - self.start).is_multiple_of(every),
+        }
+    }
+}
+
+/// Cells of the board that hurt the snake following some [`HazardRules`]. <br>
+/// Entities are never placed on hazards.
+/// # Examples
+/// ```
+/// use snake3::snake::{HazardGrowth, HazardRules, Hazards, Topology};
+/// let mut hazards = Hazards::new(HazardRules {
+///     growth: HazardGrowth::Shrink,
+///     every: 10,
+///     ..HazardRules::default()
+/// });
+/// hazards.update(10, 8, 8, Topology::Square);
+/// assert!(hazards.contains((0, 3)));
+/// assert!(!hazards.contains((1, 3)));
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct Hazards {
+    rules: HazardRules,
+    seeds: HashSet<(i16, i16)>,
+    cells: HashSet<(i16, i16)>,
+    rings: i16,
+}
+
+impl Hazards {
+    pub fn new(rules: HazardRules) -> Self {
+        Hazards {
+            rules,
+            ..Hazards::default()
+        }
+    }
+    pub fn rules(&self) -> &HazardRules {
+        &self.rules
+    }
+    pub fn set_rules(&mut self, rules: HazardRules) {
+        self.rules = rules;
+    }
+    /// Makes `position` a hazard from the start of the game, [`HazardGrowth::Spread`] grows from these cells.
+    pub fn add(&mut self, position: (i16, i16)) {
+        self.seeds.insert(position);
+        self.cells.insert(position);
+    }
+    pub fn contains(&self, position: (i16, i16)) -> bool {
+        self.cells.contains(&position)
+    }
+    pub fn cells(&self) -> &HashSet<(i16, i16)> {
+        &self.cells
+    }
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+    /// Back to the cells added with [`Hazards::add`].
+    pub fn reset(&mut self) {
+        self.cells = self.seeds.clone();
+        self.rings = 0;
+    }
+    /// Grows the hazards if the rules say so on `tick`, on a board of `columns` by `rows`,
+    /// and returns how many cells became hazards.
+    pub fn update(&mut self, tick: u64, columns: i16, rows: i16, topology: Topology) -> usize {
+        if !self.rules.grows_on(tick) {
+            return 0;
+        }
+        let before = self.cells.len();
+        match self.rules.growth {
+            HazardGrowth::Static => {}
+            HazardGrowth::Shrink => {
+                self.rings += 1;
+                let rings = self.rings;
+                self.cells.extend(
+                    (0..columns)
+                        .flat_map(|x| (0..rows).map(move |y| (x, y)))
+                        .filter(|(x, y)| {
+                            (*x).min(*y).min(columns - 1 - x).min(rows - 1 - y) < rings
+                        }),
+                );
+            }
+            HazardGrowth::Spread => {
+                let next: Vec<(i16, i16)> = self
+                    .cells
+                    .iter()
+                    .flat_map(|cell| topology.neighbours(*cell))
+                    .filter(|(x, y)| *x >= 0 && *y >= 0 && *x < columns && *y < rows)
+                    .collect();
+                self.cells.extend(next);
+            }
+        }
+        self.cells.len() - before
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hazards_shrink() {
+        let mut hazards = Hazards::new(HazardRules {
+            growth: HazardGrowth::Shrink,
+            start: 5,
+            every: 5,
+            ..HazardRules::default()
+        });
+        assert_eq!(hazards.update(4, 6, 6, Topology::Square), 0);
+        assert_eq!(hazards.update(5, 6, 6, Topology::Square), 20);
+        assert_eq!(hazards.update(7, 6, 6, Topology::Square), 0);
+        assert_eq!(hazards.update(10, 6, 6, Topology::Square), 12);
+        assert!(!hazards.contains((2, 3)));
+        hazards.reset();
+        assert!(hazards.is_empty());
+    }
+
+    #[test]
+    fn hazards_spread() {
+        let mut hazards = Hazards::new(HazardRules {
+            growth: HazardGrowth::Spread,
+            every: 1,
+            ..HazardRules::default()
+        });
+        hazards.add((0, 0));
+        assert_eq!(hazards.update(0, 5, 5, Topology::Square), 3);
+        assert_eq!(hazards.update(1, 5, 5, Topology::Square), 5);
+        hazards.reset();
+        assert_eq!(hazards.cells().len(), 1);
+    }
+}
@@ -0,0 +1,120 @@
+/// How much [`Health`] eating an entity gives back.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum Restore {
+    /// Back to the maximum health.
+    Full,
+    /// Adds this much health, up to the maximum.
+    Amount(u32),
+}
+
+/// Health of a [`Snake`](`super::Snake`) that drains every tick and is restored by eating. <br>
+/// The snake starves when it runs out, see [`DeathCause::Starvation`](`super::DeathCause::Starvation`).
+/// # Examples
+/// ```
+/// use snake3::snake::{Health, Restore};
+/// let mut health = Health::new(100);
+/// health.set_restore("GoldenApple", Restore::Amount(50));
+/// health.drain();
+/// assert_eq!(health.current(), 99);
+/// assert_eq!(health.eat("Apple"), 1);
+/// assert_eq!(health.current(), 100);
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Health {
+    current: u32,
+    max: u32,
+    drain: u32,
+    restores: Vec<(&'static str, Restore)>,
+}
+
+impl Health {
+    /// Full health of `max`, draining one every tick and fully restored by apples.
+    pub fn new(max: u32) -> Self {
+        Health {
+            current: max,
+            max,
+            drain: 1,
+            restores: vec![("Apple", Restore::Full)],
+        }
+    }
+    pub fn current(&self) -> u32 {
+        self.current
+    }
+    pub fn max(&self) -> u32 {
+        self.max
+    }
+    /// Part of the health left, from `0.0` to `1.0`, handy to draw a health bar.
+    pub fn fraction(&self) -> f32 {
+        if self.max == 0 {
+            return 0.0;
+        }
+        self.current as f32 / self.max as f32
+    }
+    pub fn is_empty(&self) -> bool {
+        self.current == 0
+    }
+    /// Health lost every tick.
+    pub fn set_drain(&mut self, drain: u32) {
+        self.drain = drain;
+    }
+    /// How eating the entity called `entity` restores health, entities without a restore don't feed the snake.
+    pub fn set_restore(&mut self, entity: &'static str, restore: Restore) {
+        self.restores.retain(|(name, _)| *name != entity);
+        self.restores.push((entity, restore));
+    }
+    /// What eating the entity called `entity` restores, if anything.
+    pub fn restore_for(&self, entity: &str) -> Option<Restore> {
+        self.restores
+            .iter()
+            .find(|(name, _)| *name == entity)
+            .map(|(_, restore)| *restore)
+    }
+    /// Loses one tick worth of health and returns what is left.
+    pub fn drain(&mut self) -> u32 {
+        self.current = self.current.saturating_sub(self.drain);
+        self.current
+    }
+    /// Loses `amount` of health, like from a [`HazardDamage::Drain`](`super::HazardDamage::Drain`), and returns what is left.
+    pub fn take(&mut self, amount: u32) -> u32 {
+        self.current = self.current.saturating_sub(amount);
+        self.current
+    }
+    /// Restores the health for eating the entity called `entity` and returns how much was gained.
+    pub fn eat(&mut self, entity: &str) -> u32 {
+        let before = self.current;
+        self.current = match self.restore_for(entity) {
+            Some(Restore::Full) => self.max,
+            Some(Restore::Amount(amount)) => self.current.saturating_add(amount).min(self.max),
+            None => self.current,
+        };
+        self.current - before
+    }
+    /// Back to full health, keeping the drain and restores.
+    pub fn refill(&mut self) {
+        self.current = self.max;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn health_drain_and_restore() {
+        let mut health = Health::new(10);
+        health.set_drain(4);
+        health.set_restore("Apple", Restore::Amount(3));
+        assert_eq!(health.drain(), 6);
+        assert_eq!(health.eat("Apple"), 3);
+        assert_eq!(health.eat("Apple"), 1);
+        assert_eq!(health.eat("PowerUp"), 0);
+        assert_eq!(health.drain(), 6);
+        assert_eq!(health.drain(), 2);
+        assert_eq!(health.drain(), 0);
+        assert!(health.is_empty());
+        assert_eq!(health.fraction(), 0.0);
+        health.refill();
+        assert_eq!(health.current(), 10);
+        assert_eq!(health.fraction(), 1.0);
+    }
+}
@@ -5,8 +5,44 @@ macro_rules! named {
     };
 }
 
+/// Implements [`Entity`](`crate::snake::Entity`) and a `new(x, y)` constructor for a struct with `x` and `y` fields. <br>
+/// With `layered` the struct also needs a `z` field and the entity can be placed on any layer of the board.
 #[macro_export]
 macro_rules! impl_entity {
+    ($type:ident, layered) => {
+        impl $type {
+            pub fn new(x: i16, y: i16) -> Self {
+                Self { x, y, z: 0 }
+            }
+        }
+
+        impl Entity for $type {
+            fn as_any(&self) -> &dyn std::any::Any {
+                self
+            }
+
+            fn x(&self) -> i16 {
+                self.x
+            }
+
+            fn y(&self) -> i16 {
+                self.y
+            }
+
+            fn z(&self) -> i16 {
+                self.z
+            }
+
+            fn set_position(&mut self, x: i16, y: i16) {
+                self.x = x;
+                self.y = y;
+            }
+
+            fn set_z(&mut self, z: i16) {
+                self.z = z;
+            }
+        }
+    };
     ($type:ident) => {
         impl $type {
             pub fn new(x: i16, y: i16) -> Self {
@@ -26,6 +62,11 @@ macro_rules! impl_entity {
             fn y(&self) -> i16 {
                 self.y
             }
+
+            fn set_position(&mut self, x: i16, y: i16) {
+                self.x = x;
+                self.y = y;
+            }
         }
     };
 }
@@ -0,0 +1,185 @@
+use std::{collections::BTreeMap, fmt, str::FromStr};
+
+use super::{SnakeDirection, portal::PortalPair};
+
+/// A board layout parsed from text, one character per cell:
+/// - `#` is a wall.
+/// - `.` or a space is an empty cell.
+/// - `>`, `<`, `^` and `v` is where the snake starts and the direction it faces, `^` moves to the top of the text.
+/// - A digit is the end of a portal, every digit has to appear exactly twice. <br>
+///
+/// Lines shorter than the widest one are padded with empty cells.
+/// # Examples
+/// ```
+/// # use snake3::SnakeGame;
+/// use snake3::snake::Map;
+/// let map: Map = "
+/// ##########
+/// #........#
+/// #..>.....#
+/// ##########
+/// ".parse().unwrap();
+/// let game = SnakeGame::from_map(&map);
+/// assert_eq!(game.dimensions(), (10, 4));
+/// ```
+#[derive(Debug, PartialEq, Clone)]
+pub struct Map {
+    pub columns: i16,
+    pub rows: i16,
+    pub walls: Vec<(i16, i16)>,
+    pub portals: Vec<PortalPair>,
+    pub start: Option<(i16, i16)>,
+    pub direction: Option<SnakeDirection>,
+}
+
+/// Returned when a [`Map`] can't be parsed.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct MapError(pub String);
+
+impl fmt::Display for MapError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for MapError {}
+
+impl FromStr for Map {
+    type Err = MapError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let lines: Vec<&str> = s
+            .lines()
+            .skip_while(|line| line.trim().is_empty())
+            .collect();
+        let lines: Vec<&str> = match lines.iter().rposition(|line| !line.trim().is_empty()) {
+            Some(last) => lines[..=last].to_vec(),
+            None => return Err(MapError("The map is empty.".to_string())),
+        };
+        let columns = lines
+            .iter()
+            .map(|line| line.chars().count())
+            .max()
+            .unwrap_or(0);
+        if columns > i16::MAX as usize || lines.len() > i16::MAX as usize {
+            return Err(MapError("The map is too big.".to_string()));
+        }
+        let mut map = Map {
+            columns: columns as i16,
+            rows: lines.len() as i16,
+            walls: Vec::new(),
+            portals: Vec::new(),
+            start: None,
+            direction: None,
+        };
+        let mut portal_ends: BTreeMap<char, Vec<(i16, i16)>> = BTreeMap::new();
+        for (y, line) in lines.iter().enumerate() {
+            for (x, ch) in line.chars().enumerate() {
+                let position = (x as i16, y as i16);
+                let direction = match ch {
+                    '#' => {
+                        map.walls.push(position);
+                        continue;
+                    }
+                    '.' | ' ' => continue,
+                    '0'..='9' => {
+                        portal_ends.entry(ch).or_default().push(position);
+                        continue;
+                    }
+                    // The terminal draws `y` growing downwards, `Down` is towards the top
+                    '^' => SnakeDirection::Down,
+                    'v' => SnakeDirection::Up,
+                    '<' => SnakeDirection::Left,
+                    '>' => SnakeDirection::Right,
+                    other => {
+                        return Err(MapError(format!(
+                            "Unknown map cell '{other}' at ({x}, {y})."
+                        )));
+                    }
+                };
+                if map.start.is_some() {
+                    return Err(MapError("The map has more than one start.".to_string()));
+                }
+                map.start = Some(position);
+                map.direction = Some(direction);
+            }
+        }
+        for (digit, ends) in portal_ends {
+            match ends[..] {
+                [a, b] => map.portals.push((a, b)),
+                _ => {
+                    return Err(MapError(format!(
+                        "Portal '{digit}' has to appear exactly twice."
+                    )));
+                }
+            }
+        }
+        Ok(map)
+    }
+}
+
+impl Map {
+    /// An empty map with no walls.
+    pub fn empty(columns: i16, rows: i16) -> Self {
+        Map {
+            columns,
+            rows,
+            walls: Vec::new(),
+            portals: Vec::new(),
+            start: None,
+            direction: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn map_parse() {
+        let map: Map = "\n#####\n#.<.\n#####\n".parse().unwrap();
+        assert_eq!(map.columns, 5);
+        assert_eq!(map.rows, 3);
+        assert_eq!(map.walls.len(), 11);
+        assert!(map.walls.contains(&(0, 1)));
+        assert!(!map.walls.contains(&(4, 1)));
+        assert_eq!(map.start, Some((2, 1)));
+        assert_eq!(map.direction, Some(SnakeDirection::Left));
+    }
+
+    #[test]
+    fn map_parse_portals() {
+        let map: Map = "1.2\n...\n2.1".parse().unwrap();
+        assert_eq!(map.portals, vec![((0, 0), (2, 2)), ((2, 0), (0, 2))]);
+        assert!(map.walls.is_empty());
+    }
+
+    #[test]
+    fn map_parse_errors() {
+        assert_eq!(
+            "".parse::<Map>(),
+            Err(MapError("The map is empty.".to_string()))
+        );
+        assert_eq!(
+            "#X#".parse::<Map>(),
+            Err(MapError("Unknown map cell 'X' at (1, 0).".to_string()))
+        );
+        assert_eq!(
+            ">.<".parse::<Map>(),
+            Err(MapError("The map has more than one start.".to_string()))
+        );
+        assert_eq!(
+            "1.1.1".parse::<Map>(),
+            Err(MapError(
+                "Portal '1' has to appear exactly twice.".to_string()
+            ))
+        );
+        assert_eq!(
+            "..2..".parse::<Map>(),
+            Err(MapError(
+                "Portal '2' has to appear exactly twice.".to_string()
+            ))
+        );
+    }
+}
@@ -1,10 +1,48 @@
+pub mod campaign;
+pub mod clock;
+pub mod daily;
+pub mod difficulty;
+pub mod effects;
 pub mod entities;
+pub mod events;
 pub mod game;
+pub mod hazards;
+pub mod health;
 pub mod macros;
+pub mod map;
+pub mod outcome;
+pub mod plugins;
+pub mod portal;
 pub mod random;
+pub mod registry;
+pub mod replay;
+pub mod scoring;
 pub mod snake_obj;
+pub mod state;
+pub mod topology;
+pub mod versus;
 
-pub use entities::{Apple, Entity};
-pub use game::{GameState, SnakeGame};
-pub use random::random_range;
-pub use snake_obj::{Snake, SnakeDirection};
+pub use campaign::{Campaign, CampaignProgress, CampaignStep, Level};
+pub use clock::GameClock;
+pub use daily::{
+    DAILY_COLUMNS, DAILY_ROWS, DailyChallenge, DailyResult, DailyResults, DailyVariant, Date,
+};
+pub use difficulty::{Difficulty, DifficultyCurve};
+pub use effects::{ActiveEffect, Stacking, StatusEffect, StatusEffects};
+pub use entities::{Apple, Entity, PowerUp};
+pub use events::{DeathCause, EventListener, GameEvent};
+pub use game::{MAX_QUEUED_EVENTS, SnakeGame};
+pub use hazards::{HazardDamage, HazardGrowth, HazardRules, Hazards};
+pub use health::{Health, Restore};
+pub use map::{Map, MapError};
+pub use outcome::{GameResult, WinCondition};
+pub use plugins::{AppleGrowth, GamePlugin, Verdict};
+pub use portal::{PortalPair, Portals};
+pub use random::{SeededRng, random_index, random_range};
+pub use registry::{EntityColor, EntityKind, EntityRegistry, RegistryError};
+pub use replay::{Ghost, Recording};
+pub use scoring::{ScoreBreakdown, Scoring, ScoringRules};
+pub use snake_obj::{Movement, Snake, SnakeBodyPoint, SnakeDirection};
+pub use state::{GameState, InvalidTransition, StateTransition, TransitionHook};
+pub use topology::Topology;
+pub use versus::{Player, VersusMatch};
@@ -0,0 +1,97 @@
+use super::{DeathCause, SnakeGame};
+
+/// How a game finished, see [`SnakeGame::result`].
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum GameResult {
+    /// One of the [`WinCondition`]s was met.
+    Won,
+    /// The snake died before winning, in a versus game the [rival](`SnakeGame::rival`) wins.
+    Died(DeathCause),
+    /// The [rival](`SnakeGame::rival`) died and the snake wins the versus game.
+    RivalDied(DeathCause),
+    /// Both snakes died on the same tick.
+    Draw,
+    /// The game was stopped with [`SnakeGame::abort`].
+    Aborted,
+}
+
+/// Conditions that make the player win the game, set them with [`SnakeGame::set_win_conditions`]. <br>
+/// The game is won as soon as any of them is met.
+/// # Examples
+/// ```
+/// # use snake3::SnakeGame;
+/// use snake3::snake::WinCondition;
+/// let mut new_game = SnakeGame::new(10, 10, None, None);
+/// new_game.set_win_conditions(vec![WinCondition::TargetLength(20), WinCondition::SurviveTicks(500)]);
+/// ```
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum WinCondition {
+    /// The snake body covers every cell of the board that isn't a wall or a portal, the perfect run.
+    /// Hazards still count as cells to cover.
+    BoardFilled,
+    /// The snake body reaches this length.
+    TargetLength(usize),
+    /// The score reaches this value.
+    TargetScore(u64),
+    /// The snake stays alive for this many ticks.
+    SurviveTicks(u64),
+}
+
+impl WinCondition {
+    /// Returns `true` if the game meets this condition.
+    pub fn is_met(&self, game: &SnakeGame) -> bool {
+        match *self {
+            WinCondition::BoardFilled => game.unfilled_spots().is_empty(),
+            WinCondition::TargetLength(length) => game.snake.body.len() >= length,
+            WinCondition::TargetScore(score) => game.score() >= score,
+            WinCondition::SurviveTicks(ticks) => game.ticks() >= ticks,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::GameState;
+    use crate::snake::{HazardDamage, HazardGrowth, HazardRules, Health};
+
+    #[test]
+    fn win_condition_is_met() {
+        let mut game = SnakeGame::new(42, 24, None, None);
+        assert!(!WinCondition::BoardFilled.is_met(&game));
+        assert!(WinCondition::TargetLength(1).is_met(&game));
+        assert!(!WinCondition::TargetLength(2).is_met(&game));
+        assert!(WinCondition::TargetScore(0).is_met(&game));
+        game.scoring_mut().add_bonus(9);
+        assert!(!WinCondition::TargetScore(10).is_met(&game));
+        game.scoring_mut().add_bonus(1);
+        assert!(WinCondition::TargetScore(10).is_met(&game));
+        assert!(!WinCondition::SurviveTicks(1).is_met(&game));
+    }
+
+    #[test]
+    fn win_condition_board_filled() {
+        let game = SnakeGame::new(1, 1, None, None);
+        assert!(WinCondition::BoardFilled.is_met(&game));
+    }
+
+    #[test]
+    fn win_condition_board_filled_counts_hazards() {
+        let mut game = SnakeGame::new(6, 6, None, Some((3, 3)));
+        game.set_hazard_rules(HazardRules {
+            growth: HazardGrowth::Shrink,
+            start: 1,
+            every: 1,
+            damage: HazardDamage::Drain(1),
+        });
+        game.snake.set_health(Some(Health::new(100)));
+        game.set_win_conditions(vec![WinCondition::BoardFilled]);
+        game.set_state(GameState::Playing);
+        for _ in 0..5 {
+            game.tick();
+        }
+        assert!(game.empty_spots().is_empty());
+        assert!(!WinCondition::BoardFilled.is_met(&game));
+        assert_ne!(game.result(), Some(GameResult::Won));
+    }
+}
@@ -0,0 +1,228 @@
+use super::{Apple, DeathCause, Entity, GameResult, Player, SnakeGame};
+
+/// What a [`GamePlugin`] thinks of something the game is about to do.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum Verdict {
+    Allow,
+    /// The game does not do it, the plugins after this one are not asked.
+    Veto,
+}
+
+/// Rules that hook into each phase of [`SnakeGame::tick`], registered with [`SnakeGame::add_plugin`]. <br>
+/// Every hook gets the game to look at and change, plugins run in the order they were added and
+/// the ones answering with a [`Verdict`] can veto what the game is about to do. Hooks that are not
+/// implemented do nothing and allow everything.
+/// # Examples
+/// ```
+/// # use snake3::{SnakeGame, GameState};
+/// use snake3::snake::{DeathCause, GamePlugin, Player, Verdict};
+/// /// The snake comes out on the other side of the board instead of hitting it.
+/// struct Wraparound;
+///
+/// impl GamePlugin for Wraparound {
+///     fn on_collision(&mut self, game: &mut SnakeGame, _: Player, cause: DeathCause) -> Verdict {
+///         if cause != DeathCause::Wall {
+///             return Verdict::Allow;
+///         }
+///         let (columns, rows) = (game.columns + 1, game.rows + 1);
+///         let head = &mut game.snake.body[0];
+///         head.x = head.x.rem_euclid(columns);
+///         head.y = head.y.rem_euclid(rows);
+///         Verdict::Veto
+///     }
+/// }
+///
+/// let mut game = SnakeGame::new(10, 10, None, Some((10, 5)));
+/// game.add_plugin(Wraparound);
+/// game.set_state(GameState::Playing);
+/// game.tick();
+/// assert_eq!(game.get_state(), GameState::Playing);
+/// assert_eq!(game.snake.body[0].x, 0);
+/// ```
+pub trait GamePlugin {
+    /// Name of the plugin, defaults to the type name.
+    fn name(&self) -> &'static str {
+        let full_name = std::any::type_name::<Self>();
+        full_name.rsplit("::").next().unwrap_or(full_name)
+    }
+    /// Before the snakes move, ending the game here skips the rest of the tick.
+    fn pre_move(&mut self, _game: &mut SnakeGame) {}
+    /// After the snakes moved and the [hazards](`SnakeGame::hazards`) grew, before checking for collisions.
+    /// Ending the game here skips the rest of the tick.
+    fn post_move(&mut self, _game: &mut SnakeGame) {}
+    /// The snake of `player` hit something and is about to die of `cause`, a veto keeps it alive.
+    /// Starving is not a collision and does not go through here.
+    fn on_collision(
+        &mut self,
+        _game: &mut SnakeGame,
+        _player: Player,
+        _cause: DeathCause,
+    ) -> Verdict {
+        Verdict::Allow
+    }
+    /// The snake of `player` is about to eat `entity`, a veto leaves it on the board.
+    /// It is also called by [`SnakeGame::check_entity_collision`].
+    fn on_eat(&mut self, _game: &mut SnakeGame, _player: Player, _entity: &dyn Entity) -> Verdict {
+        Verdict::Allow
+    }
+    /// [`SnakeGame::generate_entity`] is about to place `entity`, a veto leaves it out of the board.
+    fn on_spawn(&mut self, _game: &mut SnakeGame, _entity: &dyn Entity) -> Verdict {
+        Verdict::Allow
+    }
+    /// The game just finished with `result`, `None` if the state was changed by hand without one.
+    fn on_end(&mut self, _game: &mut SnakeGame, _result: Option<GameResult>) {}
+}
+
+/// The classic rule: snakes grow one body point for every [`Apple`] they eat.
+/// # Examples
+/// ```
+/// # use snake3::{SnakeGame, GameState};
+/// use snake3::snake::{Apple, AppleGrowth};
+/// let mut game = SnakeGame::new(10, 10, None, Some((2, 2)));
+/// game.add_plugin(AppleGrowth);
+/// game.entities.push(Box::new(Apple::new(3, 2)));
+/// game.set_state(GameState::Playing);
+/// game.tick();
+/// assert_eq!(game.snake.pending_growth(), 1);
+/// ```
+#[derive(Debug, Copy, Clone, Default)]
+pub struct AppleGrowth;
+
+impl GamePlugin for AppleGrowth {
+    fn on_eat(&mut self, game: &mut SnakeGame, player: Player, entity: &dyn Entity) -> Verdict {
+        if entity.downcast_ref::<Apple>().is_some() {
+            match player {
+                Player::One => game.snake.grow(),
+                Player::Two => {
+                    if let Some(rival) = &mut game.rival {
+                        rival.grow();
+                    }
+                }
+            }
+        }
+        Verdict::Allow
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    use super::*;
+    use crate::GameState;
+    use crate::snake::{PowerUp, StatusEffect};
+
+    /// Writes down every hook it gets and vetoes what it is told to.
+    struct Recorder {
+        tag: &'static str,
+        log: Rc<RefCell<Vec<String>>>,
+        veto_eat: bool,
+        veto_spawn: bool,
+    }
+
+    impl Recorder {
+        fn new(tag: &'static str, log: &Rc<RefCell<Vec<String>>>) -> Self {
+            Recorder {
+                tag,
+                log: Rc::clone(log),
+                veto_eat: false,
+                veto_spawn: false,
+            }
+        }
+        fn write(&self, hook: String) {
+            self.log.borrow_mut().push(format!("{} {hook}", self.tag));
+        }
+    }
+
+    impl GamePlugin for Recorder {
+        fn pre_move(&mut self, game: &mut SnakeGame) {
+            self.write(format!("pre {}", game.ticks()));
+        }
+        fn post_move(&mut self, game: &mut SnakeGame) {
+            self.write(format!("post {}", game.ticks()));
+        }
+        fn on_collision(&mut self, _: &mut SnakeGame, _: Player, cause: DeathCause) -> Verdict {
+            self.write(format!("collision {cause:?}"));
+            Verdict::Allow
+        }
+        fn on_eat(&mut self, _: &mut SnakeGame, _: Player, entity: &dyn Entity) -> Verdict {
+            self.write(format!("eat {}", entity.name()));
+            if self.veto_eat {
+                Verdict::Veto
+            } else {
+                Verdict::Allow
+            }
+        }
+        fn on_spawn(&mut self, _: &mut SnakeGame, entity: &dyn Entity) -> Verdict {
+            self.write(format!("spawn {}", entity.name()));
+            if self.veto_spawn {
+                Verdict::Veto
+            } else {
+                Verdict::Allow
+            }
+        }
+        fn on_end(&mut self, _: &mut SnakeGame, result: Option<GameResult>) {
+            self.write(format!("end {result:?}"));
+        }
+    }
+
+    #[test]
+    fn plugins_run_in_order_on_every_phase() {
+        let log = Rc::new(RefCell::new(Vec::new()));
+        let mut game = SnakeGame::new(3, 3, None, Some((2, 1)));
+        game.add_plugin(Recorder::new("a", &log));
+        game.add_plugin(Recorder::new("b", &log));
+        assert_eq!(game.plugin_names(), ["Recorder", "Recorder"]);
+        game.entities.push(Box::new(Apple::new(3, 1)));
+        game.set_state(GameState::Playing);
+        assert!(game.tick().is_some());
+        game.tick();
+        assert_eq!(
+            *log.borrow(),
+            [
+                "a pre 0",
+                "b pre 0",
+                "a post 1",
+                "b post 1",
+                "a eat Apple",
+                "b eat Apple",
+                "a pre 1",
+                "b pre 1",
+                "a post 2",
+                "b post 2",
+                "a collision Wall",
+                "b collision Wall",
+                "a end Some(Died(Wall))",
+                "b end Some(Died(Wall))",
+            ]
+        );
+    }
+
+    #[test]
+    fn plugins_veto_outcomes() {
+        let log = Rc::new(RefCell::new(Vec::new()));
+        let mut game = SnakeGame::new(10, 10, None, Some((2, 2)));
+        let mut picky = Recorder::new("a", &log);
+        picky.veto_eat = true;
+        picky.veto_spawn = true;
+        game.add_plugin(picky);
+        game.add_plugin(Recorder::new("b", &log));
+        game.entities.push(Box::new(Apple::new(3, 2)));
+        game.set_state(GameState::Playing);
+        // The apple stays and nobody after the veto hears about it
+        assert!(game.tick().is_none());
+        assert_eq!(game.entities.len(), 1);
+        assert_eq!(game.score(), 0);
+        assert!(!game.generate_entity(|x, y| Box::new(PowerUp::new(
+            x,
+            y,
+            StatusEffect::Magnet,
+            5
+        ))));
+        assert_eq!(game.entities.len(), 1);
+        assert!(log.borrow().contains(&"a eat Apple".to_string()));
+        assert!(!log.borrow().contains(&"b eat Apple".to_string()));
+        assert!(!log.borrow().contains(&"b spawn PowerUp".to_string()));
+    }
+}
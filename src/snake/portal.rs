@@ -0,0 +1,75 @@
+/// Two linked cells.
+pub type PortalPair = ((i16, i16), (i16, i16));
+
+/// Pairs of linked cells, when the snake head enters one cell of a pair it comes out of the other one
+/// keeping its direction, see [`Snake::advance_through`](`super::Snake::advance_through`).
+/// # Examples
+/// ```
+/// use snake3::snake::Portals;
+/// let mut portals = Portals::default();
+/// portals.add_pair((1, 1), (8, 8));
+/// assert_eq!(portals.exit_for((8, 8)), Some((1, 1)));
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct Portals {
+    pairs: Vec<PortalPair>,
+}
+
+impl Portals {
+    /// Links two cells.
+    /// # Panics
+    /// - If both ends are the same cell or one of them already belongs to a pair.
+    pub fn add_pair(&mut self, a: (i16, i16), b: (i16, i16)) {
+        if a == b || self.contains(a) || self.contains(b) {
+            panic!("A cell can only be the end of one portal.")
+        }
+        self.pairs.push((a, b));
+    }
+    /// Returns the cell linked to `position` if it is a portal.
+    pub fn exit_for(&self, position: (i16, i16)) -> Option<(i16, i16)> {
+        self.pairs.iter().find_map(|&(a, b)| {
+            if a == position {
+                Some(b)
+            } else if b == position {
+                Some(a)
+            } else {
+                None
+            }
+        })
+    }
+    pub fn contains(&self, position: (i16, i16)) -> bool {
+        self.exit_for(position).is_some()
+    }
+    pub fn pairs(&self) -> &[PortalPair] {
+        &self.pairs
+    }
+    pub fn is_empty(&self) -> bool {
+        self.pairs.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn portals_exit_for() {
+        let mut portals = Portals::default();
+        assert!(portals.is_empty());
+        portals.add_pair((1, 2), (5, 6));
+        portals.add_pair((0, 0), (9, 9));
+        assert_eq!(portals.exit_for((1, 2)), Some((5, 6)));
+        assert_eq!(portals.exit_for((9, 9)), Some((0, 0)));
+        assert_eq!(portals.exit_for((3, 3)), None);
+        assert!(portals.contains((5, 6)));
+        assert_eq!(portals.pairs().len(), 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "A cell can only be the end of one portal.")]
+    fn portals_shared_end() {
+        let mut portals = Portals::default();
+        portals.add_pair((1, 2), (5, 6));
+        portals.add_pair((5, 6), (7, 7));
+    }
+}
@@ -14,3 +14,99 @@ pub fn random_range(min: i16, max: i16) -> i16 {
     let mut rng = rand::rng();
     rng.random_range(min..max)
 }
+
+/// Same as [`random_range`] for indexes, a number from `0` up to but not including `len`.
+#[cfg(target_arch = "wasm32")]
+pub fn random_index(len: usize) -> usize {
+    gen_range(0, len)
+}
+
+/// Same as [`random_range`] for indexes, a number from `0` up to but not including `len`.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn random_index(len: usize) -> usize {
+    let mut rng = rand::rng();
+    rng.random_range(0..len)
+}
+
+/// Random numbers that are always the same for the same seed, on every platform. <br>
+/// Used by [`SnakeGame::set_seed`](`super::SnakeGame::set_seed`) so a game can be played again.
+/// # Examples
+/// ```
+/// use snake3::snake::SeededRng;
+/// let mut a = SeededRng::new(42);
+/// let mut b = SeededRng::new(42);
+/// assert_eq!(a.range(0, 100), b.range(0, 100));
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct SeededRng {
+    seed: u64,
+    state: u64,
+}
+
+impl SeededRng {
+    pub fn new(seed: u64) -> Self {
+        SeededRng { seed, state: seed }
+    }
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+    /// Starts the sequence over from the seed.
+    pub fn reset(&mut self) {
+        self.state = self.seed;
+    }
+    /// Next number of the sequence, using SplitMix64.
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+    /// Same as [`random_range`], a number from `min` up to but not including `max`.
+    /// # Panics
+    /// - If `max` is not greater than `min`.
+    pub fn range(&mut self, min: i16, max: i16) -> i16 {
+        if max <= min {
+            panic!("The range {min}..{max} is empty.");
+        }
+        let span = (max as i32 - min as i32) as u64;
+        (min as i64 + (self.next_u64() % span) as i64) as i16
+    }
+    /// Same as [`random_index`], a number from `0` up to but not including `len`. <br>
+    /// Gives the same numbers as [`SeededRng::range`] from `0` for the lengths both can take.
+    /// # Panics
+    /// - If `len` is zero.
+    pub fn index(&mut self, len: usize) -> usize {
+        if len == 0 {
+            panic!("The range 0..0 is empty.");
+        }
+        (self.next_u64() % len as u64) as usize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn seeded_rng_repeats() {
+        let mut rng = SeededRng::new(7);
+        let first: Vec<i16> = (0..20).map(|_| rng.range(-3, 4)).collect();
+        assert!(first.iter().all(|value| (-3..4).contains(value)));
+        rng.reset();
+        let again: Vec<i16> = (0..20).map(|_| rng.range(-3, 4)).collect();
+        assert_eq!(first, again);
+        assert_ne!(SeededRng::new(8).next_u64(), SeededRng::new(7).next_u64());
+    }
+
+    #[test]
+    fn seeded_rng_index() {
+        let mut rng = SeededRng::new(7);
+        let small: Vec<i16> = (0..20).map(|_| rng.range(0, 50)).collect();
+        rng.reset();
+        let indexes: Vec<i16> = (0..20).map(|_| rng.index(50) as i16).collect();
+        assert_eq!(small, indexes);
+        assert!((0..20).all(|_| rng.index(100_000) < 100_000));
+        assert!(random_index(100_000) < 100_000);
+    }
+}
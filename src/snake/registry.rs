@@ -0,0 +1,380 @@
+use std::{fmt, rc::Rc, str::FromStr};
+
+use super::{Apple, Entity, PowerUp, StatusEffect};
+
+/// Colours an [`EntityKind`] asks renderers to draw its entities with, each renderer maps them to its own palette.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum EntityColor {
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+    Grey,
+}
+
+impl EntityColor {
+    pub const ALL: [EntityColor; 8] = [
+        EntityColor::Red,
+        EntityColor::Green,
+        EntityColor::Yellow,
+        EntityColor::Blue,
+        EntityColor::Magenta,
+        EntityColor::Cyan,
+        EntityColor::White,
+        EntityColor::Grey,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            EntityColor::Red => "red",
+            EntityColor::Green => "green",
+            EntityColor::Yellow => "yellow",
+            EntityColor::Blue => "blue",
+            EntityColor::Magenta => "magenta",
+            EntityColor::Cyan => "cyan",
+            EntityColor::White => "white",
+            EntityColor::Grey => "grey",
+        }
+    }
+}
+
+impl fmt::Display for EntityColor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for EntityColor {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        EntityColor::ALL
+            .into_iter()
+            .find(|color| color.name().eq_ignore_ascii_case(s))
+            .ok_or_else(|| format!("Unknown colour '{s}'."))
+    }
+}
+
+/// Returned when an entity can't be saved or loaded by an [`EntityRegistry`].
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct RegistryError(pub String);
+
+impl fmt::Display for RegistryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for RegistryError {}
+
+type SaveHook = Rc<dyn Fn(&dyn Entity) -> Option<String>>;
+type LoadHook = Rc<dyn Fn(i16, i16, &str) -> Option<Box<dyn Entity>>>;
+
+/// What renderers and saves need to know about a type of [`Entity`], registered with
+/// [`SnakeGame::register_entity`](`super::SnakeGame::register_entity`).
+/// # Examples
+/// ```
+/// # use snake3::{SnakeGame, impl_entity};
+/// use snake3::snake::{Entity, EntityColor, EntityKind};
+/// pub struct Bomb {
+///     pub x: i16,
+///     pub y: i16,
+/// }
+/// impl_entity!(Bomb);
+///
+/// let mut new_game = SnakeGame::new(10, 10, None, None);
+/// new_game.register_entity(
+///     EntityKind::new("bomb", "Bomb")
+///         .with_glyph('B')
+///         .with_color(EntityColor::Magenta)
+///         .with_constructor(|x, y| Box::new(Bomb::new(x, y))),
+/// );
+/// let kind = new_game.entity_kind(&Bomb::new(1, 1)).unwrap();
+/// assert_eq!((kind.glyph, kind.color, kind.sprite), ('B', EntityColor::Magenta, "bomb"));
+/// ```
+#[derive(Clone)]
+pub struct EntityKind {
+    /// Identifies the type on saves, it should never change once entities of the type were saved.
+    pub id: &'static str,
+    /// The [name](`Entity::name`) of its entities, also the one shown to players.
+    pub name: &'static str,
+    /// Character text renderers draw its entities with, `o` by default.
+    pub glyph: char,
+    /// Colour its entities are drawn with, white by default.
+    pub color: EntityColor,
+    /// Key renderers drawing images look its sprite up with, the id by default.
+    pub sprite: &'static str,
+    save: Option<SaveHook>,
+    load: Option<LoadHook>,
+}
+
+impl EntityKind {
+    pub fn new(id: &'static str, name: &'static str) -> Self {
+        EntityKind {
+            id,
+            name,
+            glyph: 'o',
+            color: EntityColor::White,
+            sprite: id,
+            save: None,
+            load: None,
+        }
+    }
+    pub fn with_glyph(mut self, glyph: char) -> Self {
+        self.glyph = glyph;
+        self
+    }
+    pub fn with_color(mut self, color: EntityColor) -> Self {
+        self.color = color;
+        self
+    }
+    pub fn with_sprite(mut self, sprite: &'static str) -> Self {
+        self.sprite = sprite;
+        self
+    }
+    /// Saves and loads entities that are nothing but their position, like the ones made with
+    /// [`impl_entity!`](`crate::impl_entity`).
+    pub fn with_constructor(self, new: fn(i16, i16) -> Box<dyn Entity>) -> Self {
+        self.with_serde(
+            |_| Some(String::new()),
+            move |x, y, data| data.is_empty().then(|| new(x, y)),
+        )
+    }
+    /// Saves the data of an entity besides its position with `save` and builds it again from
+    /// its position and that data with `load`. Either one returning `None` fails the save or load.
+    pub fn with_serde<S, L>(mut self, save: S, load: L) -> Self
+    where
+        S: Fn(&dyn Entity) -> Option<String> + 'static,
+        L: Fn(i16, i16, &str) -> Option<Box<dyn Entity>> + 'static,
+    {
+        self.save = Some(Rc::new(save));
+        self.load = Some(Rc::new(load));
+        self
+    }
+    /// Whether the entities of this type can be saved, see [`EntityKind::with_serde`].
+    pub fn can_save(&self) -> bool {
+        self.save.is_some() && self.load.is_some()
+    }
+}
+
+impl fmt::Debug for EntityKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EntityKind")
+            .field("id", &self.id)
+            .field("name", &self.name)
+            .field("glyph", &self.glyph)
+            .field("color", &self.color)
+            .field("sprite", &self.sprite)
+            .field("can_save", &self.can_save())
+            .finish()
+    }
+}
+
+/// The [`EntityKind`]s a game knows about, see [`SnakeGame::entity_registry`](`super::SnakeGame::entity_registry`). <br>
+/// Entities are saved one per line as `id x,y,z data`, the data being whatever the kind saves.
+/// # Examples
+/// ```
+/// use snake3::snake::{EntityRegistry, PowerUp, StatusEffect};
+/// let registry = EntityRegistry::builtin();
+/// let line = registry.save(&PowerUp::new(2, 3, StatusEffect::Magnet, 20)).unwrap();
+/// assert_eq!(line, "power_up 2,3,0 Magnet:20");
+/// let loaded = registry.load(&line).unwrap();
+/// assert_eq!(loaded.effect(), Some((StatusEffect::Magnet, 20)));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct EntityRegistry {
+    kinds: Vec<EntityKind>,
+}
+
+impl EntityRegistry {
+    /// A registry without any kind.
+    pub fn new() -> Self {
+        EntityRegistry::default()
+    }
+    /// A registry with the kinds of the entities of this crate, [`Apple`] and [`PowerUp`].
+    pub fn builtin() -> Self {
+        let mut registry = EntityRegistry::new();
+        registry.register(
+            EntityKind::new("apple", "Apple")
+                .with_color(EntityColor::Red)
+                .with_constructor(|x, y| Box::new(Apple::new(x, y))),
+        );
+        registry.register(
+            EntityKind::new("power_up", "PowerUp")
+                .with_glyph('*')
+                .with_color(EntityColor::Yellow)
+                .with_serde(
+                    |entity| {
+                        let power_up = entity.downcast_ref::<PowerUp>()?;
+                        Some(format!("{}:{}", power_up.effect.name(), power_up.duration))
+                    },
+                    |x, y, data| {
+                        let (effect, duration) = data.rsplit_once(':')?;
+                        let effect = StatusEffect::ALL
+                            .into_iter()
+                            .find(|known| known.name() == effect)?;
+                        let power_up = PowerUp::new(x, y, effect, duration.parse().ok()?);
+                        Some(Box::new(power_up))
+                    },
+                ),
+        );
+        registry
+    }
+    /// Adds `kind`, replacing the kind with the same id or name if there is one.
+    pub fn register(&mut self, kind: EntityKind) {
+        self.kinds
+            .retain(|known| known.id != kind.id && known.name != kind.name);
+        self.kinds.push(kind);
+    }
+    /// The kinds in the order they were registered.
+    pub fn kinds(&self) -> &[EntityKind] {
+        &self.kinds
+    }
+    /// The kind with the stable `id`.
+    pub fn get(&self, id: &str) -> Option<&EntityKind> {
+        self.kinds.iter().find(|kind| kind.id == id)
+    }
+    /// The kind of the entities called `name`, see [`Entity::name`].
+    pub fn get_by_name(&self, name: &str) -> Option<&EntityKind> {
+        self.kinds.iter().find(|kind| kind.name == name)
+    }
+    /// The kind of `entity`, found by its [name](`Entity::name`).
+    pub fn kind_of(&self, entity: &dyn Entity) -> Option<&EntityKind> {
+        self.get_by_name(entity.name())
+    }
+    /// Saves `entity` in a single line.
+    pub fn save(&self, entity: &dyn Entity) -> Result<String, RegistryError> {
+        let kind = self
+            .kind_of(entity)
+            .ok_or_else(|| RegistryError(format!("Unknown entity type '{}'.", entity.name())))?;
+        let data = kind
+            .save
+            .as_ref()
+            .and_then(|save| save(entity))
+            .ok_or_else(|| {
+                RegistryError(format!("Entities of type '{}' can't be saved.", kind.id))
+            })?;
+        let line = format!("{} {},{},{}", kind.id, entity.x(), entity.y(), entity.z());
+        Ok(if data.is_empty() {
+            line
+        } else {
+            format!("{line} {data}")
+        })
+    }
+    /// Loads an entity saved by [`EntityRegistry::save`].
+    pub fn load(&self, line: &str) -> Result<Box<dyn Entity>, RegistryError> {
+        let invalid = || RegistryError(format!("Invalid entity '{line}'."));
+        let mut parts = line.trim().splitn(3, ' ');
+        let id = parts.next().ok_or_else(invalid)?;
+        let position = parts.next().ok_or_else(invalid)?;
+        let data = parts.next().unwrap_or("");
+        let kind = self
+            .get(id)
+            .ok_or_else(|| RegistryError(format!("Unknown entity type '{id}'.")))?;
+        let mut coordinates = position.split(',').map(|value| value.parse::<i16>());
+        let (Some(Ok(x)), Some(Ok(y)), Some(Ok(z)), None) = (
+            coordinates.next(),
+            coordinates.next(),
+            coordinates.next(),
+            coordinates.next(),
+        ) else {
+            return Err(invalid());
+        };
+        let mut entity = kind
+            .load
+            .as_ref()
+            .and_then(|load| load(x, y, data))
+            .ok_or_else(invalid)?;
+        entity.set_z(z);
+        Ok(entity)
+    }
+    /// Saves `entities` one per line.
+    pub fn save_all(&self, entities: &[Box<dyn Entity>]) -> Result<String, RegistryError> {
+        entities
+            .iter()
+            .map(|entity| self.save(entity.as_ref()))
+            .collect::<Result<Vec<_>, _>>()
+            .map(|lines| lines.join("\n"))
+    }
+    /// Loads the entities saved by [`EntityRegistry::save_all`], blank lines are skipped.
+    pub fn load_all(&self, text: &str) -> Result<Vec<Box<dyn Entity>>, RegistryError> {
+        text.lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| self.load(line))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::impl_entity;
+
+    struct Bomb {
+        x: i16,
+        y: i16,
+    }
+    impl_entity!(Bomb);
+
+    #[test]
+    fn builtin_kinds_round_trip() {
+        let registry = EntityRegistry::builtin();
+        let mut apple = Apple::new(4, 5);
+        apple.z = 1;
+        let entities: Vec<Box<dyn Entity>> = vec![
+            Box::new(apple),
+            Box::new(PowerUp::new(0, 9, StatusEffect::InvincibleWalls, 7)),
+        ];
+        let text = registry.save_all(&entities).unwrap();
+        assert_eq!(text, "apple 4,5,1\npower_up 0,9,0 Invincible walls:7");
+        let loaded = registry.load_all(&format!("\n{text}\n")).unwrap();
+        assert_eq!(loaded.len(), 2);
+        assert_eq!(loaded[0].name(), "Apple");
+        assert_eq!((loaded[0].x(), loaded[0].y(), loaded[0].z()), (4, 5, 1));
+        assert_eq!(loaded[1].effect(), Some((StatusEffect::InvincibleWalls, 7)));
+    }
+
+    #[test]
+    fn registering_replaces_and_looks_up() {
+        let mut registry = EntityRegistry::builtin();
+        assert!(registry.kind_of(&Bomb::new(0, 0)).is_none());
+        registry.register(EntityKind::new("bomb", "Bomb").with_glyph('B'));
+        registry.register(EntityKind::new("red_apple", "Apple").with_glyph('@'));
+        assert_eq!(registry.kinds().len(), 3);
+        assert!(registry.get("apple").is_none());
+        assert_eq!(registry.kind_of(&Apple::new(0, 0)).unwrap().glyph, '@');
+        let bomb = registry.kind_of(&Bomb::new(0, 0)).unwrap();
+        assert_eq!((bomb.id, bomb.sprite, bomb.glyph), ("bomb", "bomb", 'B'));
+        assert!(!bomb.can_save());
+        assert_eq!(
+            registry.save(&Bomb::new(1, 1)),
+            Err(RegistryError(
+                "Entities of type 'bomb' can't be saved.".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn loading_rejects_bad_lines() {
+        let registry = EntityRegistry::builtin();
+        for line in [
+            "",
+            "apple",
+            "apple 1,2",
+            "apple 1,2,3,4",
+            "apple x,2,0",
+            "apple 1,2,0 extra",
+            "power_up 1,2,0 Nothing:3",
+            "power_up 1,2,0 Magnet",
+        ] {
+            assert!(registry.load(line).is_err(), "{line}");
+        }
+        assert_eq!(
+            registry.load("bomb 1,1,0").err(),
+            Some(RegistryError("Unknown entity type 'bomb'.".to_string()))
+        );
+    }
+}
@@ -0,0 +1,249 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use super::{SnakeDirection, SnakeGame};
+
+/// Inputs of a run given with [`SnakeGame::steer`], enough to play it again on a game with the same
+/// [seed](`SnakeGame::set_seed`) and rules, see [`SnakeGame::start_recording`].
+/// # Examples
+/// ```
+/// # use snake3::{SnakeGame, GameState};
+/// use snake3::snake::SnakeDirection;
+/// let mut game = SnakeGame::new(10, 10, None, None);
+/// game.set_seed(Some(3));
+/// game.start_recording("10x10 classic");
+/// game.set_state(GameState::Playing);
+/// game.tick();
+/// game.steer(SnakeDirection::Up);
+/// game.tick();
+/// let recording = game.take_recording().unwrap();
+/// assert_eq!(recording.inputs, vec![(1, SnakeDirection::Up)]);
+/// assert_eq!(recording.ticks, 2);
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct Recording {
+    /// Seed of the game, runs of games without one can't be played again the same way.
+    pub seed: Option<u64>,
+    /// Free text describing the rules of the game, runs only make sense against games with the same one.
+    pub config: String,
+    /// Every direction given and the [tick](`SnakeGame::ticks`) it was given on.
+    pub inputs: Vec<(u64, SnakeDirection)>,
+    pub score: u64,
+    /// Ticks the run lasted.
+    pub ticks: u64,
+}
+
+impl Recording {
+    pub fn new(seed: Option<u64>, config: &str) -> Self {
+        Recording {
+            seed,
+            config: config.replace('\n', " "),
+            ..Recording::default()
+        }
+    }
+    pub fn record(&mut self, tick: u64, direction: SnakeDirection) {
+        self.inputs.push((tick, direction));
+    }
+    /// A short fingerprint of the whole run, the same on every machine, to compare runs without sharing them.
+    /// # Examples
+    /// ```
+    /// use snake3::snake::{Recording, SnakeDirection};
+    /// let mut recording = Recording::new(Some(7), "daily");
+    /// let empty = recording.hash();
+    /// recording.record(3, SnakeDirection::Up);
+    /// assert_ne!(recording.hash(), empty);
+    /// ```
+    pub fn hash(&self) -> u64 {
+        // FNV-1a, unlike the hasher of the standard library it is stable between releases
+        self.serialize()
+            .bytes()
+            .fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+                (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+            })
+    }
+    /// Reads a recording written with [`Recording::save`].
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let text = fs::read_to_string(path)?;
+        Self::parse(&text)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "Invalid recording file."))
+    }
+    /// Writes the recording to `path`, creating the parent directories.
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, self.serialize())
+    }
+    fn serialize(&self) -> String {
+        let seed = self.seed.map(|seed| seed.to_string()).unwrap_or_default();
+        let inputs: Vec<String> = self
+            .inputs
+            .iter()
+            .map(|(tick, direction)| format!("{tick}:{direction}"))
+            .collect();
+        format!(
+            "seed={seed}\nconfig={}\nscore={}\nticks={}\ninputs={}\n",
+            self.config,
+            self.score,
+            self.ticks,
+            inputs.join(",")
+        )
+    }
+    fn parse(text: &str) -> Option<Self> {
+        let mut recording = Self::default();
+        for line in text.lines().filter(|line| !line.trim().is_empty()) {
+            let (key, value) = line.split_once('=')?;
+            let value = value.trim();
+            match key.trim() {
+                "seed" if value.is_empty() => {}
+                "seed" => recording.seed = Some(value.parse().ok()?),
+                "config" => recording.config = value.to_string(),
+                "score" => recording.score = value.parse().ok()?,
+                "ticks" => recording.ticks = value.parse().ok()?,
+                "inputs" if value.is_empty() => {}
+                "inputs" => {
+                    recording.inputs = value
+                        .split(',')
+                        .map(|input| {
+                            let (tick, direction) = input.split_once(':')?;
+                            Some((tick.trim().parse().ok()?, direction.trim().parse().ok()?))
+                        })
+                        .collect::<Option<_>>()?
+                }
+                _ => return None,
+            }
+        }
+        Some(recording)
+    }
+}
+
+/// A [`Recording`] played again on its own game, to race against it. <br>
+/// The ghost never touches the game of the player, it only moves when told to.
+/// # Examples
+/// ```
+/// # use snake3::{SnakeGame, GameState};
+/// use snake3::snake::{Ghost, SnakeDirection};
+/// let mut game = SnakeGame::new(10, 10, None, None);
+/// game.set_seed(Some(3));
+/// game.start_recording("");
+/// game.set_state(GameState::Playing);
+/// game.steer(SnakeDirection::Up);
+/// game.tick();
+/// let recording = game.take_recording().unwrap();
+///
+/// let mut ghost = Ghost::new(SnakeGame::new(10, 10, None, None), recording);
+/// ghost.advance(|ghost_game| {
+///     ghost_game.tick();
+/// });
+/// assert_eq!(ghost.game().snake.body, game.snake.body);
+/// ```
+pub struct Ghost {
+    game: SnakeGame,
+    recording: Recording,
+    next_input: usize,
+}
+
+impl Ghost {
+    /// `game` has to be set up like the recorded one, it gets the seed of the `recording` and starts playing
+    /// right away, without countdown. Entities the game needs before the first tick can be added with [`Ghost::game_mut`].
+    pub fn new(mut game: SnakeGame, recording: Recording) -> Self {
+        game.set_seed(recording.seed);
+        game.reset();
+        game.set_countdown(0);
+        game.set_state(super::GameState::Playing);
+        Ghost {
+            game,
+            recording,
+            next_input: 0,
+        }
+    }
+    pub fn game(&self) -> &SnakeGame {
+        &self.game
+    }
+    pub fn game_mut(&mut self) -> &mut SnakeGame {
+        &mut self.game
+    }
+    pub fn recording(&self) -> &Recording {
+        &self.recording
+    }
+    /// Gives the ghost snake the directions recorded up to its current tick and calls `step`, which should
+    /// [tick](`SnakeGame::tick`) the game the same way the recorded one was. Does nothing once the ghost game is over.
+    pub fn advance<F: FnOnce(&mut SnakeGame)>(&mut self, step: F) {
+        if self.game.get_state().is_finished() {
+            return;
+        }
+        let tick = self.game.ticks();
+        while let Some((at, direction)) = self.recording.inputs.get(self.next_input)
+            && *at <= tick
+        {
+            self.game.snake.set_direction(*direction);
+            self.next_input += 1;
+        }
+        step(&mut self.game);
+    }
+    /// Score of the player minus the score of the ghost, positive when the player is ahead.
+    pub fn lead(&self, game: &SnakeGame) -> i64 {
+        game.score() as i64 - self.game.score() as i64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::snake::Apple;
+    use crate::{GameState, named};
+
+    fn step(game: &mut SnakeGame) {
+        if let Some(hit) = game.tick()
+            && hit.downcast_ref::<Apple>().is_some()
+        {
+            game.snake.grow();
+        }
+        if game.entities.is_empty() {
+            game.generate_entity(named!(Apple));
+        }
+    }
+
+    #[test]
+    fn recording_round_trip() {
+        let mut recording = Recording::new(Some(9), "20x10\nnormal");
+        recording.record(0, SnakeDirection::Up);
+        recording.record(12, SnakeDirection::DownLeft);
+        recording.score = 4;
+        recording.ticks = 30;
+        assert_eq!(recording.config, "20x10 normal");
+        assert_eq!(Recording::parse(&recording.serialize()), Some(recording));
+        assert_eq!(Recording::parse("inputs=1:sideways"), None);
+    }
+
+    #[test]
+    fn ghost_replays_the_run() {
+        let new_game = || SnakeGame::new(12, 12, None, Some((2, 2)));
+        let mut game = new_game();
+        game.set_seed(Some(21));
+        game.start_recording("12x12");
+        game.generate_entity(named!(Apple));
+        game.set_state(GameState::Playing);
+        let turns = [(3, SnakeDirection::Up), (6, SnakeDirection::Right)];
+        let mut bodies = Vec::new();
+        for tick in 0..9 {
+            if let Some((_, direction)) = turns.iter().find(|(at, _)| *at == tick) {
+                game.steer(*direction);
+            }
+            step(&mut game);
+            bodies.push(game.snake.body.clone());
+        }
+        let recording = game.take_recording().unwrap();
+        assert_eq!(recording.inputs.len(), 2);
+
+        let mut ghost = Ghost::new(new_game(), recording);
+        ghost.game_mut().generate_entity(named!(Apple));
+        for body in bodies {
+            ghost.advance(step);
+            assert_eq!(ghost.game().snake.body, body);
+        }
+        assert_eq!(ghost.game().entities[0].x(), game.entities[0].x());
+        assert_eq!(ghost.lead(&game), 0);
+    }
+}
@@ -0,0 +1,260 @@
+/// Rules used by [`Scoring`] to turn what happens in the game into points.
+/// # Examples
+/// ```
+/// use snake3::snake::ScoringRules;
+/// let rules = ScoringRules {
+///     points: vec![("Apple", 10), ("GoldenApple", 50)],
+///     combo_window: 8,
+///     max_combo: 4,
+///     ..ScoringRules::default()
+/// };
+/// assert_eq!(rules.points_for("GoldenApple"), 50);
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ScoringRules {
+    /// Base points for eating each [entity name](`super::Entity::name`).
+    pub points: Vec<(&'static str, u64)>,
+    /// Base points for entities not in [`ScoringRules::points`].
+    pub default_points: u64,
+    /// Eating again within this many ticks of the last meal raises the combo, `0` disables combos.
+    pub combo_window: u64,
+    /// Highest combo multiplier.
+    pub max_combo: u32,
+    /// Every meal gives one extra point for each of these many body points, `0` disables it.
+    pub length_bonus_every: usize,
+    /// At the end of the game one point is given for each of these many ticks played, `0` disables it.
+    pub time_bonus_every: u64,
+}
+
+impl Default for ScoringRules {
+    /// The classic rules, one point per apple and nothing else.
+    fn default() -> Self {
+        ScoringRules {
+            points: vec![("Apple", 1)],
+            default_points: 0,
+            combo_window: 0,
+            max_combo: 1,
+            length_bonus_every: 0,
+            time_bonus_every: 0,
+        }
+    }
+}
+
+impl ScoringRules {
+    /// Rules for competitive play, with combos, length and time bonuses.
+    pub fn competitive() -> Self {
+        ScoringRules {
+            points: vec![("Apple", 10)],
+            default_points: 0,
+            combo_window: 12,
+            max_combo: 5,
+            length_bonus_every: 5,
+            time_bonus_every: 50,
+        }
+    }
+    /// Base points for eating the entity called `entity`.
+    pub fn points_for(&self, entity: &str) -> u64 {
+        self.points
+            .iter()
+            .find(|(name, _)| *name == entity)
+            .map_or(self.default_points, |(_, points)| *points)
+    }
+}
+
+/// Where the points of a [`Scoring`] came from.
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Default)]
+pub struct ScoreBreakdown {
+    /// Points brought from a previous game, like an earlier campaign level.
+    pub carried: u64,
+    pub base: u64,
+    pub combo: u64,
+    pub length: u64,
+    pub time: u64,
+    /// Extra points from multipliers like [`StatusEffect::DoubleScore`](`super::StatusEffect::DoubleScore`).
+    pub multiplier: u64,
+    /// Points given with [`Scoring::add_bonus`].
+    pub bonus: u64,
+}
+
+impl ScoreBreakdown {
+    /// Sum of every source, it stops at [`u64::MAX`] instead of overflowing.
+    pub fn total(&self) -> u64 {
+        self.carried
+            .saturating_add(self.base)
+            .saturating_add(self.combo)
+            .saturating_add(self.length)
+            .saturating_add(self.time)
+            .saturating_add(self.multiplier)
+            .saturating_add(self.bonus)
+    }
+}
+
+/// Keeps the score of a [`SnakeGame`](`super::SnakeGame`) following some [`ScoringRules`].
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct Scoring {
+    rules: ScoringRules,
+    breakdown: ScoreBreakdown,
+    combo: u32,
+    last_meal_tick: Option<u64>,
+    finished: bool,
+}
+
+impl Scoring {
+    pub fn new(rules: ScoringRules) -> Self {
+        Scoring {
+            rules,
+            ..Scoring::default()
+        }
+    }
+    pub fn rules(&self) -> &ScoringRules {
+        &self.rules
+    }
+    pub fn set_rules(&mut self, rules: ScoringRules) {
+        self.rules = rules;
+    }
+    pub fn total(&self) -> u64 {
+        self.breakdown.total()
+    }
+    pub fn breakdown(&self) -> ScoreBreakdown {
+        self.breakdown
+    }
+    /// Current combo multiplier, `1` when there is no combo going.
+    pub fn combo(&self) -> u32 {
+        self.combo.max(1)
+    }
+    /// Scores eating `entity` at `tick` with a snake of `length`, everything gets multiplied by `multiplier`. <br>
+    /// Returns the points given, entities worth no base points don't affect the combo.
+    pub fn record_meal(&mut self, entity: &str, tick: u64, length: usize, multiplier: u64) -> u64 {
+        let base = self.rules.points_for(entity);
+        if base == 0 {
+            return 0;
+        }
+        let in_window = self
+            .last_meal_tick
+            .is_some_and(|last| tick.saturating_sub(last) <= self.rules.combo_window);
+        self.combo = if self.rules.combo_window > 0 && in_window {
+            self.combo
+                .saturating_add(1)
+                .min(self.rules.max_combo.max(1))
+        } else {
+            1
+        };
+        self.last_meal_tick = Some(tick);
+
+        let combo = base.saturating_mul(self.combo as u64 - 1);
+        let length = match self.rules.length_bonus_every {
+            0 => 0,
+            every => (length / every) as u64,
+        };
+        let subtotal = base.saturating_add(combo).saturating_add(length);
+        let extra = subtotal.saturating_mul(multiplier.saturating_sub(1));
+        let breakdown = &mut self.breakdown;
+        breakdown.base = breakdown.base.saturating_add(base);
+        breakdown.combo = breakdown.combo.saturating_add(combo);
+        breakdown.length = breakdown.length.saturating_add(length);
+        breakdown.multiplier = breakdown.multiplier.saturating_add(extra);
+        subtotal.saturating_add(extra)
+    }
+    /// Gives points outside of the rules.
+    pub fn add_bonus(&mut self, points: u64) {
+        self.breakdown.bonus = self.breakdown.bonus.saturating_add(points);
+    }
+    /// Starts the score from the points of a previous game.
+    pub fn carry(&mut self, points: u64) {
+        self.breakdown.carried = points;
+    }
+    /// Gives the time bonus for playing `ticks`, only the first call does anything. Returns the points given.
+    pub fn finish(&mut self, ticks: u64) -> u64 {
+        if self.finished {
+            return 0;
+        }
+        self.finished = true;
+        self.breakdown.time = match self.rules.time_bonus_every {
+            0 => 0,
+            every => ticks / every,
+        };
+        self.breakdown.time
+    }
+    /// Clears the score keeping the rules.
+    pub fn reset(&mut self) {
+        *self = Scoring::new(self.rules.clone());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scoring_default_rules() {
+        let mut scoring = Scoring::default();
+        assert_eq!(scoring.record_meal("Apple", 1, 1, 1), 1);
+        assert_eq!(scoring.record_meal("Apple", 2, 30, 1), 1);
+        assert_eq!(scoring.record_meal("Bomb", 3, 30, 1), 0);
+        assert_eq!(scoring.finish(1000), 0);
+        assert_eq!(scoring.total(), 2);
+        assert_eq!(scoring.combo(), 1);
+    }
+
+    #[test]
+    fn scoring_combo() {
+        let mut scoring = Scoring::new(ScoringRules {
+            combo_window: 5,
+            max_combo: 3,
+            ..ScoringRules::default()
+        });
+        assert_eq!(scoring.record_meal("Apple", 10, 1, 1), 1);
+        assert_eq!(scoring.record_meal("Apple", 15, 1, 1), 2);
+        assert_eq!(scoring.combo(), 2);
+        assert_eq!(scoring.record_meal("Apple", 16, 1, 1), 3);
+        assert_eq!(scoring.record_meal("Apple", 17, 1, 1), 3);
+        assert_eq!(scoring.record_meal("Apple", 40, 1, 1), 1);
+        assert_eq!(scoring.breakdown().base, 5);
+        assert_eq!(scoring.breakdown().combo, 5);
+    }
+
+    #[test]
+    fn scoring_bonuses_and_breakdown() {
+        let mut scoring = Scoring::new(ScoringRules::competitive());
+        scoring.carry(100);
+        assert_eq!(scoring.record_meal("Apple", 1, 12, 2), 24);
+        scoring.add_bonus(7);
+        assert_eq!(scoring.finish(260), 5);
+        assert_eq!(scoring.finish(1000), 0);
+        assert_eq!(
+            scoring.breakdown(),
+            ScoreBreakdown {
+                carried: 100,
+                base: 10,
+                combo: 0,
+                length: 2,
+                time: 5,
+                multiplier: 12,
+                bonus: 7,
+            }
+        );
+        assert_eq!(scoring.total(), 136);
+        scoring.reset();
+        assert_eq!(scoring.total(), 0);
+        assert_eq!(scoring.rules(), &ScoringRules::competitive());
+    }
+
+    #[test]
+    fn scoring_saturates_at_u64_max() {
+        let mut scoring = Scoring::new(ScoringRules {
+            points: vec![("Apple", u64::MAX)],
+            combo_window: 5,
+            max_combo: u32::MAX,
+            length_bonus_every: 1,
+            ..ScoringRules::default()
+        });
+        scoring.carry(u64::MAX);
+        assert_eq!(scoring.record_meal("Apple", 1, 10, 3), u64::MAX);
+        assert_eq!(scoring.record_meal("Apple", 2, 10, 3), u64::MAX);
+        scoring.add_bonus(u64::MAX);
+        scoring.add_bonus(1);
+        assert_eq!(scoring.breakdown().base, u64::MAX);
+        assert_eq!(scoring.breakdown().bonus, u64::MAX);
+        assert_eq!(scoring.total(), u64::MAX);
+    }
+}
@@ -1,114 +1,426 @@
+use std::{fmt, str::FromStr};
+
+use super::{Health, Portals, StatusEffect, StatusEffects, Topology};
+
 /// Every tick of the game we move to the current direction <br>
-/// the snake is pointing at, this is changed by player movement.
+/// the snake is pointing at, this is changed by player movement. <br>
+/// The diagonal directions are only used with [`Movement::EightWay`], and [`SnakeDirection::Ascend`]
+/// and [`SnakeDirection::Descend`] on [layered](`Snake::set_layered`) boards.
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub enum SnakeDirection {
     Up,
     Down,
     Left,
     Right,
+    UpLeft,
+    UpRight,
+    DownLeft,
+    DownRight,
+    /// To the layer above, increasing `z`.
+    Ascend,
+    /// To the layer below, decreasing `z`.
+    Descend,
 }
 
 impl SnakeDirection {
+    pub const ALL: [SnakeDirection; 10] = [
+        SnakeDirection::Up,
+        SnakeDirection::Down,
+        SnakeDirection::Left,
+        SnakeDirection::Right,
+        SnakeDirection::UpLeft,
+        SnakeDirection::UpRight,
+        SnakeDirection::DownLeft,
+        SnakeDirection::DownRight,
+        SnakeDirection::Ascend,
+        SnakeDirection::Descend,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            SnakeDirection::Up => "up",
+            SnakeDirection::Down => "down",
+            SnakeDirection::Left => "left",
+            SnakeDirection::Right => "right",
+            SnakeDirection::UpLeft => "up-left",
+            SnakeDirection::UpRight => "up-right",
+            SnakeDirection::DownLeft => "down-left",
+            SnakeDirection::DownRight => "down-right",
+            SnakeDirection::Ascend => "ascend",
+            SnakeDirection::Descend => "descend",
+        }
+    }
     pub fn is_opposite(&self, other: &SnakeDirection) -> bool {
-        matches!(
-            (self, other),
-            (SnakeDirection::Up, SnakeDirection::Down)
-                | (SnakeDirection::Down, SnakeDirection::Up)
-                | (SnakeDirection::Left, SnakeDirection::Right)
-                | (SnakeDirection::Right, SnakeDirection::Left)
-        )
+        self.opposite() == *other
+    }
+    pub fn opposite(&self) -> SnakeDirection {
+        match self {
+            SnakeDirection::Up => SnakeDirection::Down,
+            SnakeDirection::Down => SnakeDirection::Up,
+            SnakeDirection::Left => SnakeDirection::Right,
+            SnakeDirection::Right => SnakeDirection::Left,
+            SnakeDirection::UpLeft => SnakeDirection::DownRight,
+            SnakeDirection::UpRight => SnakeDirection::DownLeft,
+            SnakeDirection::DownLeft => SnakeDirection::UpRight,
+            SnakeDirection::DownRight => SnakeDirection::UpLeft,
+            SnakeDirection::Ascend => SnakeDirection::Descend,
+            SnakeDirection::Descend => SnakeDirection::Ascend,
+        }
+    }
+    /// Whether the direction goes to another layer.
+    pub fn is_vertical(&self) -> bool {
+        matches!(self, SnakeDirection::Ascend | SnakeDirection::Descend)
+    }
+    pub fn is_diagonal(&self) -> bool {
+        let (x, y) = self.offset();
+        x != 0 && y != 0
+    }
+    /// How much a single step in this direction moves on `x` and `y`, see [`SnakeDirection::layer_offset`] for `z`.
+    pub fn offset(&self) -> (i16, i16) {
+        match self {
+            SnakeDirection::Up => (0, 1),
+            SnakeDirection::Down => (0, -1),
+            SnakeDirection::Left => (-1, 0),
+            SnakeDirection::Right => (1, 0),
+            SnakeDirection::UpLeft => (-1, 1),
+            SnakeDirection::UpRight => (1, 1),
+            SnakeDirection::DownLeft => (-1, -1),
+            SnakeDirection::DownRight => (1, -1),
+            SnakeDirection::Ascend | SnakeDirection::Descend => (0, 0),
+        }
+    }
+    /// How much a single step in this direction moves on `z`.
+    pub fn layer_offset(&self) -> i16 {
+        match self {
+            SnakeDirection::Ascend => 1,
+            SnakeDirection::Descend => -1,
+            _ => 0,
+        }
+    }
+    /// Direction of a single step from `from` to `to`, `None` if they are not next to each other.
+    pub fn between(from: &SnakeBodyPoint, to: &SnakeBodyPoint) -> Option<SnakeDirection> {
+        if from.z != to.z {
+            return SnakeDirection::between_layers(from, to);
+        }
+        match (to.x - from.x, to.y - from.y) {
+            (0, 1) => Some(SnakeDirection::Up),
+            (0, -1) => Some(SnakeDirection::Down),
+            (-1, 0) => Some(SnakeDirection::Left),
+            (1, 0) => Some(SnakeDirection::Right),
+            (-1, 1) => Some(SnakeDirection::UpLeft),
+            (1, 1) => Some(SnakeDirection::UpRight),
+            (-1, -1) => Some(SnakeDirection::DownLeft),
+            (1, -1) => Some(SnakeDirection::DownRight),
+            _ => None,
+        }
+    }
+    /// [`SnakeDirection::Ascend`] or [`SnakeDirection::Descend`] if `to` is right above or below `from`.
+    pub fn between_layers(from: &SnakeBodyPoint, to: &SnakeBodyPoint) -> Option<SnakeDirection> {
+        if (from.x, from.y) != (to.x, to.y) {
+            return None;
+        }
+        match to.z - from.z {
+            1 => Some(SnakeDirection::Ascend),
+            -1 => Some(SnakeDirection::Descend),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for SnakeDirection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for SnakeDirection {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        SnakeDirection::ALL
+            .into_iter()
+            .find(|direction| direction.name().eq_ignore_ascii_case(s))
+            .ok_or_else(|| format!("Unknown direction '{s}'."))
     }
 }
 
+/// Which directions the snake can take.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum Movement {
+    /// Only up, down, left and right.
+    #[default]
+    Orthogonal,
+    /// The four diagonals are allowed too.
+    EightWay,
+}
+
 /// Point of the snake on the game grid.
 #[derive(Clone, Copy, PartialEq, Debug)]
 pub struct SnakeBodyPoint {
     pub x: i16,
     pub y: i16,
+    /// Layer of the board, always `0` unless the game has [more than one](`super::SnakeGame::set_layers`).
+    pub z: i16,
 }
 
 /// Player.
+#[derive(Clone)]
 pub struct Snake {
     direction: SnakeDirection,
     pub body: Vec<SnakeBodyPoint>,
+    pending_growth: i32,
+    effects: StatusEffects,
+    reverse_on_opposite: bool,
+    movement: Movement,
+    topology: Topology,
+    layered: bool,
+    health: Option<Health>,
 }
 
 impl Snake {
     pub fn new(starting_position: (i16, i16), initial_direction: SnakeDirection) -> Self {
         let (x, y) = starting_position;
-        let first_body_part = SnakeBodyPoint { x, y };
+        let first_body_part = SnakeBodyPoint { x, y, z: 0 };
         Snake {
             direction: initial_direction,
             body: vec![first_body_part],
+            pending_growth: 0,
+            effects: StatusEffects::default(),
+            reverse_on_opposite: false,
+            movement: Movement::Orthogonal,
+            topology: Topology::Square,
+            layered: false,
+            health: None,
+        }
+    }
+    /// Returns a new snake at `starting_position` with the same rules as this one, like its movement,
+    /// topology and magnet radius. <br>
+    /// Pending growth and status effects are gone and the [health](`Snake::health`) is back to full.
+    /// # Examples
+    /// ```
+    /// use snake3::snake::{Movement, Snake, SnakeBodyPoint, SnakeDirection};
+    /// let mut snake = Snake::new((5, 5), SnakeDirection::Right);
+    /// snake.set_movement(Movement::EightWay);
+    /// snake.grow_by(2);
+    /// let fresh = snake.respawn((1, 1), SnakeDirection::Up);
+    /// assert_eq!(fresh.body, vec![SnakeBodyPoint { x: 1, y: 1, z: 0 }]);
+    /// assert_eq!(fresh.movement(), Movement::EightWay);
+    /// assert_eq!(fresh.pending_growth(), 0);
+    /// ```
+    pub fn respawn(
+        &self,
+        starting_position: (i16, i16),
+        initial_direction: SnakeDirection,
+    ) -> Self {
+        let (x, y) = starting_position;
+        let mut snake = self.clone();
+        snake.direction = initial_direction;
+        snake.body = vec![SnakeBodyPoint { x, y, z: 0 }];
+        snake.pending_growth = 0;
+        snake.effects.clear();
+        if let Some(health) = &mut snake.health {
+            health.refill();
         }
+        snake
     }
     pub fn get_direction(&self) -> SnakeDirection {
         self.direction
     }
+    /// Points the snake to a new direction unless it is the opposite of the current one,
+    /// or one the [`Topology`] does not [allow](`Topology::allows`) and is not [vertical](`SnakeDirection::is_vertical`)
+    /// on a [layered](`Snake::set_layered`) snake. <br>
+    /// While [`StatusEffect::ReverseControls`] is active the new direction gets flipped first,
+    /// and if [`Snake::reverses_on_opposite`] the opposite direction [reverses](`Snake::reverse`) the snake.
     pub fn set_direction(&mut self, new_direction: SnakeDirection) {
+        let allowed = if new_direction.is_vertical() {
+            self.layered
+        } else {
+            self.topology.allows(new_direction, self.movement)
+        };
+        if !allowed {
+            return;
+        }
+        let new_direction = if self.effects.is_active(StatusEffect::ReverseControls) {
+            new_direction.opposite()
+        } else {
+            new_direction
+        };
         if !self.direction.is_opposite(&new_direction) {
             self.direction = new_direction;
+        } else if self.reverses_on_opposite() {
+            self.reverse();
         }
     }
-    /// Removes the last body point from [`Snake::body`] and adds a new <br>
-    /// one in the current snake direction.
+    /// Points the snake to `direction` skipping every check of [`Snake::set_direction`], for rules
+    /// where turning back is allowed and makes the snake bite its neck.
+    pub fn face(&mut self, direction: SnakeDirection) {
+        self.direction = direction;
+    }
+    /// Flips the snake so the tail becomes the head, it then moves the way the old tail was pointing. <br>
+    /// If the tail is the only body point, or its last step went through a portal or around the board,
+    /// the snake just turns back. Pending growth is kept and ends up on the new tail.
+    /// # Examples
+    /// ```
+    /// use snake3::snake::{Snake, SnakeBodyPoint, SnakeDirection};
+    /// let mut snake = Snake::new((5, 5), SnakeDirection::Right);
+    /// snake.grow_by(2);
+    /// snake.advance();
+    /// snake.set_direction(SnakeDirection::Up);
+    /// snake.advance();
+    /// snake.reverse();
+    /// assert_eq!(snake.body[0], SnakeBodyPoint { x: 5, y: 5, z: 0 });
+    /// assert_eq!(snake.get_direction(), SnakeDirection::Left);
+    /// ```
+    pub fn reverse(&mut self) {
+        self.body.reverse();
+        self.direction = match self.body.get(1) {
+            Some(neck) => self
+                .topology
+                .direction_between(neck, &self.body[0])
+                .unwrap_or_else(|| self.direction.opposite()),
+            None => self.direction.opposite(),
+        };
+    }
+    /// Whether turning to the opposite direction reverses the snake, either because
+    /// [`Snake::set_reverse_on_opposite`] was used or [`StatusEffect::Reversible`] is active.
+    pub fn reverses_on_opposite(&self) -> bool {
+        self.reverse_on_opposite || self.effects.is_active(StatusEffect::Reversible)
+    }
+    /// Whether the reverse rule was turned on with [`Snake::set_reverse_on_opposite`], ignoring effects.
+    pub fn reverse_on_opposite(&self) -> bool {
+        self.reverse_on_opposite
+    }
+    pub fn set_reverse_on_opposite(&mut self, reverse_on_opposite: bool) {
+        self.reverse_on_opposite = reverse_on_opposite;
+    }
+    /// Adds a new body point in the current snake direction and removes the last one from [`Snake::body`]. <br>
+    /// While there is pending growth the tail stays where it is, and while there is pending shrink an extra
+    /// tail point is removed, the snake never goes below one body point.
     pub fn advance(&mut self) {
+        self.advance_through(&Portals::default());
+    }
+    /// Same as [`Snake::advance`] but if the new head lands on a portal it comes out of the
+    /// linked one, keeping its direction. The body follows through the same path.
+    pub fn advance_through(&mut self, portals: &Portals) {
         let head: SnakeBodyPoint = self.body[0];
-        let new_head = match self.direction {
-            SnakeDirection::Up => SnakeBodyPoint {
-                x: head.x,
-                y: head.y + 1,
-            },
-            SnakeDirection::Down => SnakeBodyPoint {
+        let (x, y) = self.topology.step((head.x, head.y), self.direction);
+        let z = head.z + self.direction.layer_offset();
+        let (x, y) = portals.exit_for((x, y)).unwrap_or((x, y));
+        self.body.insert(0, SnakeBodyPoint { x, y, z });
+        if self.pending_growth > 0 {
+            self.pending_growth -= 1;
+            return;
+        }
+        self.body.pop();
+        if self.pending_growth < 0 {
+            if self.body.len() > 1 {
+                self.body.pop();
+            }
+            self.pending_growth += 1;
+        }
+    }
+    /// Whether the head just crossed the body diagonally, going between two body points
+    /// that touch each other by their corners. Hex cells always share a side so it never happens on [`Topology::Hex`].
+    /// # Examples
+    /// ```
+    /// use snake3::snake::{Movement, Snake, SnakeBodyPoint, SnakeDirection};
+    /// let mut snake = Snake::new((5, 5), SnakeDirection::UpRight);
+    /// snake.set_movement(Movement::EightWay);
+    /// snake.body.push(SnakeBodyPoint { x: 6, y: 5, z: 0 });
+    /// snake.body.push(SnakeBodyPoint { x: 5, y: 6, z: 0 });
+    /// snake.body.push(SnakeBodyPoint { x: 4, y: 6, z: 0 });
+    /// snake.advance();
+    /// assert!(snake.crosses_itself());
+    /// ```
+    pub fn crosses_itself(&self) -> bool {
+        self.body.len() > 1 && self.crosses(&self.body[1..])
+    }
+    /// Whether the head just crossed `body` diagonally, like [`Snake::crosses_itself`] but for any body,
+    /// such as the one of another snake.
+    pub fn crosses(&self, body: &[SnakeBodyPoint]) -> bool {
+        let (head, neck) = match self.body.as_slice() {
+            [head, neck, ..] => (head, neck),
+            _ => return false,
+        };
+        if self.topology != Topology::Square {
+            return false;
+        }
+        if !SnakeDirection::between(neck, head).is_some_and(|direction| direction.is_diagonal()) {
+            return false;
+        }
+        let corners = [
+            SnakeBodyPoint {
                 x: head.x,
-                y: head.y - 1,
+                y: neck.y,
+                z: head.z,
             },
-            SnakeDirection::Left => SnakeBodyPoint {
-                x: head.x - 1,
+            SnakeBodyPoint {
+                x: neck.x,
                 y: head.y,
+                z: head.z,
             },
-            SnakeDirection::Right => SnakeBodyPoint {
-                x: head.x + 1,
-                y: head.y,
-            },
-        };
-        self.body.insert(0, new_head);
-        self.body.pop();
+        ];
+        body.windows(2).any(|segment| {
+            (segment[0] == corners[0] && segment[1] == corners[1])
+                || (segment[0] == corners[1] && segment[1] == corners[0])
+        })
+    }
+    pub fn movement(&self) -> Movement {
+        self.movement
+    }
+    /// Changes which directions [`Snake::set_direction`] accepts, the current direction is kept.
+    pub fn set_movement(&mut self, movement: Movement) {
+        self.movement = movement;
+    }
+    /// Whether the snake can [ascend](`SnakeDirection::Ascend`) and [descend](`SnakeDirection::Descend`) between layers.
+    pub fn is_layered(&self) -> bool {
+        self.layered
+    }
+    pub fn set_layered(&mut self, layered: bool) {
+        self.layered = layered;
+    }
+    /// The [`Health`] of the snake, `None` unless hunger was turned on with [`Snake::set_health`].
+    pub fn health(&self) -> Option<&Health> {
+        self.health.as_ref()
     }
-    /// Adds a new body point to [`Snake::body`].
+    pub fn health_mut(&mut self) -> Option<&mut Health> {
+        self.health.as_mut()
+    }
+    /// Turns hunger on with some [`Health`] or off with `None`.
+    pub fn set_health(&mut self, health: Option<Health>) {
+        self.health = health;
+    }
+    pub fn topology(&self) -> Topology {
+        self.topology
+    }
+    /// Changes the [`Topology`] the snake moves on, the current direction is kept.
+    pub fn set_topology(&mut self, topology: Topology) {
+        self.topology = topology;
+    }
+    /// Grows the snake by one body point, see [`Snake::grow_by`].
     pub fn grow(&mut self) {
-        if self.body.len() < 2 {
-            let tail = self.body[0];
-            let new_tail = match self.direction {
-                SnakeDirection::Up => SnakeBodyPoint {
-                    x: tail.x,
-                    y: tail.y + 1,
-                },
-                SnakeDirection::Down => SnakeBodyPoint {
-                    x: tail.x,
-                    y: tail.y - 1,
-                },
-                SnakeDirection::Left => SnakeBodyPoint {
-                    x: tail.x + 1,
-                    y: tail.y,
-                },
-                SnakeDirection::Right => SnakeBodyPoint {
-                    x: tail.x - 1,
-                    y: tail.y,
-                },
-            };
-            self.body.push(new_tail);
-        } else {
-            let last = self.body[self.body.len() - 1];
-            let before_last = self.body[self.body.len() - 2];
-            let dx = last.x - before_last.x;
-            let dy = last.y - before_last.y;
-            let new_tail = SnakeBodyPoint {
-                x: last.x + dx,
-                y: last.y + dy,
-            };
-            self.body.push(new_tail);
-        }
+        self.grow_by(1);
+    }
+    /// Queues `amount` body points to be added to [`Snake::body`]. <br>
+    /// The growth happens on the next calls to [`Snake::advance`], where the tail does not move.
+    pub fn grow_by(&mut self, amount: u16) {
+        self.pending_growth = self.pending_growth.saturating_add(amount as i32);
+    }
+    /// Queues `amount` body points to be removed from [`Snake::body`]. <br>
+    /// The shrink happens on the next calls to [`Snake::advance`] and cancels out any pending growth.
+    pub fn shrink_by(&mut self, amount: u16) {
+        self.pending_growth = self.pending_growth.saturating_sub(amount as i32);
+    }
+    /// Returns how many body points are still waiting to be added (positive) or removed (negative).
+    pub fn pending_growth(&self) -> i32 {
+        self.pending_growth
+    }
+    /// Returns the [`StatusEffects`] currently on the snake.
+    pub fn effects(&self) -> &StatusEffects {
+        &self.effects
+    }
+    pub fn effects_mut(&mut self) -> &mut StatusEffects {
+        &mut self.effects
     }
 }
 
@@ -181,88 +493,305 @@ mod test {
     }
 
     #[test]
-    fn snake_grow_when_snake_has_one_segment() {
+    fn snake_grow_is_deferred_until_advance() {
         let mut snake = Snake::new((5, 5), SnakeDirection::Right);
+        snake.grow();
         assert_eq!(snake.body.len(), 1);
+        assert_eq!(snake.pending_growth(), 1);
 
-        snake.grow();
+        snake.advance();
         assert_eq!(snake.body.len(), 2);
-
-        let expected_tail = SnakeBodyPoint { x: 4, y: 5 };
-        assert_eq!(snake.body[1], expected_tail);
+        assert_eq!(snake.pending_growth(), 0);
+        assert_eq!(snake.body[0], SnakeBodyPoint { x: 6, y: 5, z: 0 });
+        assert_eq!(snake.body[1], SnakeBodyPoint { x: 5, y: 5, z: 0 });
     }
 
     #[test]
-    fn snake_grow_when_snake_has_multiple_segments() {
+    fn snake_grow_tail_stays_on_the_body_path() {
         let mut snake = Snake::new((5, 5), SnakeDirection::Right);
-        // Manually extend the body to simulate a moving snake
-        snake.body.push(SnakeBodyPoint { x: 4, y: 5 });
-        snake.body.push(SnakeBodyPoint { x: 3, y: 5 });
-        assert_eq!(snake.body.len(), 3);
+        snake.body.push(SnakeBodyPoint { x: 5, y: 6, z: 0 });
+        snake.body.push(SnakeBodyPoint { x: 4, y: 6, z: 0 });
 
         snake.grow();
+        snake.advance();
         assert_eq!(snake.body.len(), 4);
+        // Tail did not get extrapolated, it stayed where it was
+        assert_eq!(snake.body[3], SnakeBodyPoint { x: 4, y: 6, z: 0 });
+    }
 
-        let expected_tail = SnakeBodyPoint { x: 2, y: 5 };
-        assert_eq!(snake.body[3], expected_tail);
+    #[test]
+    fn snake_grow_by_multiple() {
+        let mut snake = Snake::new((10, 10), SnakeDirection::Left);
+        snake.grow_by(3);
+        snake.grow();
+        assert_eq!(snake.pending_growth(), 4);
+
+        for _ in 0..6 {
+            snake.advance();
+        }
+        assert_eq!(snake.body.len(), 5);
+        assert_eq!(snake.pending_growth(), 0);
+        let l = snake.body.len();
+        let dx = snake.body[l - 1].x - snake.body[l - 2].x;
+        let dy = snake.body[l - 1].y - snake.body[l - 2].y;
+        assert_eq!((dx, dy), (1, 0));
     }
 
     #[test]
-    fn snake_grow_tail_extension_correctness_for_vertical_snake() {
-        let mut snake = Snake::new((5, 5), SnakeDirection::Up);
-        snake.body.push(SnakeBodyPoint { x: 5, y: 6 });
-        snake.body.push(SnakeBodyPoint { x: 5, y: 7 });
+    fn snake_shrink_by() {
+        let mut snake = Snake::new((10, 10), SnakeDirection::Left);
+        snake.grow_by(4);
+        for _ in 0..4 {
+            snake.advance();
+        }
+        assert_eq!(snake.body.len(), 5);
 
-        snake.grow();
+        snake.shrink_by(2);
+        snake.advance();
         assert_eq!(snake.body.len(), 4);
+        snake.advance();
+        assert_eq!(snake.body.len(), 3);
+        assert_eq!(snake.pending_growth(), 0);
+    }
 
-        let expected_tail = SnakeBodyPoint { x: 5, y: 8 };
-        assert_eq!(snake.body[3], expected_tail);
+    #[test]
+    fn snake_shrink_never_removes_the_head() {
+        let mut snake = Snake::new((10, 10), SnakeDirection::Left);
+        snake.shrink_by(3);
+        for _ in 0..3 {
+            snake.advance();
+        }
+        assert_eq!(snake.body.len(), 1);
+        assert_eq!(snake.body[0], SnakeBodyPoint { x: 7, y: 10, z: 0 });
     }
 
     #[test]
-    fn snake_grow_multiple_times() {
+    fn snake_pending_growth_saturates() {
         let mut snake = Snake::new((10, 10), SnakeDirection::Left);
-        snake.grow();
-        snake.grow();
-        snake.grow();
+        for _ in 0..40_000 {
+            snake.grow_by(u16::MAX);
+        }
+        assert_eq!(snake.pending_growth(), i32::MAX);
+        for _ in 0..80_000 {
+            snake.shrink_by(u16::MAX);
+        }
+        assert_eq!(snake.pending_growth(), i32::MIN);
+    }
 
-        assert_eq!(snake.body.len(), 4);
-        // Check the last segment is consistent with direction
-        let l = snake.body.len();
-        let dx = snake.body[l - 1].x - snake.body[l - 2].x;
-        let dy = snake.body[l - 1].y - snake.body[l - 2].y;
+    #[test]
+    fn snake_grow_and_shrink_cancel_out() {
+        let mut snake = Snake::new((10, 10), SnakeDirection::Up);
+        snake.grow_by(2);
+        snake.shrink_by(2);
+        assert_eq!(snake.pending_growth(), 0);
+        snake.advance();
+        assert_eq!(snake.body.len(), 1);
+    }
 
-        assert_eq!((dx, dy), (1, 0));
+    #[test]
+    fn snake_advance_through_portal() {
+        let mut portals = Portals::default();
+        portals.add_pair((6, 5), (20, 2));
+        let mut snake = Snake::new((5, 5), SnakeDirection::Right);
+        snake.grow_by(2);
+        snake.advance_through(&portals);
+        assert_eq!(snake.body[0], SnakeBodyPoint { x: 20, y: 2, z: 0 });
+        assert_eq!(snake.get_direction(), SnakeDirection::Right);
+        snake.advance_through(&portals);
+        snake.advance_through(&portals);
+        assert_eq!(
+            snake.body,
+            vec![
+                SnakeBodyPoint { x: 22, y: 2, z: 0 },
+                SnakeBodyPoint { x: 21, y: 2, z: 0 },
+                SnakeBodyPoint { x: 20, y: 2, z: 0 },
+            ]
+        );
     }
 
     #[test]
-    fn snake_grow_with_one_segment_up() {
-        let mut snake = Snake::new((3, 3), SnakeDirection::Up);
-        snake.grow();
+    fn snake_set_direction_reverse_controls() {
+        let mut snake = Snake::new((10, 10), SnakeDirection::Right);
+        snake.effects_mut().apply(StatusEffect::ReverseControls, 5);
+        snake.set_direction(SnakeDirection::Up);
+        assert_eq!(snake.get_direction(), SnakeDirection::Down);
+        // Left is flipped to Right, not an opposite turn
+        snake.set_direction(SnakeDirection::Left);
+        assert_eq!(snake.get_direction(), SnakeDirection::Right);
+        snake.set_direction(SnakeDirection::Right);
+        assert_eq!(snake.get_direction(), SnakeDirection::Right);
+    }
 
-        assert_eq!(snake.body.len(), 2);
-        let expected_tail = SnakeBodyPoint { x: 3, y: 4 };
-        assert_eq!(snake.body[1], expected_tail);
+    #[test]
+    fn snake_reverse() {
+        let mut snake = Snake::new((5, 5), SnakeDirection::Right);
+        snake.grow_by(3);
+        snake.advance();
+        snake.advance();
+        snake.set_direction(SnakeDirection::Up);
+        snake.advance();
+        assert_eq!(snake.pending_growth(), 0);
+        snake.reverse();
+        assert_eq!(
+            snake.body,
+            vec![
+                SnakeBodyPoint { x: 5, y: 5, z: 0 },
+                SnakeBodyPoint { x: 6, y: 5, z: 0 },
+                SnakeBodyPoint { x: 7, y: 5, z: 0 },
+                SnakeBodyPoint { x: 7, y: 6, z: 0 },
+            ]
+        );
+        assert_eq!(snake.get_direction(), SnakeDirection::Left);
+        snake.advance();
+        assert_eq!(snake.body[0], SnakeBodyPoint { x: 4, y: 5, z: 0 });
+        assert_eq!(snake.body[3], SnakeBodyPoint { x: 7, y: 5, z: 0 });
     }
 
     #[test]
-    fn snake_grow_with_one_segment_down() {
-        let mut snake = Snake::new((3, 3), SnakeDirection::Down);
+    fn snake_reverse_single_point_and_portal() {
+        let mut snake = Snake::new((5, 5), SnakeDirection::Up);
+        snake.reverse();
+        assert_eq!(snake.get_direction(), SnakeDirection::Down);
+
+        let mut portals = Portals::default();
+        portals.add_pair((6, 5), (20, 2));
+        let mut snake = Snake::new((5, 5), SnakeDirection::Right);
         snake.grow();
+        snake.advance_through(&portals);
+        snake.reverse();
+        assert_eq!(snake.body[0], SnakeBodyPoint { x: 5, y: 5, z: 0 });
+        assert_eq!(snake.get_direction(), SnakeDirection::Left);
+    }
 
-        assert_eq!(snake.body.len(), 2);
-        let expected_tail = SnakeBodyPoint { x: 3, y: 2 };
-        assert_eq!(snake.body[1], expected_tail);
+    #[test]
+    fn snake_reverse_keeps_pending_growth() {
+        let mut snake = Snake::new((5, 5), SnakeDirection::Right);
+        snake.grow_by(2);
+        snake.advance();
+        snake.reverse();
+        assert_eq!(snake.pending_growth(), 1);
+        snake.advance();
+        assert_eq!(
+            snake.body,
+            vec![
+                SnakeBodyPoint { x: 4, y: 5, z: 0 },
+                SnakeBodyPoint { x: 5, y: 5, z: 0 },
+                SnakeBodyPoint { x: 6, y: 5, z: 0 },
+            ]
+        );
     }
 
     #[test]
-    fn snake_grow_with_one_segment_left() {
-        let mut snake = Snake::new((3, 3), SnakeDirection::Left);
+    fn snake_set_direction_reverse_on_opposite() {
+        let mut snake = Snake::new((5, 5), SnakeDirection::Right);
         snake.grow();
+        snake.advance();
+        snake.set_direction(SnakeDirection::Left);
+        assert_eq!(snake.body[0], SnakeBodyPoint { x: 6, y: 5, z: 0 });
 
-        assert_eq!(snake.body.len(), 2);
-        let expected_tail = SnakeBodyPoint { x: 4, y: 3 };
-        assert_eq!(snake.body[1], expected_tail);
+        snake.set_reverse_on_opposite(true);
+        snake.set_direction(SnakeDirection::Left);
+        assert_eq!(snake.body[0], SnakeBodyPoint { x: 5, y: 5, z: 0 });
+        assert_eq!(snake.get_direction(), SnakeDirection::Left);
+
+        snake.set_reverse_on_opposite(false);
+        snake.effects_mut().apply(StatusEffect::Reversible, 5);
+        snake.set_direction(SnakeDirection::Right);
+        assert_eq!(snake.body[0], SnakeBodyPoint { x: 6, y: 5, z: 0 });
+        assert_eq!(snake.get_direction(), SnakeDirection::Right);
+    }
+
+    #[test]
+    fn snake_direction_diagonals() {
+        assert!(SnakeDirection::UpLeft.is_opposite(&SnakeDirection::DownRight));
+        assert!(SnakeDirection::DownLeft.is_opposite(&SnakeDirection::UpRight));
+        assert!(!SnakeDirection::UpLeft.is_opposite(&SnakeDirection::Down));
+        assert!(SnakeDirection::DownRight.is_diagonal());
+        assert!(!SnakeDirection::Left.is_diagonal());
+    }
+
+    #[test]
+    fn snake_eight_way_movement() {
+        let mut snake = Snake::new((5, 5), SnakeDirection::Right);
+        snake.set_direction(SnakeDirection::UpRight);
+        assert_eq!(snake.get_direction(), SnakeDirection::Right);
+
+        snake.set_movement(Movement::EightWay);
+        snake.set_direction(SnakeDirection::UpRight);
+        snake.advance();
+        assert_eq!(snake.body[0], SnakeBodyPoint { x: 6, y: 6, z: 0 });
+        snake.set_direction(SnakeDirection::DownLeft);
+        assert_eq!(snake.get_direction(), SnakeDirection::UpRight);
+        snake.set_direction(SnakeDirection::DownRight);
+        snake.advance();
+        assert_eq!(snake.body[0], SnakeBodyPoint { x: 7, y: 5, z: 0 });
+    }
+
+    #[test]
+    fn snake_crosses_itself() {
+        let mut snake = Snake::new((5, 5), SnakeDirection::UpRight);
+        snake.set_movement(Movement::EightWay);
+        snake.grow_by(4);
+        snake.advance();
+        snake.set_direction(SnakeDirection::Down);
+        snake.advance();
+        assert!(!snake.crosses_itself());
+        // Back up between (5, 5) and (6, 6)
+        snake.set_direction(SnakeDirection::UpLeft);
+        snake.advance();
+        assert_eq!(snake.body[0], SnakeBodyPoint { x: 5, y: 6, z: 0 });
+        assert!(snake.crosses_itself());
+
+        // Going diagonally next to the body is fine
+        let mut snake = Snake::new((5, 5), SnakeDirection::Right);
+        snake.set_movement(Movement::EightWay);
+        snake.grow_by(4);
+        snake.advance();
+        snake.advance();
+        snake.set_direction(SnakeDirection::UpLeft);
+        snake.advance();
+        assert!(!snake.crosses_itself());
+    }
+
+    #[test]
+    fn snake_hex_movement() {
+        let mut snake = Snake::new((4, 2), SnakeDirection::Right);
+        snake.set_topology(Topology::Hex);
+        snake.grow_by(2);
+        snake.set_direction(SnakeDirection::Up);
+        assert_eq!(snake.get_direction(), SnakeDirection::Right);
+        snake.set_direction(SnakeDirection::UpRight);
+        snake.advance();
+        assert_eq!(snake.body[0], SnakeBodyPoint { x: 4, y: 3, z: 0 });
+        snake.set_direction(SnakeDirection::UpLeft);
+        snake.advance();
+        assert_eq!(snake.body[0], SnakeBodyPoint { x: 4, y: 4, z: 0 });
+        assert!(!snake.crosses_itself());
+        snake.reverse();
+        assert_eq!(snake.get_direction(), SnakeDirection::DownLeft);
+    }
+
+    #[test]
+    fn snake_layers() {
+        let mut snake = Snake::new((5, 5), SnakeDirection::Right);
+        snake.set_direction(SnakeDirection::Ascend);
+        assert_eq!(snake.get_direction(), SnakeDirection::Right);
+
+        snake.set_layered(true);
+        snake.grow_by(2);
+        snake.set_direction(SnakeDirection::Ascend);
+        snake.advance();
+        assert_eq!(snake.body[0], SnakeBodyPoint { x: 5, y: 5, z: 1 });
+        snake.set_direction(SnakeDirection::Descend);
+        assert_eq!(snake.get_direction(), SnakeDirection::Ascend);
+        snake.set_direction(SnakeDirection::Up);
+        snake.advance();
+        assert_eq!(snake.body[0], SnakeBodyPoint { x: 5, y: 6, z: 1 });
+        assert_eq!(
+            SnakeDirection::between(&snake.body[2], &snake.body[1]),
+            Some(SnakeDirection::Ascend)
+        );
+        snake.reverse();
+        assert_eq!(snake.get_direction(), SnakeDirection::Descend);
     }
 }
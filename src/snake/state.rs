@@ -0,0 +1,114 @@
+use std::fmt;
+
+/// Represents the state of the game.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum GameState {
+    /// A new game that has not started yet.
+    New,
+    /// The game is about to start, the snake does not move until the countdown ends.
+    Countdown,
+    /// The game is currently in progress and the ticker should be counting.
+    Playing,
+    /// The game is paused; the ticker should not be counting.
+    Paused,
+    /// The game has ended due to player failure or because it was aborted, <br>
+    /// [`SnakeGame::result`](`super::SnakeGame::result`) tells us which one.
+    GameOver,
+    /// The game has ended because the player met a [`WinCondition`](`super::WinCondition`).
+    Won,
+}
+
+impl GameState {
+    /// Returns `true` if the game is allowed to go from this state to `to`. <br>
+    /// Once the game is over the only way out is [`SnakeGame::reset`](`super::SnakeGame::reset`).
+    ///
+    /// | From        | To                                  |
+    /// |-------------|-------------------------------------|
+    /// | `New`       | `Countdown`, `Playing`, `GameOver`  |
+    /// | `Countdown` | `Playing`, `Paused`, `GameOver`     |
+    /// | `Playing`   | `Paused`, `GameOver`, `Won`         |
+    /// | `Paused`    | `Playing`, `Countdown`, `GameOver`  |
+    /// | `GameOver`  |                                     |
+    /// | `Won`       |                                     |
+    pub fn can_transition_to(&self, to: GameState) -> bool {
+        use GameState::*;
+        matches!(
+            (self, to),
+            (New, Countdown | Playing | GameOver)
+                | (Countdown, Playing | Paused | GameOver)
+                | (Playing, Paused | GameOver | Won)
+                | (Paused, Playing | Countdown | GameOver)
+        )
+    }
+    /// Returns `true` for [`GameState::GameOver`] and [`GameState::Won`].
+    pub fn is_finished(&self) -> bool {
+        matches!(self, GameState::GameOver | GameState::Won)
+    }
+}
+
+/// A change of [`GameState`] and the tick it happened at, counting every tick since the game was created or reset.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub struct StateTransition {
+    pub from: GameState,
+    pub to: GameState,
+    pub at_tick: u64,
+}
+
+/// Returned by [`SnakeGame::try_set_state`](`super::SnakeGame::try_set_state`) when the
+/// transition is not allowed by [`GameState::can_transition_to`].
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub struct InvalidTransition {
+    pub from: GameState,
+    pub to: GameState,
+}
+
+impl fmt::Display for InvalidTransition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Can't go from {:?} to {:?}.", self.from, self.to)
+    }
+}
+
+impl std::error::Error for InvalidTransition {}
+
+/// Callback registered with [`SnakeGame::on_transition`](`super::SnakeGame::on_transition`).
+pub type TransitionHook = Box<dyn FnMut(&StateTransition)>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn game_state_transitions() {
+        use GameState::*;
+        assert!(New.can_transition_to(Countdown));
+        assert!(New.can_transition_to(Playing));
+        assert!(!New.can_transition_to(Paused));
+        assert!(!New.can_transition_to(New));
+        assert!(Countdown.can_transition_to(Playing));
+        assert!(!Countdown.can_transition_to(Won));
+        assert!(Playing.can_transition_to(Won));
+        assert!(!Playing.can_transition_to(Playing));
+        assert!(Paused.can_transition_to(Countdown));
+        assert!(!Paused.can_transition_to(Won));
+        for to in [New, Countdown, Playing, Paused, GameOver, Won] {
+            assert!(!GameOver.can_transition_to(to));
+            assert!(!Won.can_transition_to(to));
+        }
+    }
+
+    #[test]
+    fn game_state_is_finished() {
+        assert!(GameState::GameOver.is_finished());
+        assert!(GameState::Won.is_finished());
+        assert!(!GameState::Paused.is_finished());
+    }
+
+    #[test]
+    fn invalid_transition_message() {
+        let error = InvalidTransition {
+            from: GameState::Won,
+            to: GameState::Playing,
+        };
+        assert_eq!(error.to_string(), "Can't go from Won to Playing.");
+    }
+}
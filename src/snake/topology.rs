@@ -0,0 +1,205 @@
+use super::{Movement, SnakeBodyPoint, SnakeDirection};
+
+/// Shape of the cells of the board, it decides where a step takes the snake,
+/// which cells are next to each other and how far apart two cells are.
+/// # Examples
+/// ```
+/// use snake3::snake::{SnakeDirection, Topology};
+/// let hex = Topology::Hex;
+/// assert_eq!(hex.step((4, 3), SnakeDirection::UpRight), (5, 4));
+/// assert_eq!(hex.neighbours((4, 3)).len(), 6);
+/// assert_eq!(hex.distance((0, 0), (3, 3)), 5);
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum Topology {
+    /// The classic grid of square cells.
+    #[default]
+    Square,
+    /// Hexagonal cells with six neighbours: left, right and the four diagonals. <br>
+    /// Cells keep their `(x, y)` position with odd rows shifted half a cell to the right, so the
+    /// board is still a rectangle of columns and rows, but every computation happens in axial coordinates.
+    Hex,
+}
+
+impl Topology {
+    /// Every direction the snake can take on this topology.
+    pub fn directions(&self, movement: Movement) -> &'static [SnakeDirection] {
+        match (self, movement) {
+            (Topology::Square, Movement::Orthogonal) => &[
+                SnakeDirection::Up,
+                SnakeDirection::Down,
+                SnakeDirection::Left,
+                SnakeDirection::Right,
+            ],
+            (Topology::Square, Movement::EightWay) => &[
+                SnakeDirection::Up,
+                SnakeDirection::Down,
+                SnakeDirection::Left,
+                SnakeDirection::Right,
+                SnakeDirection::UpLeft,
+                SnakeDirection::UpRight,
+                SnakeDirection::DownLeft,
+                SnakeDirection::DownRight,
+            ],
+            (Topology::Hex, _) => &[
+                SnakeDirection::Left,
+                SnakeDirection::Right,
+                SnakeDirection::UpLeft,
+                SnakeDirection::UpRight,
+                SnakeDirection::DownLeft,
+                SnakeDirection::DownRight,
+            ],
+        }
+    }
+    /// Whether the snake can take `direction` on this topology, [`Movement`] only matters on [`Topology::Square`].
+    pub fn allows(&self, direction: SnakeDirection, movement: Movement) -> bool {
+        self.directions(movement).contains(&direction)
+    }
+    /// Cell reached by taking a single step in `direction` from `position`, steps between layers stay on the same cell.
+    pub fn step(&self, position: (i16, i16), direction: SnakeDirection) -> (i16, i16) {
+        match self {
+            Topology::Square => {
+                let (x, y) = direction.offset();
+                (position.0 + x, position.1 + y)
+            }
+            Topology::Hex => {
+                let (q, r) = to_axial(position);
+                let (dq, dr) = match direction {
+                    SnakeDirection::Left => (-1, 0),
+                    SnakeDirection::Right => (1, 0),
+                    SnakeDirection::UpLeft => (-1, 1),
+                    SnakeDirection::UpRight => (0, 1),
+                    SnakeDirection::DownLeft => (0, -1),
+                    SnakeDirection::DownRight => (1, -1),
+                    // Not a hex direction, keep going straight up or down the axial axis
+                    SnakeDirection::Up => (0, 1),
+                    SnakeDirection::Down => (0, -1),
+                    SnakeDirection::Ascend | SnakeDirection::Descend => (0, 0),
+                };
+                from_axial((q + dq, r + dr))
+            }
+        }
+    }
+    /// Direction of a single step from `from` to `to`, `None` if they are not next to each other.
+    pub fn direction_between(
+        &self,
+        from: &SnakeBodyPoint,
+        to: &SnakeBodyPoint,
+    ) -> Option<SnakeDirection> {
+        if from.z != to.z {
+            return SnakeDirection::between_layers(from, to);
+        }
+        match self {
+            Topology::Square => SnakeDirection::between(from, to),
+            Topology::Hex => self
+                .directions(Movement::EightWay)
+                .iter()
+                .find(|direction| self.step((from.x, from.y), **direction) == (to.x, to.y))
+                .copied(),
+        }
+    }
+    /// Cells at a [distance](`Topology::distance`) of one from `position`.
+    pub fn neighbours(&self, position: (i16, i16)) -> Vec<(i16, i16)> {
+        self.directions(Movement::EightWay)
+            .iter()
+            .map(|direction| self.step(position, *direction))
+            .collect()
+    }
+    /// Smallest number of steps between two cells, diagonal steps count as one on [`Topology::Square`].
+    pub fn distance(&self, a: (i16, i16), b: (i16, i16)) -> i16 {
+        match self {
+            Topology::Square => (a.0 - b.0).abs().max((a.1 - b.1).abs()),
+            Topology::Hex => {
+                let (aq, ar) = to_axial(a);
+                let (bq, br) = to_axial(b);
+                let (dq, dr) = (aq - bq, ar - br);
+                (dq.abs() + dr.abs() + (dq + dr).abs()) / 2
+            }
+        }
+    }
+    /// The neighbour of `from` that is closest to `to`.
+    pub fn towards(&self, from: (i16, i16), to: (i16, i16)) -> (i16, i16) {
+        match self {
+            Topology::Square => (
+                from.0 + (to.0 - from.0).signum(),
+                from.1 + (to.1 - from.1).signum(),
+            ),
+            Topology::Hex => self
+                .neighbours(from)
+                .into_iter()
+                .min_by_key(|cell| self.distance(*cell, to))
+                .unwrap_or(from),
+        }
+    }
+}
+
+/// From odd rows shifted right offset coordinates to axial ones.
+fn to_axial((x, y): (i16, i16)) -> (i16, i16) {
+    (x - (y - (y & 1)) / 2, y)
+}
+
+fn from_axial((q, r): (i16, i16)) -> (i16, i16) {
+    (q + (r - (r & 1)) / 2, r)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn topology_square() {
+        let square = Topology::Square;
+        assert_eq!(square.step((5, 5), SnakeDirection::Up), (5, 6));
+        assert_eq!(square.step((5, 5), SnakeDirection::DownLeft), (4, 4));
+        assert_eq!(square.distance((0, 0), (3, -5)), 5);
+        assert_eq!(square.towards((0, 0), (3, -5)), (1, -1));
+        assert_eq!(square.neighbours((0, 0)).len(), 8);
+        assert!(!square.allows(SnakeDirection::UpLeft, Movement::Orthogonal));
+        assert!(square.allows(SnakeDirection::UpLeft, Movement::EightWay));
+    }
+
+    #[test]
+    fn topology_hex_steps() {
+        let hex = Topology::Hex;
+        // Even row
+        assert_eq!(hex.step((4, 2), SnakeDirection::UpLeft), (3, 3));
+        assert_eq!(hex.step((4, 2), SnakeDirection::UpRight), (4, 3));
+        assert_eq!(hex.step((4, 2), SnakeDirection::DownLeft), (3, 1));
+        assert_eq!(hex.step((4, 2), SnakeDirection::DownRight), (4, 1));
+        // Odd row
+        assert_eq!(hex.step((4, 3), SnakeDirection::UpLeft), (4, 4));
+        assert_eq!(hex.step((4, 3), SnakeDirection::UpRight), (5, 4));
+        assert_eq!(hex.step((4, 3), SnakeDirection::DownLeft), (4, 2));
+        assert_eq!(hex.step((4, 3), SnakeDirection::DownRight), (5, 2));
+        assert_eq!(hex.step((4, 3), SnakeDirection::Left), (3, 3));
+        for direction in hex.directions(Movement::Orthogonal) {
+            let next = hex.step((4, 3), *direction);
+            assert_eq!(hex.step(next, direction.opposite()), (4, 3));
+            assert_eq!(
+                hex.direction_between(
+                    &SnakeBodyPoint { x: 4, y: 3, z: 0 },
+                    &SnakeBodyPoint {
+                        x: next.0,
+                        y: next.1,
+                        z: 0
+                    }
+                ),
+                Some(*direction)
+            );
+        }
+        assert!(!hex.allows(SnakeDirection::Up, Movement::EightWay));
+    }
+
+    #[test]
+    fn topology_hex_distance() {
+        let hex = Topology::Hex;
+        for cell in hex.neighbours((4, 3)) {
+            assert_eq!(hex.distance((4, 3), cell), 1);
+        }
+        assert_eq!(hex.distance((0, 0), (0, 2)), 2);
+        assert_eq!(hex.distance((0, 0), (5, 0)), 5);
+        assert_eq!(hex.distance((2, 1), (2, 1)), 0);
+        let next = hex.towards((0, 0), (5, 4));
+        assert_eq!(hex.distance(next, (5, 4)), hex.distance((0, 0), (5, 4)) - 1);
+    }
+}
@@ -0,0 +1,115 @@
+use super::GameResult;
+
+/// The two players of a versus game, one plays [`SnakeGame::snake`](`super::SnakeGame::snake`)
+/// and two plays [`SnakeGame::rival`](`super::SnakeGame::rival`).
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum Player {
+    One,
+    Two,
+}
+
+impl Player {
+    /// The player that won a round that ended with `result`, `None` on a draw or an aborted round.
+    pub fn winner_of(result: GameResult) -> Option<Player> {
+        match result {
+            GameResult::Won | GameResult::RivalDied(_) => Some(Player::One),
+            GameResult::Died(_) => Some(Player::Two),
+            GameResult::Draw | GameResult::Aborted => None,
+        }
+    }
+}
+
+/// Rounds of a best of N versus match, the first player winning more than half of them wins the match.
+/// # Examples
+/// ```
+/// use snake3::snake::{DeathCause, GameResult, Player, VersusMatch};
+/// let mut versus = VersusMatch::new(3);
+/// versus.record(GameResult::RivalDied(DeathCause::Wall));
+/// versus.record(GameResult::Draw);
+/// versus.record(GameResult::RivalDied(DeathCause::Rival));
+/// assert_eq!(versus.winner(), Some(Player::One));
+/// assert_eq!(versus.rounds_played(), 3);
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct VersusMatch {
+    best_of: u32,
+    rounds: Vec<Option<Player>>,
+}
+
+impl VersusMatch {
+    /// # Panics
+    /// - If `best_of` is `0`.
+    pub fn new(best_of: u32) -> Self {
+        if best_of == 0 {
+            panic!("A match needs at least one round.");
+        }
+        VersusMatch {
+            best_of,
+            rounds: Vec::new(),
+        }
+    }
+    pub fn best_of(&self) -> u32 {
+        self.best_of
+    }
+    /// Adds a round that ended with `result` and returns its winner, rounds after the match is over are ignored.
+    pub fn record(&mut self, result: GameResult) -> Option<Player> {
+        if self.is_over() {
+            return None;
+        }
+        let winner = Player::winner_of(result);
+        self.rounds.push(winner);
+        winner
+    }
+    /// Winner of every round played, `None` for the draws.
+    pub fn rounds(&self) -> &[Option<Player>] {
+        &self.rounds
+    }
+    pub fn rounds_played(&self) -> usize {
+        self.rounds.len()
+    }
+    pub fn wins(&self, player: Player) -> u32 {
+        self.rounds
+            .iter()
+            .filter(|winner| **winner == Some(player))
+            .count() as u32
+    }
+    /// The player that won more than half of the rounds, if any.
+    pub fn winner(&self) -> Option<Player> {
+        [Player::One, Player::Two]
+            .into_iter()
+            .find(|player| self.wins(*player) > self.best_of / 2)
+    }
+    pub fn is_over(&self) -> bool {
+        self.winner().is_some()
+    }
+    /// Starts over with no rounds played.
+    pub fn reset(&mut self) {
+        self.rounds.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::snake::DeathCause;
+
+    #[test]
+    fn versus_match_best_of() {
+        let mut versus = VersusMatch::new(5);
+        assert_eq!(
+            versus.record(GameResult::Died(DeathCause::Wall)),
+            Some(Player::Two)
+        );
+        assert_eq!(versus.record(GameResult::Draw), None);
+        versus.record(GameResult::Died(DeathCause::Rival));
+        assert!(!versus.is_over());
+        versus.record(GameResult::RivalDied(DeathCause::SelfCollision));
+        versus.record(GameResult::Died(DeathCause::SelfCollision));
+        assert_eq!(versus.winner(), Some(Player::Two));
+        assert_eq!(versus.wins(Player::One), 1);
+        assert_eq!(versus.record(GameResult::Won), None);
+        assert_eq!(versus.rounds_played(), 5);
+        versus.reset();
+        assert_eq!(versus.winner(), None);
+    }
+}